@@ -36,7 +36,7 @@ class PressureReading(Model):
     # Pressure reading in psi
     pressure = DoubleField(null=True)
 
-    # Temperature of the manifold from the NTC resistor at the time of the recording in degrees Celsius
+    # Temperature of the manifold from the NTC resistor at the time of the recording in degrees Celsius, NaN when it isn't available
     temperature = DoubleField(null=True)
 
     class Meta:
@@ -0,0 +1,95 @@
+use core::str::FromStr;
+
+use defmt::{error, info};
+use strum::EnumCount;
+use uor_utils::csv::SerializeCSV;
+
+use crate::adc::config::ADC_CALIBRATIONS_FILE_NAME;
+use crate::adc::driver::types::{AnalogChannel, CalibrationType, CalibrationValues, Gain};
+use crate::adc::service::{AdcError, AdcService};
+use crate::adc::types::{AdcCalibration, AdcDevice};
+use crate::sd::types::{FileName, OperationScope, SdCardError};
+
+// Calibration logic has been separated into its own file for clarity
+impl<const ADC_COUNT: usize> AdcService<ADC_COUNT> {
+	/// Runs a calibration routine of the given ADC on the given input pair at the ADC's current gain.
+	/// For system offset calibration the inputs must be shorted, for gain calibration a full-scale signal must be applied.
+	pub async fn calibrate(
+		&self,
+		adc: AdcDevice,
+		calibration_type: CalibrationType,
+		positive: AnalogChannel,
+		negative: AnalogChannel,
+	) -> Result<AdcCalibration, AdcError> {
+		let mut driver = self.drivers[adc as usize].lock().await;
+		driver.set_channels(positive, negative).await?;
+		let values = driver.calibrate(calibration_type).await?;
+		let calibration = AdcCalibration::new(adc, driver.gain, values);
+		info!("Calibrated {:?} ({:?}): {:?}", adc, calibration_type, calibration);
+		Ok(calibration)
+	}
+
+	/// Loads the calibrations stored on the SD card into the drivers.
+	/// Must be called before apply_configurations() for the calibrations to be written to the ADCs.
+	pub async fn load_calibrations(&self) -> Result<(), SdCardError> {
+		// Collected first since the drivers can't be locked from within the read callback. Later lines take precedence
+		let mut calibrations: [[Option<CalibrationValues>; Gain::COUNT]; ADC_COUNT] = [[None; Gain::COUNT]; ADC_COUNT];
+		let result = self
+			.sd_card_service
+			.lock()
+			.await
+			.read(OperationScope::Root, FileName::from_str(ADC_CALIBRATIONS_FILE_NAME).unwrap(), |line| {
+				if *line == AdcCalibration::get_csv_header() {
+					return true; // Skip header line
+				}
+
+				match AdcCalibration::from_csv_line(line) {
+					Ok(calibration) => {
+						if let Some(adc_calibrations) = calibrations.get_mut(calibration.adc as usize) {
+							adc_calibrations[calibration.gain as usize] = Some(calibration.values());
+							info!("Loaded ADC calibration: {:?}", calibration);
+						}
+					}
+					Err(e) => {
+						error!("Error parsing ADC calibration for line '{}': {:?}", line.as_str(), e);
+					}
+				}
+				true // Continue reading
+			});
+
+		match result {
+			Ok(_) => (),
+			Err(SdCardError::NotFound) => {
+				// If calibrations not found, keep using the reset values of the ADC and ignore this error.
+				info!("ADC calibrations file not found, using defaults.");
+			}
+			Err(e) => return Err(e),
+		}
+
+		for (driver, adc_calibrations) in self.drivers.iter().zip(calibrations) {
+			let mut driver = driver.lock().await;
+			for (gain_index, values) in adc_calibrations.into_iter().enumerate() {
+				if let Some(values) = values {
+					driver.calibrations[gain_index] = values;
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Appends the calibration to the calibrations file so it's restored on the next boot
+	pub async fn save_calibration(
+		&self,
+		calibration: AdcCalibration,
+	) -> Result<(), SdCardError> {
+		info!("Saving ADC calibration: {:?}", calibration);
+		let mut sd_card_service = self.sd_card_service.lock().await;
+		let path = FileName::from_str(ADC_CALIBRATIONS_FILE_NAME).unwrap();
+		if !(sd_card_service.file_exists(OperationScope::Root, path.clone())?) {
+			sd_card_service.write(OperationScope::Root, path.clone(), AdcCalibration::get_csv_header())?;
+		}
+
+		sd_card_service.write(OperationScope::Root, path, calibration.to_csv_line())?;
+		Ok(())
+	}
+}
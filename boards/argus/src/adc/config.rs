@@ -0,0 +1,6 @@
+// File name used to read/write the ADC offset and full-scale calibrations to/from the SD card
+// Calibrations are stored in CSV format, later lines take precedence over earlier ones
+pub const ADC_CALIBRATIONS_FILE_NAME: &str = "adc_cal.csv"; // Cannot be longer than 12 characters
+
+// File name the register snapshot of each ADC is written to in the session directory, documenting the configuration the session ran with
+pub const ADC_REGISTERS_FILE_NAME: &str = "adc_regs.csv"; // Cannot be longer than 12 characters
@@ -0,0 +1,76 @@
+use defmt::{error, info, warn};
+use heapless::{format, String};
+use uor_peripherals::serial::peripheral::UORSerial;
+use uor_utils::utils::types::AsyncMutex;
+
+use crate::adc::driver::config::ADS1263_DEVICE_ID;
+use crate::adc::service::AdcService;
+use crate::adc::types::{AdcAvailability, AdcDevice};
+use crate::sensor::config::ADC_CHANNEL_KINDS;
+use crate::sensor::types::ChannelKind;
+
+// Presence detection logic has been separated into its own file for clarity
+impl<const ADC_COUNT: usize> AdcService<ADC_COUNT> {
+	/// Probes every ADC, records which ones are available and reports the detected hardware over serial.
+	/// ADCs that don't answer are marked missing so the rest of the board keeps working without them.
+	pub async fn detect_devices(
+		&self,
+		serial_service: &'static AsyncMutex<UORSerial>,
+	) {
+		for adc_index in 0..ADC_COUNT {
+			let adc = AdcDevice::from(adc_index);
+			let probe_result = self.drivers[adc_index].lock().await.probe().await;
+			let availability = match probe_result {
+				Ok(Some((device_id, revision_id))) => AdcAvailability::Available { device_id, revision_id },
+				Ok(None) => AdcAvailability::Missing,
+				Err(e) => {
+					error!("Failed to probe {:?}: {:?}", adc, e);
+					AdcAvailability::Missing
+				}
+			};
+			self.availability.lock().await[adc_index] = availability;
+
+			let report: String<64> = match availability {
+				AdcAvailability::Available { device_id, revision_id } => {
+					info!("Detected {:?} with device ID {} and revision {}", adc, device_id, revision_id);
+					let model = if device_id == ADS1263_DEVICE_ID { "ADS1263" } else { "ADS1262" };
+					format!("ADC {}: {} revision {}\n", adc_index, model, revision_id).unwrap()
+				}
+				_ => {
+					warn!("{:?} did not answer and will be skipped", adc);
+					format!("ADC {}: not detected\n", adc_index).unwrap()
+				}
+			};
+			match serial_service.lock().await.write_str(report.as_str()).await {
+				Err(e) => error!("Failed to report detected ADC over serial: {:?}", e),
+				_ => {}
+			}
+		}
+	}
+
+	/// Marks an ADC that answered the probe but can't be used, e.g. because it failed to be configured
+	pub async fn mark_faulted(
+		&self,
+		adc: AdcDevice,
+	) {
+		warn!("{:?} is faulted and will be skipped", adc);
+		self.availability.lock().await[adc as usize] = AdcAvailability::Faulted;
+	}
+
+	pub async fn is_available(
+		&self,
+		adc: AdcDevice,
+	) -> bool {
+		self.availability.lock().await[adc as usize].is_available()
+	}
+
+	/// Whether the ADC is available and its channels are wired to the given kind of sensor, see ADC_CHANNEL_KINDS.
+	/// Sensor services only use the ADCs this returns true for, so boards can mix kinds across their ADCs.
+	pub async fn is_available_for(
+		&self,
+		adc: AdcDevice,
+		kind: ChannelKind,
+	) -> bool {
+		ADC_CHANNEL_KINDS[adc as usize] == kind && self.is_available(adc).await
+	}
+}
@@ -1,3 +1,32 @@
 /// The 32 bit signed integer value read from the ADC ranges from this negative value to this positive value.
 /// This is used to convert the raw ADC code to a voltage.
 pub const MAX_SIGNED_CODE_SIZE: f64 = 2147483648.0; // 2^31
+
+/// Fixed margin added on top of the DRDY timeout to account for SPI and scheduling latency at high data rates.
+pub const DATA_READY_TIMEOUT_MARGIN_MS: u64 = 5;
+
+/// Self/system calibration takes this many conversion periods to complete before DRDY goes low.
+pub const CALIBRATION_DURATION_IN_CONVERSIONS: u32 = 16;
+
+/// Fraction of the full-scale range above which a reading taken with the sensor bias enabled is considered an open circuit.
+pub const OPEN_CIRCUIT_FULL_SCALE_THRESHOLD: f32 = 0.9;
+
+/// Internal temperature sensor output at 25 °C and its slope, from the ADS1262 datasheet.
+pub const TEMPERATURE_SENSOR_VOLTAGE_AT_25C: f32 = 0.1224; // volts
+pub const TEMPERATURE_SENSOR_COEFFICIENT: f32 = 0.000420; // volts per °C
+
+/// The supply monitors divide the supply voltage by this factor before it reaches the PGA.
+pub const SUPPLY_MONITOR_DIVIDER: f32 = 4.0;
+
+/// Time for the internal reference to settle after being powered up, when it was not already enabled.
+pub const INTERNAL_REFERENCE_SETTLING_TIME_MS: u64 = 50;
+
+/// Maximum number of input pairs per ADC that can have their own acquisition profile.
+pub const MAX_ACQUISITION_PROFILES: usize = 16;
+
+/// Values of the DEV_ID field of the ID register. The ADS1263 is accepted as well since it is a superset of the ADS1262.
+pub const ADS1262_DEVICE_ID: u8 = 0b000;
+pub const ADS1263_DEVICE_ID: u8 = 0b001;
+
+/// Value of the POWER register after a reset (RESET flag and internal reference enabled).
+pub const POWER_REGISTER_RESET_VALUE: u8 = 0x11;
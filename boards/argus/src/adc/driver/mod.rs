@@ -3,12 +3,23 @@
 pub mod config;
 pub mod types;
 
-use embassy_time::Timer;
+use embassy_time::{with_timeout, Duration, Timer};
 use embedded_hal::digital::{InputPin, OutputPin};
+use embedded_hal_async::digital::Wait;
 use embedded_hal_async::spi::SpiDevice;
-use types::{AnalogChannel, Command, DataRate, Filter, Gain, ReferenceRange, Register, Voltage};
-
-use crate::adc::driver::config::MAX_SIGNED_CODE_SIZE;
+use heapless::{LinearMap, Vec};
+use strum::EnumCount;
+use types::{
+	AcquisitionProfile, Ads1262Error, AnalogChannel, CalibrationType, CalibrationValues, Command, ConversionDelay, DataIntegrityCheck, DataRate,
+	Filter, Gain, IdacMagnitude, IdacOutput, ReferenceRange, Register, RegisterSnapshot, RunMode, SensorBiasMagnitude, SensorBiasPolarity, Status,
+	Voltage, IDAC_OUTPUT_NO_CONNECTION,
+};
+
+use crate::adc::driver::config::{
+	ADS1262_DEVICE_ID, ADS1263_DEVICE_ID, CALIBRATION_DURATION_IN_CONVERSIONS, DATA_READY_TIMEOUT_MARGIN_MS, INTERNAL_REFERENCE_SETTLING_TIME_MS,
+	MAX_ACQUISITION_PROFILES, MAX_SIGNED_CODE_SIZE, OPEN_CIRCUIT_FULL_SCALE_THRESHOLD, POWER_REGISTER_RESET_VALUE, SUPPLY_MONITOR_DIVIDER,
+	TEMPERATURE_SENSOR_COEFFICIENT, TEMPERATURE_SENSOR_VOLTAGE_AT_25C,
+};
 
 pub struct Ads1262<SPI, DataReady, Reset, Start> {
 	spi_device: SPI,
@@ -19,8 +30,21 @@ pub struct Ads1262<SPI, DataReady, Reset, Start> {
 	// Cache the last set channel pair to avoid redundant SPI writes
 	last_set_channel_pair: (AnalogChannel, AnalogChannel),
 
-	// Time to wait after setting channels before reading data. This is needed for the ADC to settle.
-	pub delay_after_setting_channel: u64,
+	// Last value written to each register, indexed by address. Used to verify the registers after applying the configuration
+	written_registers: [Option<u8>; Register::COUNT],
+
+	// Acquisition profiles of the input pairs that need a different gain, data rate, filter or reference than the driver's configuration
+	acquisition_profiles: LinearMap<(AnalogChannel, AnalogChannel), AcquisitionProfile, MAX_ACQUISITION_PROFILES>,
+
+	// Driver configuration saved while an acquisition profile is applied, restored when switching to a pair without a profile
+	default_profile: Option<AcquisitionProfile>,
+
+	// Conversions to discard after changing channels, on top of the ADC's own settling.
+	// Only needed when an external input filter takes longer to settle than the first conversion.
+	pub conversions_to_discard_after_setting_channel: u32,
+
+	// How many times the expected settling time to wait for DRDY before considering the ADC unresponsive
+	pub data_ready_timeout_factor: u32,
 
 	// Configurable parameters for the ADC. After changing call apply_configurations() to apply them to the ADC
 	pub enable_internal_reference: bool,
@@ -28,12 +52,35 @@ pub struct Ads1262<SPI, DataReady, Reset, Start> {
 	pub gain: Gain,
 	pub filter: Filter,
 	pub data_rate: DataRate,
+	pub idac1_output: Option<IdacOutput>, // None leaves the IDAC unconnected
+	pub idac1_magnitude: IdacMagnitude,
+	pub idac2_output: Option<IdacOutput>,
+	pub idac2_magnitude: IdacMagnitude,
+	pub sensor_bias_magnitude: SensorBiasMagnitude,
+	pub sensor_bias_polarity: SensorBiasPolarity,
+	pub enable_input_chop: bool,         // Swaps the inputs on alternate conversions to cancel the offset and its drift
+	pub enable_idac_rotation: bool,      // Swaps the IDAC outputs on alternate conversions to cancel current mismatch
+	pub enable_reference_reversal: bool, // Swaps REFP and REFN, inverting the polarity of the conversion result
+	pub conversion_delay: ConversionDelay,
+	pub run_mode: RunMode,
+	pub enable_status_byte: bool,
+	pub data_integrity_check: DataIntegrityCheck,
+
+	// Reads every written register back after apply_configurations() and fails on the first mismatch
+	pub verify_writes: bool,
+
+	// Status byte received with the last conversion data. Only available when the status byte is enabled
+	pub last_status: Option<Status>,
+
+	// Offset and full-scale calibration values for each gain setting, indexed by the gain.
+	// The values matching the current gain are written to the ADC by apply_configurations()
+	pub calibrations: [CalibrationValues; Gain::COUNT],
 }
 
 impl<SPI, E, DataReady, Reset, Start> Ads1262<SPI, DataReady, Reset, Start>
 where
 	SPI: SpiDevice<Error = E>,
-	DataReady: InputPin,
+	DataReady: InputPin + Wait,
 	Reset: OutputPin,
 	Start: OutputPin,
 {
@@ -48,8 +95,12 @@ where
 			data_ready,
 			reset,
 			start,
-			delay_after_setting_channel: 0,
+			conversions_to_discard_after_setting_channel: 0,
+			data_ready_timeout_factor: 4,
 			last_set_channel_pair: (AnalogChannel::AINCOM, AnalogChannel::AINCOM),
+			written_registers: [None; Register::COUNT],
+			acquisition_profiles: LinearMap::new(),
+			default_profile: None,
 
 			// Some default values. These will get configured later
 			reference_range: ReferenceRange::Avdd,
@@ -57,15 +108,31 @@ where
 			gain: Gain::G1,
 			filter: Filter::Sinc1,
 			data_rate: DataRate::Sps1200,
+			idac1_output: None,
+			idac1_magnitude: IdacMagnitude::Off,
+			idac2_output: None,
+			idac2_magnitude: IdacMagnitude::Off,
+			sensor_bias_magnitude: SensorBiasMagnitude::None,
+			sensor_bias_polarity: SensorBiasPolarity::PullUp,
+			enable_input_chop: false,
+			enable_idac_rotation: false,
+			enable_reference_reversal: false,
+			conversion_delay: ConversionDelay::None,
+			run_mode: RunMode::Continuous,
+			enable_status_byte: true,
+			data_integrity_check: DataIntegrityCheck::Crc,
+			verify_writes: false,
+			last_status: None,
+			calibrations: [CalibrationValues::default(); Gain::COUNT],
 		}
 	}
 
 	pub async fn read_single_ended(
 		&mut self,
 		channel: AnalogChannel,
-	) -> Result<Voltage, E> {
+	) -> Result<Voltage, Ads1262Error<E>> {
 		self.set_channels(channel, AnalogChannel::AINCOM).await?;
-		self.wait_for_next_data().await;
+		self.wait_for_next_data().await?;
 		let code = self.read_data_code().await?;
 		Ok(self.convert_code_to_volts(code))
 	}
@@ -74,27 +141,43 @@ where
 		&mut self,
 		positive: AnalogChannel,
 		negative: AnalogChannel,
-	) -> Result<Voltage, E> {
+	) -> Result<Voltage, Ads1262Error<E>> {
 		self.set_channels(positive, negative).await?;
-		self.wait_for_next_data().await;
+		self.wait_for_next_data().await?;
 		let code = self.read_data_code().await?;
 		let volts = self.convert_code_to_volts(code);
 		Ok(volts)
 	}
 
-	pub async fn reset_hardware(&mut self) -> Result<(), E> {
+	/// Reads the differential input as a fraction of the reference voltage (VIN / VREF).
+	/// With an external reference derived from the same excitation this gives a ratiometric reading.
+	pub async fn read_differential_ratio(
+		&mut self,
+		positive: AnalogChannel,
+		negative: AnalogChannel,
+	) -> Result<f32, Ads1262Error<E>> {
+		self.set_channels(positive, negative).await?;
+		self.wait_for_next_data().await?;
+		let code = self.read_data_code().await?;
+		Ok(self.convert_code_to_ratio(code))
+	}
+
+	pub async fn reset_hardware(&mut self) -> Result<(), Ads1262Error<E>> {
 		self.reset.set_low().ok();
 		Timer::after_millis(2).await;
 		self.reset.set_high().ok();
 		Timer::after_millis(5).await;
+
+		// The registers are back to their defaults, so nothing written before the reset is expected anymore
+		self.written_registers = [None; Register::COUNT];
 		Ok(())
 	}
 
 	pub async fn send_command(
 		&mut self,
 		command: Command,
-	) -> Result<(), E> {
-		self.spi_device.write(&[command as u8]).await?;
+	) -> Result<(), Ads1262Error<E>> {
+		self.spi_device.write(&[command as u8]).await.map_err(Ads1262Error::SpiError)?;
 		Ok(())
 	}
 
@@ -102,55 +185,335 @@ where
 		&mut self,
 		positive: AnalogChannel,
 		negative: AnalogChannel,
-	) -> Result<(), E> {
-		// Shift positive channel to the left by 4 bits and combine with negative channel using bitwise OR
-		// | dddd | dddd |
-		// | AINP | AINN |
-
-		if (positive, negative) == self.last_set_channel_pair {
+	) -> Result<(), Ads1262Error<E>> {
+		if !self.select_channels(positive, negative).await? {
 			// No need to set the same channel pair again
 			return Ok(());
 		}
 
+		// Writing INPMUX restarts the conversion, so the next result is settled on the new channels after settling_time()
+		for _ in 0..self.conversions_to_discard_after_setting_channel {
+			self.wait_for_next_data().await?;
+		}
+		Ok(())
+	}
+
+	// Applies the acquisition profile of the pair and writes INPMUX, unless the pair is already selected. Returns whether the pair changed
+	async fn select_channels(
+		&mut self,
+		positive: AnalogChannel,
+		negative: AnalogChannel,
+	) -> Result<bool, Ads1262Error<E>> {
+		if (positive, negative) == self.last_set_channel_pair {
+			return Ok(false);
+		}
+
+		// Shift positive channel to the left by 4 bits and combine with negative channel using bitwise OR
+		// | dddd | dddd |
+		// | AINP | AINN |
+		self.apply_acquisition_profile_for(positive, negative).await?;
 		self.write_register(Register::INPMUX, ((positive as u8) << 4) | (negative as u8)).await?;
 		self.last_set_channel_pair = (positive, negative);
-		if self.delay_after_setting_channel > 0 {
-			Timer::after_millis(self.delay_after_setting_channel).await;
+		Ok(true)
+	}
+
+	/// Stops conversions and selects the input pair, so the next conversion only starts once trigger_conversion() raises the START pin.
+	/// Arming several ADCs before triggering them lets their conversions start at the same instant.
+	/// Returns how many conversions to discard before the result is kept, see conversions_to_discard_after_setting_channel.
+	pub async fn arm_conversion(
+		&mut self,
+		positive: AnalogChannel,
+		negative: AnalogChannel,
+	) -> Result<u32, Ads1262Error<E>> {
+		self.start.set_low().ok();
+		self.send_command(Command::STOP1).await?;
+		match self.select_channels(positive, negative).await? {
+			true => Ok(self.conversions_to_discard_after_setting_channel),
+			false => Ok(0),
+		}
+	}
+
+	/// Starts the armed conversion. Starting a conversion resets the digital filter, so its result is settled after settling_time().
+	pub fn trigger_conversion(&mut self) {
+		self.start.set_high().ok();
+	}
+
+	/// Waits for the triggered conversion and reads it, then goes back to conversions started by command
+	pub async fn read_triggered_conversion(&mut self) -> Result<Voltage, Ads1262Error<E>> {
+		let timeout = self.data_ready_timeout();
+		let result = match self.wait_for_data_ready(timeout).await {
+			Ok(()) => self.read_data_code().await.map(|code| self.convert_code_to_volts(code)),
+			Err(e) => Err(e),
+		};
+
+		// The START1/STOP1 commands are only effective while the START pin is low
+		self.start.set_low().ok();
+		self.send_command(Command::START1).await?;
+		result
+	}
+
+	/// Registers the gain, data rate, filter and reference to use whenever the input pair is selected.
+	/// Pairs without a profile are converted with the configuration set on the driver.
+	pub fn set_acquisition_profile(
+		&mut self,
+		positive: AnalogChannel,
+		negative: AnalogChannel,
+		profile: AcquisitionProfile,
+	) -> Result<(), Ads1262Error<E>> {
+		match self.acquisition_profiles.insert((positive, negative), profile) {
+			Ok(_) => Ok(()),
+			Err(_) => Err(Ads1262Error::TooManyAcquisitionProfiles),
+		}
+	}
+
+	/// Gain, data rate, filter and reference currently set on the driver
+	pub fn current_profile(&self) -> AcquisitionProfile {
+		AcquisitionProfile {
+			gain: self.gain,
+			data_rate: self.data_rate,
+			filter: self.filter,
+			reference_range: self.reference_range,
+		}
+	}
+
+	/// Applies the acquisition profile to the ADC, only writing the registers whose settings differ from the current ones
+	pub async fn apply_acquisition_profile(
+		&mut self,
+		profile: AcquisitionProfile,
+	) -> Result<(), Ads1262Error<E>> {
+		let current = self.current_profile();
+		self.set_profile(profile);
+
+		if profile.reference_range != current.reference_range {
+			self.apply_reference_range_configuration().await?;
+		}
+		if profile.filter != current.filter {
+			self.apply_filter_and_sensor_bias_configuration().await?;
+		}
+		if profile.gain != current.gain || profile.data_rate != current.data_rate {
+			self.apply_gain_and_data_rate_configuration().await?;
+		}
+		if profile.gain != current.gain {
+			// Calibrations are stored per gain
+			self.apply_offset_calibration_configuration().await?;
+			self.apply_full_scale_calibration_configuration().await?;
 		}
 		Ok(())
 	}
 
-	pub async fn read_data_code(&mut self) -> Result<i32, E> {
-		// Send the RDATA1 command followed by 4 dummy bytes to read the 32-bit result 4 * 8 = 32 bits
-		let tx = [Command::RDATA1 as u8, 0, 0, 0, 0];
+	// Switches to the profile of the input pair, or back to the driver's configuration if the pair has no profile
+	async fn apply_acquisition_profile_for(
+		&mut self,
+		positive: AnalogChannel,
+		negative: AnalogChannel,
+	) -> Result<(), Ads1262Error<E>> {
+		let profile = match self.acquisition_profiles.get(&(positive, negative)).copied() {
+			Some(profile) => {
+				if self.default_profile.is_none() {
+					self.default_profile = Some(self.current_profile());
+				}
+				profile
+			}
+			None => match self.default_profile.take() {
+				Some(default_profile) => default_profile,
+				None => return Ok(()), // Already running on the driver's configuration
+			},
+		};
+		self.apply_acquisition_profile(profile).await
+	}
+
+	fn set_profile(
+		&mut self,
+		profile: AcquisitionProfile,
+	) {
+		self.gain = profile.gain;
+		self.data_rate = profile.data_rate;
+		self.filter = profile.filter;
+		self.reference_range = profile.reference_range;
+	}
 
-		// Receiving buffer is 5 bytes: first byte is a dummy byte for the command, next four are the 32-bit result
-		let mut rx = [0u8; 5];
+	pub async fn read_data_code(&mut self) -> Result<i32, Ads1262Error<E>> {
+		// The frame is the RDATA1 command, an optional status byte, the 32-bit result and an optional checksum/CRC byte
+		let status_length = if self.enable_status_byte { 1 } else { 0 };
+		let check_length = if self.data_integrity_check == DataIntegrityCheck::Disabled { 0 } else { 1 };
+		let frame_length = 1 + status_length + 4 + check_length;
 
-		self.spi_device.transfer(&mut rx, &tx).await?;
+		// Send the RDATA1 command followed by dummy bytes to clock out the rest of the frame
+		let mut tx = [0u8; 7];
+		tx[0] = Command::RDATA1 as u8;
+
+		// Receiving buffer has a dummy byte for the command followed by the rest of the frame
+		let mut rx = [0u8; 7];
+
+		self.spi_device
+			.transfer(&mut rx[..frame_length], &tx[..frame_length])
+			.await
+			.map_err(Ads1262Error::SpiError)?;
 
 		// Skip the first part because spi sends a byte for every byte you send it since it's duplex and we're using transfer
-		let b = &rx[1..5];
+		let data_start = 1 + status_length;
+		let b = &rx[data_start..data_start + 4];
+
+		// The check byte only covers the data bytes, so verify it before trusting anything else in the frame
+		if let Some(expected) = self.data_integrity_check.compute(b) {
+			let received = rx[data_start + 4];
+			if expected != received {
+				return Err(Ads1262Error::DataIntegrityMismatch { expected, received });
+			}
+		}
+
+		if self.enable_status_byte {
+			let status = Status::from(rx[1]);
+			self.last_status = Some(status);
+
+			if status.reset {
+				// The ADC lost its configuration so the data was converted with the default registers. Restore it and discard the sample
+				self.recover_from_reset().await?;
+				return Err(Ads1262Error::DeviceReset);
+			}
+			if status.reference_alarm {
+				return Err(Ads1262Error::ReferenceAlarm(status));
+			}
+			if status.has_pga_alarm() {
+				return Err(Ads1262Error::PgaAlarm(status));
+			}
+		}
 
 		// Convert the 4 bytes to a signed 32-bit integer
 		let code = i32::from_be_bytes([b[0], b[1], b[2], b[3]]);
 		Ok(code)
 	}
 
+	// Re-applies the configuration (which also clears the reset flag), then selects the input pair again so its acquisition profile
+	// is written back as well. The profiles are kept on the driver, only the registers of the ADC were lost
+	async fn recover_from_reset(&mut self) -> Result<(), Ads1262Error<E>> {
+		let (positive, negative) = self.last_set_channel_pair;
+		self.apply_configurations().await?;
+		self.select_channels(positive, negative).await?;
+		Ok(())
+	}
+
 	pub fn convert_code_to_volts(
 		&self,
 		code: i32,
 	) -> f32 {
 		// Convert a 32‑bit two’s‑complement code to volts, using current VREF and PGA gain.
-		let full_scale_range: f32 = self.reference_range.to_volts() / self.gain.to_multiplier();
-		(code as f64 / MAX_SIGNED_CODE_SIZE) as f32 * full_scale_range
+		(code as f64 / MAX_SIGNED_CODE_SIZE) as f32 * self.full_scale_range()
+	}
+
+	pub fn convert_code_to_ratio(
+		&self,
+		code: i32,
+	) -> f32 {
+		// Full-scale code corresponds to VIN = VREF / Gain
+		(code as f64 / MAX_SIGNED_CODE_SIZE) as f32 / self.gain.to_multiplier()
+	}
+
+	/// Largest differential input voltage that can be converted with the current reference and gain
+	pub fn full_scale_range(&self) -> Voltage {
+		self.reference_range.to_volts() / self.gain.to_multiplier()
+	}
+
+	/// Temperature of the ADC die in degrees Celsius, measured with the internal temperature sensor
+	pub async fn read_internal_temperature(&mut self) -> Result<f32, Ads1262Error<E>> {
+		let voltage = self.read_monitor(AnalogChannel::TemperatureSensor, ReferenceRange::Internal2_5).await?;
+		Ok((voltage - TEMPERATURE_SENSOR_VOLTAGE_AT_25C) / TEMPERATURE_SENSOR_COEFFICIENT + 25.0)
+	}
+
+	/// Analog supply voltage (AVDD - AVSS), measured against the internal reference
+	pub async fn read_analog_supply_voltage(&mut self) -> Result<Voltage, Ads1262Error<E>> {
+		let voltage = self.read_monitor(AnalogChannel::AnalogSupplyMonitor, ReferenceRange::Internal2_5).await?;
+		Ok(voltage * SUPPLY_MONITOR_DIVIDER)
+	}
+
+	/// Digital supply voltage (DVDD), measured against the internal reference
+	pub async fn read_digital_supply_voltage(&mut self) -> Result<Voltage, Ads1262Error<E>> {
+		let voltage = self.read_monitor(AnalogChannel::DigitalSupplyMonitor, ReferenceRange::Internal2_5).await?;
+		Ok(voltage * SUPPLY_MONITOR_DIVIDER)
+	}
+
+	/// Actual voltage of the configured reference.
+	/// The analog supply monitor is measured against both the configured and the internal reference,
+	/// the ratio between the two readings gives how far the configured reference is from its nominal value.
+	pub async fn read_reference_voltage(&mut self) -> Result<Voltage, Ads1262Error<E>> {
+		let reference_range = self.reference_range;
+		let against_configured_reference = self.read_monitor(AnalogChannel::AnalogSupplyMonitor, reference_range).await?;
+		let against_internal_reference = self
+			.read_monitor(AnalogChannel::AnalogSupplyMonitor, ReferenceRange::Internal2_5)
+			.await?;
+		Ok(reference_range.to_volts() * against_internal_reference / against_configured_reference)
+	}
+
+	// Reads an internal monitor at a gain of 1 against the given reference, restoring the previous configuration afterwards
+	async fn read_monitor(
+		&mut self,
+		monitor: AnalogChannel,
+		reference_range: ReferenceRange,
+	) -> Result<Voltage, Ads1262Error<E>> {
+		// Select the monitor first, so an acquisition profile applied for the previous pair is switched out before changing the configuration
+		self.set_channels(monitor, monitor).await?;
+
+		let previous_profile = self.current_profile();
+		let previous_enable_internal_reference = self.enable_internal_reference;
+		if matches!(reference_range, ReferenceRange::Internal2_5) && !self.enable_internal_reference {
+			self.enable_internal_reference = true;
+			self.apply_internal_reference_configuration().await?;
+			Timer::after_millis(INTERNAL_REFERENCE_SETTLING_TIME_MS).await;
+		}
+		self.apply_acquisition_profile(AcquisitionProfile {
+			gain: Gain::G1,
+			reference_range,
+			..previous_profile
+		})
+		.await?;
+
+		let result = self.read_differential(monitor, monitor).await;
+
+		self.apply_acquisition_profile(previous_profile).await?;
+		if self.enable_internal_reference != previous_enable_internal_reference {
+			self.enable_internal_reference = previous_enable_internal_reference;
+			self.apply_internal_reference_configuration().await?;
+		}
+
+		result
+	}
+
+	/// Checks for an open (burned out) sensor across the input pair by temporarily enabling the sensor bias current.
+	/// A connected sensor conducts the current with a small voltage drop, while an open input is driven towards the rails.
+	pub async fn detect_open_circuit(
+		&mut self,
+		positive: AnalogChannel,
+		negative: AnalogChannel,
+		magnitude: SensorBiasMagnitude,
+	) -> Result<bool, Ads1262Error<E>> {
+		let previous_magnitude = self.sensor_bias_magnitude;
+		let previous_polarity = self.sensor_bias_polarity;
+
+		// Writing MODE1 restarts the conversion, so the next result is taken with the bias current applied
+		self.sensor_bias_magnitude = magnitude;
+		self.sensor_bias_polarity = SensorBiasPolarity::PullUp;
+		self.apply_filter_and_sensor_bias_configuration().await?;
+
+		let result = self.read_differential(positive, negative).await;
+
+		// Restore the bias configuration before looking at the result so the ADC is left as it was configured
+		self.sensor_bias_magnitude = previous_magnitude;
+		self.sensor_bias_polarity = previous_polarity;
+		self.apply_filter_and_sensor_bias_configuration().await?;
+
+		match result {
+			Ok(voltage) => Ok(voltage.abs() >= self.full_scale_range() * OPEN_CIRCUIT_FULL_SCALE_THRESHOLD),
+			// The PGA saturates when the input is pulled to the rails
+			Err(Ads1262Error::PgaAlarm(_)) => Ok(true),
+			Err(e) => Err(e),
+		}
 	}
 
 	pub async fn write_register(
 		&mut self,
 		register: Register,
 		value: u8,
-	) -> Result<(), E> {
+	) -> Result<(), Ads1262Error<E>> {
 		// Mask to 5 bits just in case, to remove the leading bits
 		let mut address = register as u8;
 		address &= 0x1F;
@@ -162,14 +525,78 @@ where
 		let op2 = 0x00;
 
 		let tx = [op1, op2, value];
-		self.spi_device.write(&tx).await?;
+		self.spi_device.write(&tx).await.map_err(Ads1262Error::SpiError)?;
+		self.written_registers[register as usize] = Some(value);
 		Ok(())
 	}
 
+	/// Reads back every register written since the last reset and returns an error on the first one that doesn't hold the written value
+	pub async fn verify_registers(&mut self) -> Result<(), Ads1262Error<E>> {
+		for register in Register::ALL {
+			if let Some(expected) = self.written_registers[register as usize] {
+				let read = self.read_register(register).await?;
+				if read != expected {
+					return Err(Ads1262Error::RegisterMismatch { register, expected, read });
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Reads every register of the ADC into a snapshot of the configuration it is running with
+	pub async fn dump_registers(&mut self) -> Result<RegisterSnapshot, Ads1262Error<E>> {
+		let mut values = [0u8; Register::COUNT];
+		for register in Register::ALL {
+			values[register as usize] = self.read_register(register).await?;
+		}
+
+		let read = |register: Register| values[register as usize];
+		Ok(RegisterSnapshot {
+			id: read(Register::ID),
+			power: read(Register::POWER),
+			interface: read(Register::INTERFACE),
+			mode0: read(Register::MODE0),
+			mode1: read(Register::MODE1),
+			mode2: read(Register::MODE2),
+			inpmux: read(Register::INPMUX),
+			// Place the 24-bit value in the upper bytes and shift back down to sign extend it
+			offset_calibration: i32::from_be_bytes([read(Register::OFCAL2), read(Register::OFCAL1), read(Register::OFCAL0), 0]) >> 8,
+			full_scale_calibration: u32::from_be_bytes([0, read(Register::FSCAL2), read(Register::FSCAL1), read(Register::FSCAL0)]),
+			idacmux: read(Register::IDACMUX),
+			idacmag: read(Register::IDACMAG),
+			refmux: read(Register::REFMUX),
+			tdacp: read(Register::TDACP),
+			tdacn: read(Register::TDACN),
+			gpiocon: read(Register::GPIOCON),
+			gpiodir: read(Register::GPIODIR),
+			gpiodat: read(Register::GPIODAT),
+		})
+	}
+
+	/// Reads the registers of the driver's configuration, then of each acquisition profile with its input pair selected.
+	/// The snapshots are told apart by INPMUX. The pair selected before is selected again afterwards.
+	pub async fn dump_registers_per_profile(&mut self) -> Result<Vec<RegisterSnapshot, { MAX_ACQUISITION_PROFILES + 1 }>, Ads1262Error<E>> {
+		let (previous_positive, previous_negative) = self.last_set_channel_pair;
+		let mut snapshots = Vec::new();
+
+		// The driver's configuration is back in place once a pair without a profile is selected
+		self.select_channels(AnalogChannel::AINCOM, AnalogChannel::AINCOM).await?;
+		snapshots.push(self.dump_registers().await?).ok();
+
+		let pairs: Vec<(AnalogChannel, AnalogChannel), MAX_ACQUISITION_PROFILES> = self.acquisition_profiles.keys().copied().collect();
+		for (positive, negative) in pairs {
+			self.select_channels(positive, negative).await?;
+			snapshots.push(self.dump_registers().await?).ok();
+		}
+
+		self.select_channels(previous_positive, previous_negative).await?;
+		Ok(snapshots)
+	}
+
 	pub async fn read_register(
 		&mut self,
 		register: Register,
-	) -> Result<u8, E> {
+	) -> Result<u8, Ads1262Error<E>> {
 		let mut address = register as u8;
 		// Mask to 5 bits just in case, to remove the leading bits
 		address &= 0x1F;
@@ -183,48 +610,81 @@ where
 		// Receiving buffer is 3 bytes: first two are dummy bytes for the opcodes, third is the register value
 		let mut rx = [0u8; 3];
 		let tx = [op1, op2, 0x00];
-		self.spi_device.transfer(&mut rx, &tx).await?;
+		self.spi_device.transfer(&mut rx, &tx).await.map_err(Ads1262Error::SpiError)?;
 
 		// Skip the first two bytes because spi sends a byte for every byte you send it since it's duplex and we're using transfer
 		Ok(rx[2])
 	}
 
-	pub async fn wait_for_next_data(&mut self) {
-		loop {
-			if self.data_ready.is_low().unwrap_or(false) {
-				break;
-			}
-			Timer::after_micros(5).await;
+	/// Waits until the next conversion result is available, or returns a timeout error if the ADC doesn't respond in time
+	pub async fn wait_for_next_data(&mut self) -> Result<(), Ads1262Error<E>> {
+		// In pulse mode the ADC idles between conversions, so a fresh one is started on demand
+		if self.run_mode == RunMode::Pulse {
+			self.send_command(Command::START1).await?;
+		}
+		let timeout = self.data_ready_timeout();
+		self.wait_for_data_ready(timeout).await
+	}
+
+	/// Expected time for a settled conversion result to be available after the conversion restarts (e.g. after a mux change)
+	pub fn settling_time(&self) -> Duration {
+		let mut latency_us = self.data_rate.first_conversion_latency_ms(self.filter) * 1000.0 + self.conversion_delay.to_micros();
+		// Chop mode and IDAC rotation combine pairs of conversions, which doubles the latency
+		if self.enable_input_chop || self.enable_idac_rotation {
+			latency_us *= 2.0;
+		}
+		Duration::from_micros(latency_us as u64)
+	}
+
+	/// Maximum time to wait for DRDY, derived from the current data rate and filter
+	pub fn data_ready_timeout(&self) -> Duration {
+		self.settling_time() * self.data_ready_timeout_factor + Duration::from_millis(DATA_READY_TIMEOUT_MARGIN_MS)
+	}
+
+	async fn wait_for_data_ready(
+		&mut self,
+		timeout: Duration,
+	) -> Result<(), Ads1262Error<E>> {
+		// DRDY goes low when a new conversion result is available
+		match with_timeout(timeout, self.data_ready.wait_for_falling_edge()).await {
+			Ok(_) => Ok(()),
+			Err(_) => Err(Ads1262Error::DataReadyTimeout),
 		}
 	}
 
 	/// Applies the current configuration settings on the driver to the ADC
-	pub async fn apply_configurations(&mut self) -> Result<(), E> {
+	pub async fn apply_configurations(&mut self) -> Result<(), Ads1262Error<E>> {
+		// Go back to the driver's configuration if an acquisition profile is applied, the channels are reset to AINCOM below
+		if let Some(default_profile) = self.default_profile.take() {
+			self.set_profile(default_profile);
+		}
+
 		self.send_command(Command::STOP1).await?;
 
 		self.apply_reference_range_configuration().await?;
 		self.apply_internal_reference_configuration().await?;
 
-		// Disable all interface options (status byte, CRC, watchdog)
-		// SHOULDDO: make these configurable
-		self.write_register(Register::INTERFACE, 0x00).await?;
+		self.apply_interface_configuration().await?;
 
-		// Clears MODE0 so chop mode is disabled, conversion delay is zero, run mode is continuous.
-		// SHOULDDO: make these configurable
-		self.write_register(Register::MODE0, 0x00).await?;
+		self.apply_conversion_control_configuration().await?;
 
 		self.apply_offset_calibration_configuration().await?;
 		self.apply_full_scale_calibration_configuration().await?;
-		self.apply_filter_configuration().await?;
+		self.apply_filter_and_sensor_bias_configuration().await?;
 		self.apply_gain_and_data_rate_configuration().await?;
+		self.apply_idac_configuration().await?;
 
 		// Short the channels together before we begin
 		self.set_channels(AnalogChannel::AINCOM, AnalogChannel::AINCOM).await?;
 		self.send_command(Command::START1).await?;
+
+		if self.verify_writes {
+			self.verify_registers().await?;
+		}
 		Ok(())
 	}
 
-	pub async fn apply_reference_range_configuration(&mut self) -> Result<(), E> {
+	pub async fn apply_reference_range_configuration(&mut self) -> Result<(), Ads1262Error<E>> {
 		let mut register_value: u8 = 0x00;
 
 		match self.reference_range {
@@ -236,13 +696,18 @@ where
 				register_value |= 0b000 << 3; // INTERNAL 2.5V
 				register_value |= 0b100; // AVSS
 			}
+			ReferenceRange::External { positive, negative, .. } => {
+				register_value |= (positive as u8) << 3;
+				register_value |= negative as u8;
+			}
 		}
 
 		self.write_register(Register::REFMUX, register_value).await?;
 		Ok(())
 	}
 
-	pub async fn apply_internal_reference_configuration(&mut self) -> Result<(), E> {
+	pub async fn apply_internal_reference_configuration(&mut self) -> Result<(), Ads1262Error<E>> {
+		// Leaving the RESET bit (bit 4) at 0 also clears the reset flag reported in the status byte
 		let mut register_value: u8 = 0x00;
 
 		if self.enable_internal_reference {
@@ -253,13 +718,28 @@ where
 		Ok(())
 	}
 
-	pub async fn apply_filter_configuration(&mut self) -> Result<(), E> {
+	pub async fn apply_interface_configuration(&mut self) -> Result<(), Ads1262Error<E>> {
+		// The SPI timeout (bit 3) is left disabled
+		let mut register_value: u8 = 0x00;
+
+		if self.enable_status_byte {
+			register_value |= 1 << 2;
+		}
+		register_value |= self.data_integrity_check as u8;
+
+		self.write_register(Register::INTERFACE, register_value).await
+	}
+
+	pub async fn apply_filter_and_sensor_bias_configuration(&mut self) -> Result<(), Ads1262Error<E>> {
 		let mut register_value: u8 = 0x0;
 		register_value |= (self.filter as u8) << 5;
+		// Bit 4 is left at 0 so the sensor bias is connected to ADC1
+		register_value |= (self.sensor_bias_polarity as u8) << 3;
+		register_value |= self.sensor_bias_magnitude as u8;
 		self.write_register(Register::MODE1, register_value).await
 	}
 
-	pub async fn apply_gain_and_data_rate_configuration(&mut self) -> Result<(), E> {
+	pub async fn apply_gain_and_data_rate_configuration(&mut self) -> Result<(), Ads1262Error<E>> {
 		let mut register_value: u8 = 0x0;
 		register_value |= (self.gain as u8) << 4;
 		register_value |= self.data_rate as u8;
@@ -268,23 +748,127 @@ where
 		Ok(())
 	}
 
-	pub async fn apply_offset_calibration_configuration(&mut self) -> Result<(), E> {
-		// SHOULD DO: implement
-		self.write_register(Register::OFCAL0, 0x00).await?;
-		self.write_register(Register::OFCAL1, 0x00).await?;
-		self.write_register(Register::OFCAL2, 0x00).await?;
+	pub async fn apply_conversion_control_configuration(&mut self) -> Result<(), Ads1262Error<E>> {
+		// | d      | d       | dd   | dddd  |
+		// | REFREV | RUNMODE | CHOP | DELAY |
+		let mut register_value: u8 = 0x00;
+
+		if self.enable_reference_reversal {
+			register_value |= 1 << 7;
+		}
+		register_value |= (self.run_mode as u8) << 6;
+		if self.enable_idac_rotation {
+			register_value |= 1 << 5;
+		}
+		if self.enable_input_chop {
+			register_value |= 1 << 4;
+		}
+		register_value |= self.conversion_delay as u8;
+
+		self.write_register(Register::MODE0, register_value).await?;
+		Ok(())
+	}
+
+	pub async fn apply_idac_configuration(&mut self) -> Result<(), Ads1262Error<E>> {
+		// | dddd  | dddd  |
+		// | IDAC2 | IDAC1 |
+		let idac1_output = self.idac1_output.map_or(IDAC_OUTPUT_NO_CONNECTION, |output| output as u8);
+		let idac2_output = self.idac2_output.map_or(IDAC_OUTPUT_NO_CONNECTION, |output| output as u8);
+		self.write_register(Register::IDACMUX, (idac2_output << 4) | idac1_output).await?;
+		self.write_register(Register::IDACMAG, ((self.idac2_magnitude as u8) << 4) | self.idac1_magnitude as u8)
+			.await?;
+		Ok(())
+	}
+
+	pub async fn apply_offset_calibration_configuration(&mut self) -> Result<(), Ads1262Error<E>> {
+		// OFCAL0 is the least significant byte and OFCAL2 the most significant byte of the 24-bit offset
+		let [_, high, middle, low] = self.calibrations[self.gain as usize].offset.to_be_bytes();
+		self.write_register(Register::OFCAL0, low).await?;
+		self.write_register(Register::OFCAL1, middle).await?;
+		self.write_register(Register::OFCAL2, high).await?;
 		Ok(())
 	}
 
-	pub async fn get_id_and_revision(&mut self) -> Result<(u8, u8), E> {
+	pub async fn get_id_and_revision(&mut self) -> Result<(u8, u8), Ads1262Error<E>> {
 		let id = self.read_register(Register::ID).await?;
 		let device_id = (id >> 5) & 0x07; // bits 7:5
 		let revision_id = id & 0x1F; // bits 4:0
 		Ok((device_id, revision_id))
 	}
 
-	pub async fn apply_full_scale_calibration_configuration(&mut self) -> Result<(), E> {
-		// SHOULD DO: implement
+	/// Resets the ADC and checks that it answers like an ADS1262, returning its device and revision IDs if it does.
+	/// A missing ADC leaves MISO floating, which reads as all zeros or all ones. Neither matches the POWER reset value,
+	/// so that register is checked as well since an ID of all zeros would otherwise pass for an ADS1262.
+	pub async fn probe(&mut self) -> Result<Option<(u8, u8)>, Ads1262Error<E>> {
+		self.reset_hardware().await?;
+
+		let (device_id, revision_id) = self.get_id_and_revision().await?;
+		if device_id != ADS1262_DEVICE_ID && device_id != ADS1263_DEVICE_ID {
+			return Ok(None);
+		}
+
+		if self.read_register(Register::POWER).await? != POWER_REGISTER_RESET_VALUE {
+			return Ok(None);
+		}
+
+		Ok(Some((device_id, revision_id)))
+	}
+
+	pub async fn apply_full_scale_calibration_configuration(&mut self) -> Result<(), Ads1262Error<E>> {
+		// FSCAL0 is the least significant byte and FSCAL2 the most significant byte of the 24-bit full-scale value
+		let [_, high, middle, low] = self.calibrations[self.gain as usize].full_scale.to_be_bytes();
+		self.write_register(Register::FSCAL0, low).await?;
+		self.write_register(Register::FSCAL1, middle).await?;
+		self.write_register(Register::FSCAL2, high).await?;
 		Ok(())
 	}
+
+	/// Runs one of the ADC's calibration routines on the currently selected inputs and stores the result for the current gain.
+	/// Conversions must be running (i.e. apply_configurations() was called) for the calibration to take place.
+	pub async fn calibrate(
+		&mut self,
+		calibration_type: CalibrationType,
+	) -> Result<CalibrationValues, Ads1262Error<E>> {
+		// Start from the reset values so the result does not depend on a previous calibration
+		match calibration_type {
+			CalibrationType::SelfOffset | CalibrationType::SystemOffset => {
+				self.calibrations[self.gain as usize].offset = CalibrationValues::default().offset;
+				self.apply_offset_calibration_configuration().await?;
+			}
+			CalibrationType::Gain => {
+				self.calibrations[self.gain as usize].full_scale = CalibrationValues::default().full_scale;
+				self.apply_full_scale_calibration_configuration().await?;
+			}
+		}
+
+		// DRDY is driven high while the calibration is running and goes low once the new values are in place.
+		// Calibration takes 16 conversion periods so it can take a while at slow data rates.
+		self.send_command(calibration_type.to_command()).await?;
+		let timeout = self.data_ready_timeout() * CALIBRATION_DURATION_IN_CONVERSIONS;
+		self.wait_for_data_ready(timeout).await?;
+
+		let calibration_values = self.read_calibration_values().await?;
+		self.calibrations[self.gain as usize] = calibration_values;
+
+		// The ADC updated the calibration registers itself, write them back so verify_registers() expects the new values
+		self.apply_offset_calibration_configuration().await?;
+		self.apply_full_scale_calibration_configuration().await?;
+		Ok(calibration_values)
+	}
+
+	/// Reads the OFCAL and FSCAL registers currently held by the ADC
+	pub async fn read_calibration_values(&mut self) -> Result<CalibrationValues, Ads1262Error<E>> {
+		let offset_low = self.read_register(Register::OFCAL0).await?;
+		let offset_middle = self.read_register(Register::OFCAL1).await?;
+		let offset_high = self.read_register(Register::OFCAL2).await?;
+		let full_scale_low = self.read_register(Register::FSCAL0).await?;
+		let full_scale_middle = self.read_register(Register::FSCAL1).await?;
+		let full_scale_high = self.read_register(Register::FSCAL2).await?;
+
+		Ok(CalibrationValues {
+			// Place the 24-bit value in the upper bytes and shift back down to sign extend it
+			offset: i32::from_be_bytes([offset_high, offset_middle, offset_low, 0]) >> 8,
+			full_scale: u32::from_be_bytes([0, full_scale_high, full_scale_middle, full_scale_low]),
+		})
+	}
 }
@@ -0,0 +1,11 @@
+use crate::adc::driver::types::{DataRate, Filter, Gain, ReferenceRange};
+
+/// Gain, data rate, filter and reference used while converting a given input pair.
+/// Lets sensors with different needs share an ADC without reconfiguring it by hand before each reading.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct AcquisitionProfile {
+	pub gain: Gain,
+	pub data_rate: DataRate,
+	pub filter: Filter,
+	pub reference_range: ReferenceRange,
+}
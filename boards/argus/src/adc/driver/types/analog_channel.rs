@@ -1,4 +1,4 @@
-#[derive(Copy, Clone, Debug, PartialEq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum AnalogChannel {
 	AIN0 = 0,
 	AIN1 = 1,
@@ -11,6 +11,12 @@ pub enum AnalogChannel {
 	AIN8 = 8,
 	AIN9 = 9,
 	AINCOM = 10,
+
+	// Internal monitors. Select the same monitor on both inputs of the pair to measure it.
+	// These are only valid as ADC inputs, the IDAC outputs are restricted to the pins by IdacOutput.
+	TemperatureSensor = 11,    // Internal die temperature sensor
+	AnalogSupplyMonitor = 12,  // (AVDD - AVSS) / 4
+	DigitalSupplyMonitor = 13, // DVDD / 4
 }
 
 impl AnalogChannel {
@@ -27,6 +33,9 @@ impl AnalogChannel {
 			8 => AnalogChannel::AIN8,
 			9 => AnalogChannel::AIN9,
 			10 => AnalogChannel::AINCOM,
+			11 => AnalogChannel::TemperatureSensor,
+			12 => AnalogChannel::AnalogSupplyMonitor,
+			13 => AnalogChannel::DigitalSupplyMonitor,
 			_ => panic!("Invalid AnalogChannel value: {}", value),
 		}
 	}
@@ -0,0 +1,43 @@
+use defmt::Format;
+use serde::{Deserialize, Serialize};
+
+use crate::adc::driver::types::Command;
+
+/// Self-calibration routines built into the ADS1262.
+/// Offset calibrations update the OFCAL registers and gain calibration updates the FSCAL registers.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Format)]
+pub enum CalibrationType {
+	SelfOffset,   // SFOCAL1: Inputs are shorted internally, removes the offset of the PGA and modulator
+	SystemOffset, // SYOCAL1: Inputs must be shorted externally, also removes the offset of the external sensor wiring
+	Gain,         // GANCAL1: A full-scale signal must be applied to the inputs
+}
+
+impl CalibrationType {
+	pub fn to_command(&self) -> Command {
+		match self {
+			CalibrationType::SelfOffset => Command::SFOCAL1,
+			CalibrationType::SystemOffset => Command::SYOCAL1,
+			CalibrationType::Gain => Command::GANCAL1,
+		}
+	}
+}
+
+/// Values held in the OFCAL and FSCAL registers of the ADC.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Format, Serialize, Deserialize)]
+pub struct CalibrationValues {
+	// 24-bit two's complement offset that is subtracted from the conversion result
+	pub offset: i32,
+
+	// 24-bit full-scale correction. 0x400000 corresponds to a gain correction of 1
+	pub full_scale: u32,
+}
+
+impl Default for CalibrationValues {
+	fn default() -> Self {
+		// Reset values of the OFCAL and FSCAL registers from the datasheet
+		Self {
+			offset: 0,
+			full_scale: 0x40_0000,
+		}
+	}
+}
@@ -19,4 +19,16 @@ pub enum Command {
 	// Reads the latest conversion result from ADC1.
 	// You issue this command, then immediately read the output data bytes over SPI.
 	RDATA1 = 0x12,
+
+	// ADC1 system offset calibration.
+	// The inputs selected by INPMUX must be shorted externally. The result is written to the OFCAL registers.
+	SYOCAL1 = 0x16,
+
+	// ADC1 gain calibration.
+	// A full-scale signal must be applied to the inputs selected by INPMUX. The result is written to the FSCAL registers.
+	GANCAL1 = 0x17,
+
+	// ADC1 self offset calibration.
+	// The inputs are disconnected and shorted internally. The result is written to the OFCAL registers.
+	SFOCAL1 = 0x19,
 }
@@ -0,0 +1,37 @@
+/// Delay inserted by the ADC between the start of a conversion and the first sample,
+/// allowing external filters and excitation to settle after a mux change or a chop/IDAC rotation step.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ConversionDelay {
+	None = 0,        // 0b0000
+	Delay8_7us = 1,  // 0b0001
+	Delay17us = 2,   // 0b0010
+	Delay35us = 3,   // 0b0011
+	Delay69us = 4,   // 0b0100
+	Delay139us = 5,  // 0b0101
+	Delay278us = 6,  // 0b0110
+	Delay555us = 7,  // 0b0111
+	Delay1_1ms = 8,  // 0b1000
+	Delay2_2ms = 9,  // 0b1001
+	Delay4_4ms = 10, // 0b1010
+	Delay8_8ms = 11, // 0b1011
+}
+
+impl ConversionDelay {
+	pub fn to_micros(&self) -> f32 {
+		match self {
+			ConversionDelay::None => 0.0,
+			ConversionDelay::Delay8_7us => 8.7,
+			ConversionDelay::Delay17us => 17.0,
+			ConversionDelay::Delay35us => 35.0,
+			ConversionDelay::Delay69us => 69.0,
+			ConversionDelay::Delay139us => 139.0,
+			ConversionDelay::Delay278us => 278.0,
+			ConversionDelay::Delay555us => 555.0,
+			ConversionDelay::Delay1_1ms => 1100.0,
+			ConversionDelay::Delay2_2ms => 2200.0,
+			ConversionDelay::Delay4_4ms => 4400.0,
+			ConversionDelay::Delay8_8ms => 8800.0,
+		}
+	}
+}
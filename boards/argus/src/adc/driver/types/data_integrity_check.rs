@@ -0,0 +1,32 @@
+/// Integrity check byte that follows the conversion data, configured in the INTERFACE register.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum DataIntegrityCheck {
+	Disabled = 0b00,
+	Checksum = 0b01, // Sum of the data bytes plus 0x9B, truncated to 8 bits
+	Crc = 0b10,      // CRC-8 over the data bytes with polynomial x^8 + x^2 + x + 1
+}
+
+impl DataIntegrityCheck {
+	/// Computes the check byte the ADC is expected to send for the given data bytes, or None if disabled
+	pub fn compute(
+		&self,
+		data: &[u8],
+	) -> Option<u8> {
+		match self {
+			DataIntegrityCheck::Disabled => None,
+			DataIntegrityCheck::Checksum => Some(data.iter().fold(0x9B_u8, |sum, byte| sum.wrapping_add(*byte))),
+			DataIntegrityCheck::Crc => {
+				const POLYNOMIAL: u8 = 0x07; // x^8 + x^2 + x + 1, the x^8 term is implied
+				let mut crc: u8 = 0xFF;
+				for byte in data {
+					crc ^= byte;
+					for _ in 0..8 {
+						crc = if crc & 0x80 != 0 { (crc << 1) ^ POLYNOMIAL } else { crc << 1 };
+					}
+				}
+				Some(crc)
+			}
+		}
+	}
+}
@@ -1,7 +1,9 @@
+use crate::adc::driver::types::Filter;
+
 /// Overall data rate of the ADC in samples per second (SPS).
 /// Higher data rates give faster response but lower resolution and more noise.
 #[repr(u8)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum DataRate {
 	Sps2_5 = 0,    // 0b0000,
 	Sps5 = 1,      // 0b0001,
@@ -20,3 +22,58 @@ pub enum DataRate {
 	Sps19200 = 14, // 0b1110,
 	Sps38400 = 15, // 0b1111,
 }
+
+impl DataRate {
+	pub fn to_samples_per_second(&self) -> f32 {
+		match self {
+			DataRate::Sps2_5 => 2.5,
+			DataRate::Sps5 => 5.0,
+			DataRate::Sps10 => 10.0,
+			DataRate::Sps16_6 => 16.6,
+			DataRate::Sps20 => 20.0,
+			DataRate::Sps50 => 50.0,
+			DataRate::Sps60 => 60.0,
+			DataRate::Sps100 => 100.0,
+			DataRate::Sps400 => 400.0,
+			DataRate::Sps1200 => 1200.0,
+			DataRate::Sps2400 => 2400.0,
+			DataRate::Sps4800 => 4800.0,
+			DataRate::Sps7200 => 7200.0,
+			DataRate::Sps14400 => 14400.0,
+			DataRate::Sps19200 => 19200.0,
+			DataRate::Sps38400 => 38400.0,
+		}
+	}
+
+	/// Time from the start of a conversion until its settled result is ready, in milliseconds.
+	/// Conversions restart on START and on any register write that changes the conversion (e.g. a mux change),
+	/// and the digital filter is reset with them, so the first result after the restart is already settled.
+	/// Values are from the conversion latency table of the ADS1262 datasheet, without conversion delay or chop.
+	pub fn first_conversion_latency_ms(
+		&self,
+		filter: Filter,
+	) -> f32 {
+		// Sinc1, Sinc2, Sinc3, Sinc4, indexed by the number of conversion periods the filter takes to settle
+		let sinc_latencies: [f32; 4] = match self {
+			DataRate::Sps2_5 => [400.4, 800.4, 1200.0, 1600.0],
+			DataRate::Sps5 => [200.4, 400.4, 600.4, 800.4],
+			DataRate::Sps10 => [100.4, 200.4, 300.4, 400.4],
+			DataRate::Sps16_6 => [60.4, 120.4, 180.4, 240.4],
+			DataRate::Sps20 => [50.4, 100.4, 150.4, 200.4],
+			DataRate::Sps50 => [20.4, 40.4, 60.4, 80.4],
+			DataRate::Sps60 => [17.1, 33.7, 50.4, 67.1],
+			DataRate::Sps100 => [10.4, 20.4, 30.4, 40.4],
+			DataRate::Sps400 => [2.9, 5.4, 7.9, 10.4],
+			DataRate::Sps1200 => [1.3, 2.1, 2.9, 3.8],
+			DataRate::Sps2400 => [0.85, 1.3, 1.7, 2.1],
+			DataRate::Sps4800 => [0.65, 0.85, 1.1, 1.3],
+			DataRate::Sps7200 => [0.56, 0.71, 0.85, 1.0],
+			DataRate::Sps14400 => [0.42, 0.49, 0.56, 0.63],
+			DataRate::Sps19200 => [0.39, 0.44, 0.49, 0.54],
+			DataRate::Sps38400 => [0.34, 0.36, 0.39, 0.42],
+		};
+
+		// The FIR filter is only available at 20 SPS and below, where it settles in 3 conversion periods like Sinc3
+		sinc_latencies[filter.settling_conversions() as usize - 1]
+	}
+}
@@ -0,0 +1,31 @@
+use defmt::Format;
+
+use crate::adc::driver::types::{Register, Status};
+
+/// Errors returned by the ADS1262 driver
+#[derive(Debug, Clone, Copy, Format)]
+pub enum Ads1262Error<E> {
+	// Communication with the ADC over SPI failed
+	SpiError(E),
+
+	// The checksum or CRC byte sent with the conversion data does not match the data, so the data is corrupted
+	DataIntegrityMismatch { expected: u8, received: u8 },
+
+	// The ADC was reset since the configuration was last applied (e.g. power glitch), so it was running on default registers
+	DeviceReset,
+
+	// The PGA output exceeded its range, so the conversion data is not valid
+	PgaAlarm(Status),
+
+	// DRDY did not go low within the expected conversion time, the ADC is likely disconnected or not converting
+	DataReadyTimeout,
+
+	// The reference voltage is below the valid range, so the conversion data is not valid
+	ReferenceAlarm(Status),
+
+	// A register read back after applying the configuration does not hold the value that was written to it
+	RegisterMismatch { register: Register, expected: u8, read: u8 },
+
+	// No room left to register another acquisition profile, see MAX_ACQUISITION_PROFILES
+	TooManyAcquisitionProfiles,
+}
@@ -3,7 +3,7 @@
 /// Lower order (Sinc1) responds faster but passes more noise.
 /// FIR -> A fixed FIR filter designed for good rejection of mains interference (50/60 Hz). It gives a balance between noise rejection and throughput.
 #[repr(u8)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum Filter {
 	Sinc1 = 0, // 0b000,
 	Sinc2 = 1, // 0b001,
@@ -11,3 +11,16 @@ pub enum Filter {
 	Sinc4 = 3, // 0b011,
 	FIR = 4,   // 0b100,
 }
+
+impl Filter {
+	/// Number of conversion periods the filter needs to produce a settled result after the conversion restarts
+	pub fn settling_conversions(&self) -> u32 {
+		match self {
+			Filter::Sinc1 => 1,
+			Filter::Sinc2 => 2,
+			Filter::Sinc3 => 3,
+			Filter::Sinc4 => 4,
+			Filter::FIR => 3,
+		}
+	}
+}
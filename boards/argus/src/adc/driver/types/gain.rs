@@ -1,6 +1,10 @@
+use defmt::Format;
+use serde::{Deserialize, Serialize};
+use strum::EnumCount;
+
 /// Preset Gain values from ADS126x datasheet
 #[repr(u8)]
-#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash, Format, Serialize, Deserialize, EnumCount)]
 pub enum Gain {
 	G1 = 0b000,
 	G2 = 0b001,
@@ -0,0 +1,36 @@
+/// Value of the IDACMUX fields that leaves the IDAC output unconnected
+pub const IDAC_OUTPUT_NO_CONNECTION: u8 = 0b1011;
+
+/// Analog input pin an IDAC can source its current to. Unlike AnalogChannel, the internal monitors are not valid IDAC outputs
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IdacOutput {
+	AIN0 = 0,
+	AIN1 = 1,
+	AIN2 = 2,
+	AIN3 = 3,
+	AIN4 = 4,
+	AIN5 = 5,
+	AIN6 = 6,
+	AIN7 = 7,
+	AIN8 = 8,
+	AIN9 = 9,
+	AINCOM = 10,
+}
+
+/// Magnitude of the excitation current sourced by each IDAC, e.g. to excite RTDs
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum IdacMagnitude {
+	Off = 0,            // 0b0000
+	Current50uA = 1,    // 0b0001
+	Current100uA = 2,   // 0b0010
+	Current250uA = 3,   // 0b0011
+	Current500uA = 4,   // 0b0100
+	Current750uA = 5,   // 0b0101
+	Current1000uA = 6,  // 0b0110
+	Current1500uA = 7,  // 0b0111
+	Current2000uA = 8,  // 0b1000
+	Current2500uA = 9,  // 0b1001
+	Current3000uA = 10, // 0b1010
+}
@@ -1,17 +1,37 @@
+pub mod acquisition_profile;
 pub mod analog_channel;
+pub mod calibration;
 pub mod command;
+pub mod conversion_delay;
+pub mod data_integrity_check;
 pub mod data_rate;
+pub mod error;
 pub mod filter;
 pub mod gain;
+pub mod idac;
 pub mod reference_range;
 pub mod register;
+pub mod register_snapshot;
+pub mod run_mode;
+pub mod sensor_bias;
+pub mod status;
 
+pub use acquisition_profile::*;
 pub use analog_channel::*;
+pub use calibration::*;
 pub use command::*;
+pub use conversion_delay::*;
+pub use data_integrity_check::*;
 pub use data_rate::*;
+pub use error::*;
 pub use filter::*;
 pub use gain::*;
+pub use idac::*;
 pub use reference_range::*;
 pub use register::*;
+pub use register_snapshot::*;
+pub use run_mode::*;
+pub use sensor_bias::*;
+pub use status::*;
 
 pub type Voltage = f32;
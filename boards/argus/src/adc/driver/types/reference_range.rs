@@ -1,15 +1,41 @@
 /// Defines the reference voltage for the ADC.
 /// This defines the full-scale-differential input range = VREFP - VREFN / Gain
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq)]
 pub enum ReferenceRange {
 	Avdd,        // REFP = Avdd, REFN = Avss
 	Internal2_5, // REFP = Internal 2.5V REFN = Avss
+	// REFP and REFN are taken from analog inputs, e.g. across a reference resistor for ratiometric measurements.
+	// The nominal voltage is only used to convert codes to volts, ratiometric readings don't depend on it.
+	External {
+		positive: ReferencePositiveInput,
+		negative: ReferenceNegativeInput,
+		nominal_volts: f32,
+	},
 }
 impl ReferenceRange {
 	pub fn to_volts(&self) -> f32 {
 		match self {
 			ReferenceRange::Avdd => 5.0,
 			ReferenceRange::Internal2_5 => 2.5,
+			ReferenceRange::External { nominal_volts, .. } => *nominal_volts,
 		}
 	}
 }
+
+/// Analog inputs that can be used as the positive reference (RMUXP field of REFMUX)
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReferencePositiveInput {
+	AIN0 = 0b001,
+	AIN2 = 0b010,
+	AIN4 = 0b011,
+}
+
+/// Analog inputs that can be used as the negative reference (RMUXN field of REFMUX)
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ReferenceNegativeInput {
+	AIN1 = 0b001,
+	AIN3 = 0b010,
+	AIN5 = 0b011,
+}
@@ -1,6 +1,9 @@
+use defmt::Format;
+use strum::EnumCount;
+
 #[repr(u8)]
 #[allow(non_camel_case_types)]
-#[derive(Copy, Clone, Debug)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Format, EnumCount)]
 pub enum Register {
 	// Device ID register. Lets you confirm you’re talking to an ADS1262 and check silicon revision.
 	ID = 0x00,
@@ -33,6 +36,48 @@ pub enum Register {
 	FSCAL1 = 0x0B,
 	FSCAL2 = 0x0C,
 
+	// Excitation current multiplexer. Selects the analog input each of the two IDACs is connected to.
+	IDACMUX = 0x0D,
+
+	// Excitation current magnitude. Sets the current sourced by each of the two IDACs.
+	IDACMAG = 0x0E,
+
 	// Reference multiplexer register
 	REFMUX = 0x0F,
+
+	// Test DAC outputs. Can drive a test voltage onto AIN6/AIN7.
+	TDACP = 0x10,
+	TDACN = 0x11,
+
+	// GPIO configuration, direction and data for the analog inputs that double as GPIOs
+	GPIOCON = 0x12,
+	GPIODIR = 0x13,
+	GPIODAT = 0x14,
+}
+
+impl Register {
+	// Every register in address order, so the index of each register matches its address
+	pub const ALL: [Register; Register::COUNT] = [
+		Register::ID,
+		Register::POWER,
+		Register::INTERFACE,
+		Register::MODE0,
+		Register::MODE1,
+		Register::MODE2,
+		Register::INPMUX,
+		Register::OFCAL0,
+		Register::OFCAL1,
+		Register::OFCAL2,
+		Register::FSCAL0,
+		Register::FSCAL1,
+		Register::FSCAL2,
+		Register::IDACMUX,
+		Register::IDACMAG,
+		Register::REFMUX,
+		Register::TDACP,
+		Register::TDACN,
+		Register::GPIOCON,
+		Register::GPIODIR,
+		Register::GPIODAT,
+	];
 }
@@ -0,0 +1,23 @@
+use defmt::Format;
+
+/// Contents of every register of the ADC at the time it was read, see Register for what each one configures
+#[derive(Debug, Clone, Copy, Format)]
+pub struct RegisterSnapshot {
+	pub id: u8,
+	pub power: u8,
+	pub interface: u8,
+	pub mode0: u8,
+	pub mode1: u8,
+	pub mode2: u8,
+	pub inpmux: u8,
+	pub offset_calibration: i32,     // OFCAL2..0 sign extended
+	pub full_scale_calibration: u32, // FSCAL2..0
+	pub idacmux: u8,
+	pub idacmag: u8,
+	pub refmux: u8,
+	pub tdacp: u8,
+	pub tdacn: u8,
+	pub gpiocon: u8,
+	pub gpiodir: u8,
+	pub gpiodat: u8,
+}
@@ -0,0 +1,8 @@
+/// Continuous -> Conversions run back to back once started.
+/// Pulse -> A single conversion is taken each time a conversion is started, then the ADC idles.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum RunMode {
+	Continuous = 0,
+	Pulse = 1,
+}
@@ -0,0 +1,21 @@
+/// Magnitude of the sensor bias current sourced/sunk on the selected input pair.
+/// Used to detect open (burned out) sensors since an open input gets driven towards the supply rails.
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SensorBiasMagnitude {
+	None = 0,           // 0b000
+	Current0_5uA = 1,   // 0b001
+	Current2uA = 2,     // 0b010
+	Current10uA = 3,    // 0b011
+	Current50uA = 4,    // 0b100
+	Current200uA = 5,   // 0b101
+	Resistor10MOhm = 6, // 0b110
+}
+
+/// Direction of the sensor bias current
+#[repr(u8)]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SensorBiasPolarity {
+	PullUp = 0,   // Sources current into AINP and sinks it from AINN
+	PullDown = 1, // Sinks current from AINP and sources it into AINN
+}
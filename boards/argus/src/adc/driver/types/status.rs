@@ -0,0 +1,43 @@
+use defmt::Format;
+
+/// Status byte that precedes the conversion data when enabled in the INTERFACE register.
+/// Reports conditions that can make the conversion data invalid.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Format)]
+pub struct Status {
+	// A new conversion result from ADC1 is available since the last read
+	pub adc1_new_data: bool,
+
+	// The reference voltage dropped below the low reference threshold (~0.4 V)
+	pub reference_alarm: bool,
+
+	// The PGA absolute output voltage went below the low limit
+	pub pga_output_low_alarm: bool,
+
+	// The PGA absolute output voltage went above the high limit
+	pub pga_output_high_alarm: bool,
+
+	// The PGA differential output voltage exceeded ±105% of full scale
+	pub pga_differential_output_alarm: bool,
+
+	// The device was reset. Cleared by writing 0 to the RESET bit of the POWER register
+	pub reset: bool,
+}
+
+impl From<u8> for Status {
+	fn from(value: u8) -> Self {
+		Self {
+			adc1_new_data: value & (1 << 6) != 0,
+			reference_alarm: value & (1 << 4) != 0,
+			pga_output_low_alarm: value & (1 << 3) != 0,
+			pga_output_high_alarm: value & (1 << 2) != 0,
+			pga_differential_output_alarm: value & (1 << 1) != 0,
+			reset: value & (1 << 0) != 0,
+		}
+	}
+}
+
+impl Status {
+	pub fn has_pga_alarm(&self) -> bool {
+		self.pga_output_low_alarm || self.pga_output_high_alarm || self.pga_differential_output_alarm
+	}
+}
@@ -1,3 +1,7 @@
+pub mod calibration;
+pub mod config;
+pub mod detection;
 pub mod driver;
+pub mod registers;
 pub mod service;
 pub mod types;
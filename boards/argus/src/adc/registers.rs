@@ -0,0 +1,58 @@
+use core::str::FromStr;
+
+use defmt::{error, info};
+use heapless::Vec;
+use uor_utils::csv::SerializeCSV;
+
+use crate::adc::config::ADC_REGISTERS_FILE_NAME;
+use crate::adc::driver::config::MAX_ACQUISITION_PROFILES;
+use crate::adc::service::{AdcError, AdcService};
+use crate::adc::types::{AdcDevice, AdcRegisterSnapshot};
+use crate::sd::types::{FileName, OperationScope, SdCardError};
+
+// Register snapshot logic has been separated into its own file for clarity
+impl<const ADC_COUNT: usize> AdcService<ADC_COUNT> {
+	/// Reads every register of the given ADC, once for its configuration and once for each acquisition profile
+	pub async fn dump_registers(
+		&self,
+		adc: AdcDevice,
+	) -> Result<Vec<AdcRegisterSnapshot, { MAX_ACQUISITION_PROFILES + 1 }>, AdcError> {
+		let snapshots = self.drivers[adc as usize].lock().await.dump_registers_per_profile().await?;
+		Ok(snapshots.into_iter().map(|snapshot| AdcRegisterSnapshot::new(adc, snapshot)).collect())
+	}
+
+	/// Writes the register snapshot of every available ADC to the current session directory. A session must be set before calling this.
+	/// Failing to read the registers is returned, failing to write them to the SD card is only logged.
+	pub async fn save_register_snapshots(&self) -> Result<(), AdcError> {
+		for adc_index in 0..ADC_COUNT {
+			let adc = AdcDevice::from(adc_index);
+			if !self.is_available(adc).await {
+				continue;
+			}
+
+			for snapshot in self.dump_registers(adc).await? {
+				match self.save_register_snapshot(snapshot).await {
+					Err(e) => error!("Failed to save ADC register snapshot: {:?}", e),
+					_ => {}
+				}
+			}
+		}
+		Ok(())
+	}
+
+	/// Writes the register snapshot to the current session directory. A session must be set before calling this.
+	pub async fn save_register_snapshot(
+		&self,
+		snapshot: AdcRegisterSnapshot,
+	) -> Result<(), SdCardError> {
+		info!("Saving ADC register snapshot: {:?}", snapshot);
+		let mut sd_card_service = self.sd_card_service.lock().await;
+		let path = FileName::from_str(ADC_REGISTERS_FILE_NAME).unwrap();
+		if !(sd_card_service.file_exists(OperationScope::CurrentSession, path.clone())?) {
+			sd_card_service.write(OperationScope::CurrentSession, path.clone(), AdcRegisterSnapshot::get_csv_header())?;
+		}
+
+		sd_card_service.write(OperationScope::CurrentSession, path, snapshot.to_csv_line())?;
+		Ok(())
+	}
+}
@@ -2,24 +2,41 @@ use core::convert::Infallible;
 
 use embassy_embedded_hal::shared_bus::asynch::spi::SpiDevice;
 use embassy_embedded_hal::shared_bus::SpiDeviceError;
+use embassy_futures::join::join_array;
 use embassy_stm32::Peripheral;
-use embassy_stm32::{gpio, mode, spi, time::mhz};
-use embassy_sync::{blocking_mutex::raw::CriticalSectionRawMutex, mutex::Mutex};
+use embassy_stm32::{exti, gpio, mode, spi, time::mhz};
+use embassy_sync::{
+	blocking_mutex::raw::CriticalSectionRawMutex,
+	mutex::{Mutex, MutexGuard},
+};
+use heapless::Vec;
 use static_cell::StaticCell;
+use uor_utils::utils::types::AsyncMutex;
 
+use crate::adc::driver::types::{Ads1262Error, AnalogChannel, Voltage};
 use crate::adc::driver::Ads1262;
+use crate::adc::types::AdcAvailability;
+use crate::sd::service::SDCardService;
 
 // HACK: Use a static cell to hold the SPI bus shared between multiple ADC instances since we can't have self-referencing structs
 // i.e. AdcService holding multiple ADC instances that each hold a reference to the same SPI bus that the ADC service also owns
 static ADC_SPI_BUS: StaticCell<Mutex<CriticalSectionRawMutex, spi::Spi<'static, mode::Async>>> = StaticCell::new();
 
 /// Acts as an orchestration layer for multiple ADC drivers.
+/// Shared as is between services, each driver has its own lock so the ADCs can convert and be read concurrently.
 pub struct AdcService<const ADC_COUNT: usize> {
-	pub drivers: [AdcDriver; ADC_COUNT],
+	pub drivers: [AsyncMutex<AdcDriver>; ADC_COUNT],
+
+	// Which ADCs answered at startup, missing and faulted ADCs are skipped by the measurements
+	pub availability: AsyncMutex<[AdcAvailability; ADC_COUNT]>,
+
+	// Used to persist the ADC calibrations
+	pub sd_card_service: &'static AsyncMutex<SDCardService>,
 }
 
 impl<const ADC_COUNT: usize> AdcService<ADC_COUNT> {
 	pub fn new<T: spi::Instance>(
+		sd_card_service: &'static AsyncMutex<SDCardService>,
 		peri: impl Peripheral<P = T> + 'static,
 		sck: impl Peripheral<P = impl spi::SckPin<T>> + 'static,
 		mosi: impl Peripheral<P = impl spi::MosiPin<T>> + 'static,
@@ -34,17 +51,96 @@ impl<const ADC_COUNT: usize> AdcService<ADC_COUNT> {
 
 		let spi_bus = ADC_SPI_BUS.init(Mutex::new(spi::Spi::new(peri, sck, mosi, miso, tx_dma, rx_dma, spi_config)));
 		let mut adc_configs_iter = adc_configs.into_iter();
-		let drivers: [AdcDriver; ADC_COUNT] = core::array::from_fn(|_| {
+		let drivers: [AsyncMutex<AdcDriver>; ADC_COUNT] = core::array::from_fn(|_| {
 			let adc_config = adc_configs_iter.next().unwrap();
 			let chip_select = gpio::Output::new(adc_config.chip_select, gpio::Level::High, gpio::Speed::VeryHigh);
-			let data_ready = gpio::Input::new(adc_config.data_ready, gpio::Pull::None);
+			let data_ready = exti::ExtiInput::new(adc_config.data_ready, adc_config.data_ready_exti, gpio::Pull::None);
 			let reset = gpio::Output::new(adc_config.reset, gpio::Level::High, gpio::Speed::VeryHigh);
 			let start = gpio::Output::new(adc_config.start, gpio::Level::Low, gpio::Speed::VeryHigh);
 
-			Ads1262::new(SpiDevice::new(spi_bus, chip_select), data_ready, reset, start)
+			AsyncMutex::new(Ads1262::new(SpiDevice::new(spi_bus, chip_select), data_ready, reset, start))
 		});
 
-		Self { drivers }
+		Self {
+			drivers,
+			availability: AsyncMutex::new([AdcAvailability::Undetected; ADC_COUNT]),
+			sd_card_service,
+		}
+	}
+
+	/// Converts one input pair on every given ADC at the same instant and returns the voltages, indexed by ADC.
+	/// Each ADC is armed with its pair before the START pins are raised back to back. The ADCs then convert in parallel
+	/// and only take turns on the SPI bus to read their results, so a pair from every ADC takes about as long as one conversion.
+	/// ADCs without a pair, or that are missing or faulted, are skipped and return None.
+	pub async fn read_differential_synchronized(
+		&self,
+		pairs: [Option<(AnalogChannel, AnalogChannel)>; ADC_COUNT],
+	) -> [Option<Result<Voltage, AdcError>>; ADC_COUNT] {
+		let availability = *self.availability.lock().await;
+
+		// Hold every driver for the whole capture. Locks are taken in index order so concurrent captures can't deadlock
+		let mut drivers: Vec<MutexGuard<'_, CriticalSectionRawMutex, AdcDriver>, ADC_COUNT> = Vec::new();
+		for driver in self.drivers.iter() {
+			let _ = drivers.push(driver.lock().await);
+		}
+
+		// Holds the conversions left to discard for each armed ADC
+		let mut armed: Vec<Option<Result<u32, AdcError>>, ADC_COUNT> = Vec::new();
+		for ((driver, pair), availability) in drivers.iter_mut().zip(pairs).zip(availability) {
+			let arm_result = match pair {
+				Some((positive, negative)) if availability.is_available() => Some(driver.arm_conversion(positive, negative).await),
+				_ => None,
+			};
+			let _ = armed.push(arm_result);
+		}
+
+		// ADCs whose pair changed run their discarded conversions first, then are armed again so the kept conversions still start together
+		let discard_rounds = armed.iter().filter_map(|armed| armed.as_ref()?.as_ref().ok().copied()).max().unwrap_or(0);
+		for round in 0..discard_rounds {
+			for (driver, armed) in drivers.iter_mut().zip(armed.iter()) {
+				if let Some(Ok(discards)) = armed {
+					if *discards > round {
+						driver.trigger_conversion();
+					}
+				}
+			}
+
+			for ((driver, pair), armed) in drivers.iter_mut().zip(pairs).zip(armed.iter_mut()) {
+				if let (Some(Ok(discards)), Some((positive, negative))) = (armed.as_ref(), pair) {
+					if *discards > round {
+						let rearmed = match driver.read_triggered_conversion().await {
+							Ok(_) => driver.arm_conversion(positive, negative).await.map(|_| ()),
+							Err(e) => Err(e),
+						};
+						if let Err(e) = rearmed {
+							*armed = Some(Err(e));
+						}
+					}
+				}
+			}
+		}
+
+		// Raised in a tight loop, so the conversions start within a few instructions of each other
+		for (driver, armed) in drivers.iter_mut().zip(armed.iter()) {
+			if let Some(Ok(_)) = armed {
+				driver.trigger_conversion();
+			}
+		}
+
+		let mut drivers_iter = drivers.iter_mut();
+		let mut armed_iter = armed.into_iter();
+		let reads: [_; ADC_COUNT] = core::array::from_fn(|_| {
+			let driver = drivers_iter.next().unwrap();
+			let armed = armed_iter.next().unwrap();
+			async move {
+				match armed {
+					Some(Ok(_)) => Some(driver.read_triggered_conversion().await),
+					Some(Err(e)) => Some(Err(e)),
+					None => None,
+				}
+			}
+		});
+		join_array(reads).await
 	}
 }
 
@@ -52,17 +148,18 @@ impl<const ADC_COUNT: usize> AdcService<ADC_COUNT> {
 pub struct AdcConfig {
 	pub chip_select: gpio::AnyPin,
 	pub data_ready: gpio::AnyPin,
+	pub data_ready_exti: exti::AnyChannel, // EXTI line matching the data ready pin number so DRDY can be awaited as an interrupt
 	pub reset: gpio::AnyPin,
 	pub start: gpio::AnyPin,
 }
 
 // Type alias for the ADC driver with the specific SPI and GPIO types used within embassy_stm32 instead of embedded_hal
-type AdcDriver = Ads1262<
+pub type AdcDriver = Ads1262<
 	SpiDevice<'static, CriticalSectionRawMutex, spi::Spi<'static, mode::Async>, gpio::Output<'static>>,
-	gpio::Input<'static>,  // Data ready pin (input)
-	gpio::Output<'static>, // Reset pin (output)
-	gpio::Output<'static>, // Start pin (output)
+	exti::ExtiInput<'static>, // Data ready pin (interrupt driven input)
+	gpio::Output<'static>,    // Reset pin (output)
+	gpio::Output<'static>,    // Start pin (output)
 >;
 
-/// The Spi device error type for the ADC driver
-pub type AdcError = SpiDeviceError<spi::Error, Infallible>;
+/// The error type of the ADC driver, wrapping the Spi device error type
+pub type AdcError = Ads1262Error<SpiDeviceError<spi::Error, Infallible>>;
@@ -0,0 +1,23 @@
+use defmt::Format;
+
+// Whether an ADC can be used for measurements, as determined at startup
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Format)]
+pub enum AdcAvailability {
+	// The ADC has not been probed yet
+	Undetected,
+
+	// The ADC answered the probe with a valid ID
+	Available { device_id: u8, revision_id: u8 },
+
+	// The ADC did not answer the probe, it is either not populated or not powered
+	Missing,
+
+	// The ADC answered the probe but could not be configured, e.g. a register did not hold the value written to it
+	Faulted,
+}
+
+impl AdcAvailability {
+	pub fn is_available(&self) -> bool {
+		matches!(self, AdcAvailability::Available { .. })
+	}
+}
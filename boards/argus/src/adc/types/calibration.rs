@@ -0,0 +1,47 @@
+use core::str::FromStr;
+
+use defmt::Format;
+use serde::{Deserialize, Serialize};
+use uor_utils::csv::SerializeCSV;
+
+use crate::adc::driver::types::{CalibrationValues, Gain};
+use crate::adc::types::AdcDevice;
+use crate::sd::config::MAX_LINE_LENGTH;
+use crate::sd::types::Line;
+
+// Offset and full-scale calibration of an ADC at a given gain, as persisted on the SD card
+#[derive(Debug, Clone, Copy, Format, Serialize, Deserialize)]
+pub struct AdcCalibration {
+	pub adc: AdcDevice,
+	pub gain: Gain,
+	pub offset: i32,
+	pub full_scale: u32,
+}
+
+impl AdcCalibration {
+	pub fn new(
+		adc: AdcDevice,
+		gain: Gain,
+		values: CalibrationValues,
+	) -> Self {
+		Self {
+			adc,
+			gain,
+			offset: values.offset,
+			full_scale: values.full_scale,
+		}
+	}
+
+	pub fn values(&self) -> CalibrationValues {
+		CalibrationValues {
+			offset: self.offset,
+			full_scale: self.full_scale,
+		}
+	}
+}
+
+impl SerializeCSV<MAX_LINE_LENGTH> for AdcCalibration {
+	fn get_csv_header() -> Line {
+		Line::from_str("ADC Index,Gain,Offset,Full Scale").unwrap()
+	}
+}
@@ -1,2 +1,11 @@
+pub mod availability;
+pub mod calibration;
 pub mod device;
+pub mod open_circuit;
+pub mod register_snapshot;
+
+pub use availability::*;
+pub use calibration::*;
 pub use device::*;
+pub use open_circuit::*;
+pub use register_snapshot::*;
@@ -0,0 +1,11 @@
+use defmt::Format;
+
+// Result of the last open-circuit (burnout) check of a sensor channel
+#[derive(Debug, Clone, Copy, Format)]
+pub struct OpenCircuitCheck {
+	// Milliseconds since the board's epoch when the check was run
+	pub checked_at: u64,
+
+	// Whether the sensor was found to be disconnected
+	pub is_open: bool,
+}
@@ -0,0 +1,87 @@
+use core::str::FromStr;
+
+use defmt::Format;
+use serde::{Deserialize, Serialize};
+use uor_utils::csv::SerializeCSV;
+
+use crate::adc::driver::types::RegisterSnapshot;
+use crate::adc::types::AdcDevice;
+use crate::sd::config::MAX_LINE_LENGTH;
+use crate::sd::types::Line;
+
+// Register snapshot of an ADC, as written to the session directory
+#[derive(Debug, Clone, Copy, Format, Serialize, Deserialize)]
+pub struct AdcRegisterSnapshot {
+	pub adc: AdcDevice,
+	pub id: u8,
+	pub power: u8,
+	pub interface: u8,
+	pub mode0: u8,
+	pub mode1: u8,
+	pub mode2: u8,
+	pub inpmux: u8,
+	pub offset_calibration: i32,
+	pub full_scale_calibration: u32,
+	pub idacmux: u8,
+	pub idacmag: u8,
+	pub refmux: u8,
+	pub tdacp: u8,
+	pub tdacn: u8,
+	pub gpiocon: u8,
+	pub gpiodir: u8,
+	pub gpiodat: u8,
+}
+
+impl AdcRegisterSnapshot {
+	pub fn new(
+		adc: AdcDevice,
+		snapshot: RegisterSnapshot,
+	) -> Self {
+		Self {
+			adc,
+			id: snapshot.id,
+			power: snapshot.power,
+			interface: snapshot.interface,
+			mode0: snapshot.mode0,
+			mode1: snapshot.mode1,
+			mode2: snapshot.mode2,
+			inpmux: snapshot.inpmux,
+			offset_calibration: snapshot.offset_calibration,
+			full_scale_calibration: snapshot.full_scale_calibration,
+			idacmux: snapshot.idacmux,
+			idacmag: snapshot.idacmag,
+			refmux: snapshot.refmux,
+			tdacp: snapshot.tdacp,
+			tdacn: snapshot.tdacn,
+			gpiocon: snapshot.gpiocon,
+			gpiodir: snapshot.gpiodir,
+			gpiodat: snapshot.gpiodat,
+		}
+	}
+}
+
+impl SerializeCSV<MAX_LINE_LENGTH> for AdcRegisterSnapshot {
+	fn get_csv_header() -> Line {
+		Line::from_str(
+			"ADC Index,\
+			ID,\
+			POWER,\
+			INTERFACE,\
+			MODE0,\
+			MODE1,\
+			MODE2,\
+			INPMUX,\
+			OFCAL,\
+			FSCAL,\
+			IDACMUX,\
+			IDACMAG,\
+			REFMUX,\
+			TDACP,\
+			TDACN,\
+			GPIOCON,\
+			GPIODIR,\
+			GPIODAT",
+		)
+		.unwrap()
+	}
+}
@@ -0,0 +1,9 @@
+// How often the internal monitors of each ADC are read
+pub const BOARD_HEALTH_MEASUREMENT_INTERVAL: u64 = 10000; // milliseconds
+
+// Nominal supply voltages of the ADCs
+pub const ANALOG_SUPPLY_NOMINAL_VOLTAGE: f32 = 5.0; // volts
+pub const DIGITAL_SUPPLY_NOMINAL_VOLTAGE: f32 = 3.3; // volts
+
+// Relative deviation from the nominal voltage above which a supply or the reference is reported as out of tolerance
+pub const VOLTAGE_TOLERANCE: f32 = 0.05;
@@ -0,0 +1,69 @@
+use defmt::warn;
+use embassy_time::Instant;
+use uor_utils::utils::types::AsyncMutex;
+
+use crate::adc::service::{AdcError, AdcService};
+use crate::adc::types::AdcDevice;
+use crate::board_health::config::{ANALOG_SUPPLY_NOMINAL_VOLTAGE, DIGITAL_SUPPLY_NOMINAL_VOLTAGE, VOLTAGE_TOLERANCE};
+use crate::board_health::types::BoardHealthReading;
+use crate::session::service::SessionService;
+
+/// Reads the internal monitors of the ADCs so a sagging supply or drifting reference is noticed during a session
+pub struct BoardHealthService<const ADC_COUNT: usize> {
+	// Other services are passed by a mutex to ensure safe concurrent access
+	pub adc_service: &'static AdcService<ADC_COUNT>,
+	pub session_service: &'static AsyncMutex<SessionService>,
+}
+
+impl<const ADC_COUNT: usize> BoardHealthService<ADC_COUNT> {
+	pub fn new(
+		adc_service: &'static AdcService<ADC_COUNT>,
+		session_service: &'static AsyncMutex<SessionService>,
+	) -> Self {
+		Self {
+			adc_service,
+			session_service,
+		}
+	}
+
+	pub async fn read_board_health(
+		&mut self,
+		adc: AdcDevice,
+	) -> Result<BoardHealthReading, AdcError> {
+		let mut driver = self.adc_service.drivers[adc as usize].lock().await;
+
+		let internal_temperature = driver.read_internal_temperature().await?;
+		let analog_supply_voltage = driver.read_analog_supply_voltage().await?;
+		let digital_supply_voltage = driver.read_digital_supply_voltage().await?;
+		let reference_voltage = driver.read_reference_voltage().await?;
+		let nominal_reference_voltage = driver.reference_range.to_volts();
+		drop(driver);
+
+		if is_out_of_tolerance(analog_supply_voltage, ANALOG_SUPPLY_NOMINAL_VOLTAGE) {
+			warn!("AVDD of {:?} is out of tolerance: {}V", adc, analog_supply_voltage);
+		}
+		if is_out_of_tolerance(digital_supply_voltage, DIGITAL_SUPPLY_NOMINAL_VOLTAGE) {
+			warn!("DVDD of {:?} is out of tolerance: {}V", adc, digital_supply_voltage);
+		}
+		if is_out_of_tolerance(reference_voltage, nominal_reference_voltage) {
+			warn!("Reference of {:?} is out of tolerance: {}V", adc, reference_voltage);
+		}
+
+		Ok(BoardHealthReading {
+			local_session: self.session_service.lock().await.current_session.clone(),
+			adc_device: adc,
+			recorded_at: Instant::now().as_millis(),
+			internal_temperature,
+			analog_supply_voltage,
+			digital_supply_voltage,
+			reference_voltage,
+		})
+	}
+}
+
+fn is_out_of_tolerance(
+	voltage: f32,
+	nominal_voltage: f32,
+) -> bool {
+	((voltage - nominal_voltage) / nominal_voltage).abs() > VOLTAGE_TOLERANCE
+}
@@ -0,0 +1,3 @@
+mod monitor_board_health;
+
+pub use monitor_board_health::*;
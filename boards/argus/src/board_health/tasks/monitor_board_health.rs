@@ -0,0 +1,104 @@
+use defmt::{error, info};
+use embassy_executor::task;
+use embassy_time::Timer;
+use heapless::format;
+use strum::EnumCount;
+use uor_peripherals::serial::peripheral::UORSerial;
+use uor_utils::csv::SerializeCSV;
+use uor_utils::messages::argus::envelope::envelope::Message;
+use uor_utils::utils::types::AsyncMutex;
+
+use crate::adc::types::AdcDevice;
+use crate::board_health::config::BOARD_HEALTH_MEASUREMENT_INTERVAL;
+use crate::board_health::service::BoardHealthService;
+use crate::board_health::types::BoardHealthReading;
+use crate::sd::service::SDCardService;
+use crate::sd::types::{FileName, OperationScope};
+use crate::session::service::SessionService;
+use crate::state_machine::service::StateMachineWorker;
+use crate::state_machine::types::States;
+
+// Task that periodically reads the internal monitors of each ADC, logs them to the SD card and sends them over serial
+// This runs at a slow interval since supplies and the reference only drift slowly
+#[task]
+pub async fn monitor_board_health(
+	mut worker: StateMachineWorker,
+	board_health_service_mutex: &'static AsyncMutex<BoardHealthService<{ AdcDevice::COUNT }>>,
+	serial_service_mutex: &'static AsyncMutex<UORSerial>,
+	sd_card_service_mutex: &'static AsyncMutex<SDCardService>,
+	session_service: &'static AsyncMutex<SessionService>,
+) {
+	worker
+		.run_once(&[States::Recording], async |_| -> Result<(), ()> {
+			initialize_csv_files(board_health_service_mutex, sd_card_service_mutex, session_service).await;
+			Ok(())
+		})
+		.await
+		.unwrap();
+
+	worker
+		.run_while(&[States::Recording], async |_| -> Result<(), ()> {
+			for adc_index in 0..AdcDevice::COUNT {
+				let adc = AdcDevice::from(adc_index);
+				let mut board_health_service = board_health_service_mutex.lock().await;
+
+				// Missing or faulted ADCs are skipped
+				if !board_health_service.adc_service.is_available(adc).await {
+					continue;
+				}
+
+				let result = board_health_service.read_board_health(adc).await;
+				drop(board_health_service);
+				match result {
+					Ok(board_health_reading) => {
+						let path = get_path_from_adc(adc_index);
+						let line = board_health_reading.to_csv_line();
+						SDCardService::enqueue_write(OperationScope::CurrentSession, path, line).await;
+						let _ = serial_service_mutex
+							.lock()
+							.await
+							.write_envelope_message(Message::BoardHealthReading(board_health_reading.to_protobuf()))
+							.await;
+					}
+					Err(e) => {
+						error!("Failed to read board health on {:?}: {:?}", adc, e);
+					}
+				}
+			}
+
+			Timer::after_millis(BOARD_HEALTH_MEASUREMENT_INTERVAL).await;
+
+			Ok(())
+		})
+		.await
+		.unwrap();
+}
+
+// Create the files and write the CSV headers before starting the monitoring loop
+async fn initialize_csv_files(
+	board_health_service_mutex: &'static AsyncMutex<BoardHealthService<{ AdcDevice::COUNT }>>,
+	sd_card_service_mutex: &'static AsyncMutex<SDCardService>,
+	session_service: &'static AsyncMutex<SessionService>,
+) {
+	// Ensure session is set. Ignore if it errors like SD card not mounted, etc.
+	let _ = session_service.lock().await.ensure_session().await;
+
+	info!("Initializing CSV files for board health logging.");
+	let adc_service = board_health_service_mutex.lock().await.adc_service;
+	let mut sd_card_service = sd_card_service_mutex.lock().await;
+	for adc_index in 0..AdcDevice::COUNT {
+		// Missing or faulted ADCs aren't monitored, so no files are created for them
+		if !adc_service.is_available(AdcDevice::from(adc_index)).await {
+			continue;
+		}
+
+		let path = get_path_from_adc(adc_index);
+
+		// Ignore because if the SD card isn't mounted we don't want to panic
+		let _ = sd_card_service.write(OperationScope::CurrentSession, path, BoardHealthReading::get_csv_header());
+	}
+}
+
+fn get_path_from_adc(adc_index: usize) -> FileName {
+	format!("H_{}.csv", adc_index).unwrap() as FileName
+}
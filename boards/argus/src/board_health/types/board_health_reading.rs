@@ -0,0 +1,65 @@
+use core::str::FromStr;
+
+use defmt::Format;
+use serde::{Deserialize, Serialize};
+use uor_utils::csv::SerializeCSV;
+use uor_utils::messages::argus::board_health::board_health_reading::BoardHealthReading as BoardHealthReadingProtobuf;
+
+use crate::adc::types::AdcDevice;
+use crate::sd::config::MAX_LINE_LENGTH;
+use crate::sd::types::Line;
+
+// Represents a single reading of the internal monitors of an ADC
+#[derive(Debug, Clone, Copy, Format, Serialize, Deserialize)]
+pub struct BoardHealthReading {
+	// Local session from the device that took the reading
+	pub local_session: Option<i32>,
+
+	// ADC device whose internal monitors were read
+	pub adc_device: AdcDevice,
+
+	// Milliseconds since the board's epoch when the reading was recorded
+	pub recorded_at: u64,
+
+	// Temperature of the ADC die in degrees Celsius
+	pub internal_temperature: f32,
+
+	// Analog supply voltage (AVDD - AVSS) in volts
+	pub analog_supply_voltage: f32,
+
+	// Digital supply voltage (DVDD) in volts
+	pub digital_supply_voltage: f32,
+
+	// Measured voltage of the reference used for conversions in volts
+	pub reference_voltage: f32,
+}
+
+impl SerializeCSV<MAX_LINE_LENGTH> for BoardHealthReading {
+	fn get_csv_header() -> Line {
+		Line::from_str(
+			"Local Session #,\
+			ADC Device,\
+			Timestamp (ms),\
+			Internal Temperature (C),\
+			Analog Supply Voltage (V),\
+			Digital Supply Voltage (V),\
+			Reference Voltage (V)",
+		)
+		.unwrap()
+	}
+}
+
+impl BoardHealthReading {
+	// Convert to the protobuf representation
+	pub fn to_protobuf(&self) -> BoardHealthReadingProtobuf {
+		BoardHealthReadingProtobuf {
+			local_session: self.local_session,
+			adc_device: self.adc_device.to_protobuf() as i32,
+			recorded_at: self.recorded_at,
+			internal_temperature: self.internal_temperature,
+			analog_supply_voltage: self.analog_supply_voltage,
+			digital_supply_voltage: self.digital_supply_voltage,
+			reference_voltage: self.reference_voltage,
+		}
+	}
+}
@@ -0,0 +1,3 @@
+pub mod board_health_reading;
+
+pub use board_health_reading::*;
@@ -0,0 +1,21 @@
+// Highest order of a polynomial calibration. Normal equations of higher orders are too ill-conditioned to solve in f64
+pub const MAX_POLYNOMIAL_ORDER: usize = 5;
+
+// Number of coefficients of the highest order polynomial calibration, including the constant term
+pub const MAX_POLYNOMIAL_COEFFICIENTS: usize = MAX_POLYNOMIAL_ORDER + 1;
+
+// Maximum number of points of a piecewise-linear lookup table calibration
+pub const MAX_LOOKUP_TABLE_POINTS: usize = 16;
+
+// Maximum number of terms of a calibration read back from the history file, lookup tables have the most
+pub const MAX_CALIBRATION_TERMS: usize = MAX_LOOKUP_TABLE_POINTS;
+
+// Maximum length of the operator notes stored with each calibration, longer notes are cut to keep the record within a line
+pub const MAX_CALIBRATION_NOTES_LENGTH: usize = 48;
+
+// Number of the most recent calibrations of a channel listed from the history file
+pub const MAX_LISTED_CALIBRATIONS: usize = 8;
+
+// Number of readings averaged into each calibration data point, the window must be stable before the data point is taken
+// The stability criteria of each board set how long the window spans
+pub const STABILITY_WINDOW_SIZE: usize = 10;
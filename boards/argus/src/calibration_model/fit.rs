@@ -0,0 +1,124 @@
+use heapless::Vec;
+use num_traits::Float;
+
+use crate::calibration_model::config::{MAX_LOOKUP_TABLE_POINTS, MAX_POLYNOMIAL_COEFFICIENTS};
+
+// Pivots smaller than this fraction of their row are treated as zero, the system doesn't determine a unique solution
+const SINGULAR_PIVOT_RATIO: f64 = 1e-12;
+
+/// Solves the first `size` rows and columns of matrix * x = rhs with Gaussian elimination and partial pivoting.
+/// Returns None if the system is singular.
+pub fn solve_linear_system<const N: usize>(
+	mut matrix: [[f64; N]; N],
+	mut rhs: [f64; N],
+	size: usize,
+) -> Option<[f64; N]> {
+	for pivot in 0..size {
+		let pivot_row = (pivot..size).max_by(|a, b| matrix[*a][pivot].abs().total_cmp(&matrix[*b][pivot].abs()))?;
+		let row_magnitude = matrix[pivot_row][..size].iter().fold(0.0f64, |max, value| max.max(value.abs()));
+		if matrix[pivot_row][pivot].abs() <= SINGULAR_PIVOT_RATIO * row_magnitude {
+			return None;
+		}
+		matrix.swap(pivot, pivot_row);
+		rhs.swap(pivot, pivot_row);
+
+		for row in (pivot + 1)..size {
+			let factor = matrix[row][pivot] / matrix[pivot][pivot];
+			for column in pivot..size {
+				matrix[row][column] -= factor * matrix[pivot][column];
+			}
+			rhs[row] -= factor * rhs[pivot];
+		}
+	}
+
+	// Back substitution
+	let mut solution = [0.0f64; N];
+	for row in (0..size).rev() {
+		let known: f64 = ((row + 1)..size).map(|column| matrix[row][column] * solution[column]).sum();
+		solution[row] = (rhs[row] - known) / matrix[row][row];
+	}
+	Some(solution)
+}
+
+/// Least squares fit of expected = Σ c_i * measured^i for i = 0..=order, given (measured, expected) pairs.
+/// Returns the coefficients from the constant term up, or None if the points don't determine them.
+pub fn fit_polynomial(
+	points: &[(f64, f64)],
+	order: usize,
+) -> Option<Vec<f64, MAX_POLYNOMIAL_COEFFICIENTS>> {
+	let coefficient_count = order + 1;
+	if coefficient_count > MAX_POLYNOMIAL_COEFFICIENTS || points.len() < coefficient_count {
+		return None;
+	}
+
+	// The measured values are centered on their mean and scaled to [-1, 1] before they are raised to powers,
+	// otherwise the normal equations of readings far from zero are too ill-conditioned to solve
+	let center = points.iter().map(|&(measured, _)| measured).sum::<f64>() / points.len() as f64;
+	let half_range = points.iter().fold(0.0f64, |max, &(measured, _)| max.max((measured - center).abs()));
+	if half_range == 0.0 {
+		return None; // Every point has the same measured value
+	}
+
+	// Normal equations (X^T X) c = X^T y, with X[i][j] = u_i^j and u_i = (measured_i - center) / half_range
+	let mut matrix = [[0.0f64; MAX_POLYNOMIAL_COEFFICIENTS]; MAX_POLYNOMIAL_COEFFICIENTS];
+	let mut rhs = [0.0f64; MAX_POLYNOMIAL_COEFFICIENTS];
+	for &(measured, expected) in points.iter() {
+		let normalized = (measured - center) / half_range;
+		let mut powers = [1.0f64; MAX_POLYNOMIAL_COEFFICIENTS];
+		for power in 1..coefficient_count {
+			powers[power] = powers[power - 1] * normalized;
+		}
+		for row in 0..coefficient_count {
+			for column in 0..coefficient_count {
+				matrix[row][column] += powers[row] * powers[column];
+			}
+			rhs[row] += powers[row] * expected;
+		}
+	}
+	let normalized_coefficients = solve_linear_system(matrix, rhs, coefficient_count)?;
+
+	// Expand Σ a_k * ((measured - center) / half_range)^k back into powers of the measured value:
+	// c_j = Σ_{k >= j} a_k / half_range^k * C(k, j) * (-center)^(k - j)
+	let mut coefficients = [0.0f64; MAX_POLYNOMIAL_COEFFICIENTS];
+	let mut scale = 1.0f64; // 1 / half_range^k
+	for (k, normalized_coefficient) in normalized_coefficients.iter().enumerate().take(coefficient_count) {
+		let mut binomial = 1.0f64; // C(k, j)
+		let mut center_power = 1.0f64; // (-center)^(k - j), built from j = k down to 0
+		for j in (0..=k).rev() {
+			coefficients[j] += normalized_coefficient * scale * binomial * center_power;
+			binomial = binomial * j as f64 / (k - j + 1) as f64;
+			center_power *= -center;
+		}
+		scale /= half_range;
+	}
+	Vec::from_slice(&coefficients[..coefficient_count]).ok()
+}
+
+/// Builds a lookup table from (measured, expected) pairs, sorted by the measured value.
+/// Returns None if two points share a measured value, the table would have a vertical segment.
+pub fn build_lookup_table(points: &[(f64, f64)]) -> Option<Vec<(f64, f64), MAX_LOOKUP_TABLE_POINTS>> {
+	let mut table: Vec<(f64, f64), MAX_LOOKUP_TABLE_POINTS> = Vec::from_slice(points).ok()?;
+	table.sort_unstable_by(|a, b| a.0.total_cmp(&b.0));
+	if table.windows(2).any(|segment| segment[0].0 == segment[1].0) {
+		return None;
+	}
+	Some(table)
+}
+
+/// Coefficient of determination of a fit given (expected, fitted) pairs, 1 - residual sum of squares / total sum of squares.
+/// Returns None if there are fewer than two pairs or the expected values don't vary, R² isn't defined then.
+pub fn compute_r_squared<V: Float>(pairs: impl Iterator<Item = (V, V)> + Clone) -> Option<V> {
+	let (count, sum) = pairs.clone().fold((0usize, V::zero()), |(count, sum), (expected, _)| (count + 1, sum + expected));
+	if count < 2 {
+		return None;
+	}
+
+	let mean = sum / V::from(count)?;
+	let (residual_sum_of_squares, total_sum_of_squares) = pairs.fold((V::zero(), V::zero()), |(residual, total), (expected, fitted)| {
+		(residual + (expected - fitted).powi(2), total + (expected - mean).powi(2))
+	});
+	if total_sum_of_squares == V::zero() {
+		return None;
+	}
+	Some(V::one() - residual_sum_of_squares / total_sum_of_squares)
+}
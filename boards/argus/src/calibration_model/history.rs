@@ -0,0 +1,272 @@
+use core::str::FromStr;
+
+use defmt::{error, info};
+use embassy_time::Instant;
+use heapless::{String, Vec};
+use uor_utils::csv::SerializeCSV;
+
+use crate::adc::types::AdcDevice;
+use crate::calibration_model::config::{MAX_CALIBRATION_NOTES_LENGTH, MAX_LISTED_CALIBRATIONS};
+use crate::calibration_model::fit::compute_r_squared;
+use crate::calibration_model::service::CalibrationModelService;
+use crate::calibration_model::types::{
+	CalibrationModel, CalibrationModelType, CalibrationPoint, CalibrationRecord, CalibrationRecordTerm, CalibrationRecordTerms, CalibrationResidual,
+	ChannelMarker, ChannelValueMarker,
+};
+use crate::sd::types::{FileName, OperationScope, SdCardError};
+
+// History logic has been separated into its own file for clarity
+// Each calibration is stored as a record line followed by one line per term of its model and one line per data point.
+// Lines are told apart by their number of fields, so records are parsed first, then residuals and then terms.
+impl<Channel, ChannelValue, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>
+	CalibrationModelService<Channel, ChannelValue, ADC_COUNT, CHANNEL_COUNT>
+where
+	Channel: ChannelMarker,
+	ChannelValue: ChannelValueMarker,
+{
+	/// Saves the model as the calibration of the channel and records it in the history file.
+	/// Points are the (value_with_error, corrected_value) pairs the model was fitted to, their residuals are recorded with it.
+	/// Uncertainties are the standard errors of the measured values, in the order of the points.
+	pub async fn save_calibration(
+		&mut self,
+		adc: AdcDevice,
+		channel: Channel,
+		model: CalibrationModel<ChannelValue>,
+		points: &[(ChannelValue, ChannelValue)],
+		uncertainties: &[ChannelValue],
+		local_session: Option<i32>,
+		notes: &str,
+	) -> Result<CalibrationRecord<Channel, ChannelValue>, SdCardError> {
+		self.save_model(adc, channel, model.clone()).await?;
+
+		let points = points.iter().enumerate().map(|(index, &(measured, expected))| CalibrationPoint {
+			measured,
+			expected,
+			calibrated: model.apply(measured),
+			uncertainty: uncertainties.get(index).copied().unwrap_or(ChannelValue::zero()),
+		});
+		self.record_calibration(adc, channel, model.model_type(), model.terms(), points, local_session, notes)
+			.await
+	}
+
+	/// Records a calibration in the history file, followed by its terms and the residuals of its data points.
+	/// Calibrations that aren't models of the channel, like the temperature compensations of the pressure transducers, are only recorded
+	/// here and saved by their service. Terms are the (input, output) pairs of the calibration, see CalibrationTerm.
+	pub async fn record_calibration(
+		&mut self,
+		adc: AdcDevice,
+		channel: Channel,
+		model: CalibrationModelType,
+		terms: impl Iterator<Item = (ChannelValue, ChannelValue)> + Clone,
+		points: impl Iterator<Item = CalibrationPoint<ChannelValue>> + Clone,
+		local_session: Option<i32>,
+		notes: &str,
+	) -> Result<CalibrationRecord<Channel, ChannelValue>, SdCardError> {
+		let point_count = points.clone().count();
+		let term_count = terms.clone().count();
+
+		// A calibration with as many parameters as data points passes through every point, its R² would always be 1
+		let r_squared = match point_count > model.parameter_count(term_count) {
+			true => compute_r_squared(points.clone().map(|point| (point.expected, point.calibrated))),
+			false => None,
+		};
+
+		let record = CalibrationRecord {
+			id: self.next_calibration_id().await?,
+			adc,
+			channel,
+			model,
+			point_count: point_count as u8,
+			term_count: term_count as u8,
+			r_squared,
+			local_session,
+			created_at: Instant::now().as_millis(),
+			notes: to_record_notes(notes),
+		};
+		info!(
+			"Saving calibration #{} to the history: {:?}, {} points, R squared = {:?}",
+			record.id, record.model, record.point_count, record.r_squared
+		);
+
+		let mut sd_card_service = self.sd_card_service.lock().await;
+		let path = FileName::from_str(self.history_file_name).unwrap();
+		if !(sd_card_service.file_exists(OperationScope::Root, path.clone())?) {
+			sd_card_service.write(
+				OperationScope::Root,
+				path.clone(),
+				CalibrationRecord::<Channel, ChannelValue>::get_csv_header(),
+			)?;
+		}
+
+		sd_card_service.write(OperationScope::Root, path.clone(), record.to_csv_line())?;
+		for (index, (input, output)) in terms.enumerate() {
+			let term = CalibrationRecordTerm {
+				id: record.id,
+				index: index as u8,
+				input,
+				output,
+			};
+			sd_card_service.write(OperationScope::Root, path.clone(), term.to_csv_line())?;
+		}
+		for (index, point) in points.enumerate() {
+			let residual = CalibrationResidual {
+				id: record.id,
+				index: index as u8,
+				measured: point.measured,
+				expected: point.expected,
+				residual: point.expected - point.calibrated,
+				uncertainty: point.uncertainty,
+			};
+			sd_card_service.write(OperationScope::Root, path.clone(), residual.to_csv_line())?;
+		}
+
+		Ok(record)
+	}
+
+	/// Reads the most recent calibrations of the channel from the history file, oldest first.
+	pub async fn load_calibration_history(
+		&self,
+		adc: AdcDevice,
+		channel: Channel,
+	) -> Result<Vec<CalibrationRecord<Channel, ChannelValue>, MAX_LISTED_CALIBRATIONS>, SdCardError> {
+		let mut records: Vec<CalibrationRecord<Channel, ChannelValue>, MAX_LISTED_CALIBRATIONS> = Vec::new();
+		let result = self
+			.sd_card_service
+			.lock()
+			.await
+			.read(OperationScope::Root, FileName::from_str(self.history_file_name).unwrap(), |line| {
+				if let Ok(record) = CalibrationRecord::<Channel, ChannelValue>::from_csv_line(line) {
+					if record.adc == adc && record.channel == channel {
+						// Drop the oldest record to make room for the newer one
+						if records.is_full() {
+							records.remove(0);
+						}
+						let _ = records.push(record);
+					}
+				}
+				true // Continue reading
+			});
+
+		match result {
+			Ok(_) | Err(SdCardError::NotFound) => Ok(records),
+			Err(e) => Err(e),
+		}
+	}
+
+	/// Saves the calibration with the given id from the history file as the calibration of the channel again.
+	/// Returns None if the history has no calibration with that id for the channel, if some of its terms are missing,
+	/// or if it isn't a model of the channel.
+	pub async fn restore_calibration(
+		&mut self,
+		adc: AdcDevice,
+		channel: Channel,
+		id: u16,
+	) -> Result<Option<CalibrationRecord<Channel, ChannelValue>>, SdCardError> {
+		match self.load_calibration(adc, channel, id).await? {
+			Some((record, terms)) => self.restore_model(adc, channel, record, &terms).await,
+			None => Ok(None),
+		}
+	}
+
+	/// Saves the model of a calibration read with load_calibration as the calibration of the channel again.
+	/// Returns None if the calibration isn't a model of the channel, e.g. a temperature compensation, or its terms don't make one.
+	pub async fn restore_model(
+		&mut self,
+		adc: AdcDevice,
+		channel: Channel,
+		record: CalibrationRecord<Channel, ChannelValue>,
+		terms: &[(ChannelValue, ChannelValue)],
+	) -> Result<Option<CalibrationRecord<Channel, ChannelValue>>, SdCardError> {
+		let Some(mut model) = CalibrationModel::with_type(record.model) else {
+			error!("Calibration #{} is a {:?} calibration, not a model of the channel", record.id, record.model);
+			return Ok(None);
+		};
+		for (index, &(input, output)) in terms.iter().enumerate() {
+			if !model.push_term(index as u8, input, output) {
+				error!("Calibration #{} has too many terms for its model", record.id);
+				return Ok(None);
+			}
+		}
+
+		info!("Restoring calibration #{} from the history", record.id);
+		self.save_model(adc, channel, model).await?;
+		Ok(Some(record))
+	}
+
+	/// Reads the calibration with the given id back from the history file, with its terms as (input, output) pairs in order.
+	/// Returns None if the history has no calibration with that id for the channel, or if some of its terms are missing.
+	pub async fn load_calibration(
+		&self,
+		adc: AdcDevice,
+		channel: Channel,
+		id: u16,
+	) -> Result<Option<(CalibrationRecord<Channel, ChannelValue>, CalibrationRecordTerms<ChannelValue>)>, SdCardError> {
+		let mut record: Option<CalibrationRecord<Channel, ChannelValue>> = None;
+		let mut terms: CalibrationRecordTerms<ChannelValue> = Vec::new();
+		let mut is_complete = true;
+		let result = self
+			.sd_card_service
+			.lock()
+			.await
+			.read(OperationScope::Root, FileName::from_str(self.history_file_name).unwrap(), |line| {
+				if let Ok(line_record) = CalibrationRecord::<Channel, ChannelValue>::from_csv_line(line) {
+					if line_record.id == id && line_record.adc == adc && line_record.channel == channel {
+						record = Some(line_record);
+					}
+					return true; // Continue reading
+				}
+				if CalibrationResidual::<ChannelValue>::from_csv_line(line).is_ok() {
+					return true; // Residuals aren't needed to restore the calibration
+				}
+
+				if let (Some(_), Ok(term)) = (record.as_ref(), CalibrationRecordTerm::<ChannelValue>::from_csv_line(line)) {
+					if term.id == id && (term.index as usize != terms.len() || terms.push((term.input, term.output)).is_err()) {
+						error!("Calibration term out of order, ignoring line '{}'", line.as_str());
+						is_complete = false;
+					}
+				}
+				true // Continue reading
+			});
+
+		match result {
+			Ok(_) | Err(SdCardError::NotFound) => (),
+			Err(e) => return Err(e),
+		}
+
+		match record {
+			Some(record) if terms.len() == record.term_count as usize && is_complete => Ok(Some((record, terms))),
+			_ => Ok(None),
+		}
+	}
+
+	// Calibrations are numbered from 0 in the order they are saved, the next one follows the highest id in the history file
+	async fn next_calibration_id(&self) -> Result<u16, SdCardError> {
+		let mut next_id: u16 = 0;
+		let result = self
+			.sd_card_service
+			.lock()
+			.await
+			.read(OperationScope::Root, FileName::from_str(self.history_file_name).unwrap(), |line| {
+				if let Ok(record) = CalibrationRecord::<Channel, ChannelValue>::from_csv_line(line) {
+					next_id = next_id.max(record.id.saturating_add(1));
+				}
+				true // Continue reading
+			});
+
+		match result {
+			Ok(_) | Err(SdCardError::NotFound) => Ok(next_id),
+			Err(e) => Err(e),
+		}
+	}
+}
+
+// Cuts the notes to fit the record and replaces double quotes, which the CSV writer would escape past the maximum line length
+fn to_record_notes(notes: &str) -> String<MAX_CALIBRATION_NOTES_LENGTH> {
+	let mut record_notes = String::new();
+	for character in notes.chars().map(|character| if character == '"' { '\'' } else { character }) {
+		if record_notes.push(character).is_err() {
+			break;
+		}
+	}
+	record_notes
+}
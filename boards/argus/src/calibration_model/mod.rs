@@ -0,0 +1,6 @@
+pub mod config;
+pub mod fit;
+pub mod history;
+pub mod service;
+pub mod stability;
+pub mod types;
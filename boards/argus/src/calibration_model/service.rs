@@ -0,0 +1,271 @@
+use core::str::FromStr;
+
+use defmt::{error, info};
+use heapless::LinearMap;
+use uor_utils::csv::SerializeCSV;
+use uor_utils::utils::types::AsyncMutex;
+
+use crate::adc::types::AdcDevice;
+use crate::calibration_model::types::{CalibrationModel, CalibrationTerm, ChannelMarker, ChannelValueMarker, LinearTransformation};
+use crate::sd::service::SDCardService;
+use crate::sd::types::{FileName, OperationScope, SdCardError};
+
+// SHOULD DO: cleanup the trait bounds
+pub struct CalibrationModelService<Channel, ChannelValue, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>
+where
+	Channel: ChannelMarker,
+	ChannelValue: ChannelValueMarker, {
+	pub sd_card_service: &'static AsyncMutex<SDCardService>,
+	pub file_name: &'static str,
+
+	// File that keeps every saved calibration with its fit quality, so previous calibrations can be listed and restored
+	pub history_file_name: &'static str,
+
+	// Calibration models that are applied on top of the raw readings for each ADC and channel
+	pub models: LinearMap<AdcDevice, LinearMap<Channel, CalibrationModel<ChannelValue>, CHANNEL_COUNT>, ADC_COUNT>,
+}
+
+impl<Channel, ChannelValue, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>
+	CalibrationModelService<Channel, ChannelValue, ADC_COUNT, CHANNEL_COUNT>
+where
+	Channel: ChannelMarker,
+	ChannelValue: ChannelValueMarker,
+{
+	pub fn new(
+		sd_card_service: &'static AsyncMutex<SDCardService>,
+		file_name: &'static str,
+		history_file_name: &'static str,
+	) -> Self {
+		Self {
+			sd_card_service,
+			file_name,
+			history_file_name,
+			models: LinearMap::default(),
+		}
+	}
+
+	pub async fn load_models(&mut self) -> Result<(), SdCardError> {
+		// Terms of the model being read, it replaces the previous model of its channel once all of its terms have been read
+		let mut pending_model: Option<PendingModel<Channel, ChannelValue>> = None;
+		let result = self
+			.sd_card_service
+			.lock()
+			.await
+			.read(OperationScope::Root, FileName::from_str(self.file_name).unwrap(), |line| {
+				if *line == LinearTransformation::<Channel, ChannelValue>::get_csv_header() {
+					return true; // Skip header line
+				}
+
+				// Polynomial and lookup table terms are tried first, lines written before they existed only hold linear transformations
+				if let Ok(term) = CalibrationTerm::<Channel, ChannelValue>::from_csv_line(line) {
+					if term.index == 0 {
+						discard_incomplete_model(pending_model.take());
+						pending_model = CalibrationModel::with_type(term.model).map(|model| PendingModel {
+							adc: term.adc,
+							channel: term.channel,
+							term_count: term.term_count,
+							model,
+						});
+					}
+
+					match pending_model.as_mut().map(|pending| pending.push_term(term)) {
+						Some(true) => info!("Loaded calibration term: {:?}", term),
+						_ => {
+							error!("Calibration term out of order, ignoring line '{}'", line.as_str());
+							pending_model = None;
+						}
+					}
+
+					if let Some(pending) = pending_model.take_if(|pending| pending.is_complete()) {
+						self.register_model(pending.adc, pending.channel, pending.model);
+					}
+					return true; // Continue reading
+				}
+
+				// Any other line ends the terms of the model being read
+				discard_incomplete_model(pending_model.take());
+
+				let result = LinearTransformation::<Channel, ChannelValue>::from_csv_line(line);
+				match result {
+					Ok(transformation) => {
+						self.register_transformation(transformation);
+						info!("Loaded linear transformation: {:?}", transformation);
+					}
+					Err(e) => {
+						error!("Error parsing linear transformation for line '{}': {:?}", line.as_str(), e);
+					}
+				}
+				true // Continue reading
+			});
+
+		discard_incomplete_model(pending_model);
+
+		match result {
+			Ok(_) => (),
+			Err(SdCardError::NotFound) => {
+				// If no channel has been calibrated yet, keep using the defaults and ignore this error.
+				info!("Calibration models file not found, using defaults. Gain = 1, Offset = 0");
+			}
+			Err(e) => return Err(e),
+		}
+		Ok(())
+	}
+
+	pub fn register_transformation(
+		&mut self,
+		transformation: LinearTransformation<Channel, ChannelValue>,
+	) {
+		self.register_model(transformation.adc, transformation.channel, transformation.into());
+	}
+
+	pub fn register_model(
+		&mut self,
+		adc: AdcDevice,
+		channel: Channel,
+		model: CalibrationModel<ChannelValue>,
+	) {
+		if !self.models.contains_key(&adc) {
+			let _ = self.models.insert(adc, LinearMap::new());
+		}
+		let map = self.models.get_mut(&adc).unwrap();
+		let _ = map.insert(channel, model);
+	}
+
+	pub fn apply_model(
+		&self,
+		adc: AdcDevice,
+		channel: Channel,
+		raw_value: ChannelValue,
+	) -> ChannelValue {
+		if let Some(channel_map) = self.models.get(&adc) {
+			if let Some(model) = channel_map.get(&channel) {
+				return model.apply(raw_value);
+			}
+		}
+		raw_value // If no calibration found, return the raw value
+	}
+
+	pub fn get_model(
+		&self,
+		adc: AdcDevice,
+		channel: Channel,
+	) -> Option<&CalibrationModel<ChannelValue>> {
+		self.models.get(&adc).and_then(|channel_map| channel_map.get(&channel))
+	}
+
+	/// Stops applying the model of the channel until it is registered again or reloaded. Returns the removed model.
+	pub fn deregister_model(
+		&mut self,
+		adc: AdcDevice,
+		channel: Channel,
+	) -> Option<CalibrationModel<ChannelValue>> {
+		self.models.get_mut(&adc).and_then(|channel_map| channel_map.remove(&channel))
+	}
+
+	pub async fn save_transformation(
+		&mut self,
+		transformation: LinearTransformation<Channel, ChannelValue>,
+	) -> Result<(), SdCardError> {
+		info!("Saving linear transformation: {:?}", transformation);
+		let mut sd_card_service = self.sd_card_service.lock().await;
+		let path = FileName::from_str(self.file_name).unwrap();
+		if !(sd_card_service.file_exists(OperationScope::Root, path.clone())?) {
+			sd_card_service.write(
+				OperationScope::Root,
+				path.clone(),
+				LinearTransformation::<Channel, ChannelValue>::get_csv_header(),
+			)?;
+		}
+
+		sd_card_service.write(OperationScope::Root, path.clone(), transformation.to_csv_line())?;
+		self.register_transformation(transformation);
+
+		Ok(())
+	}
+
+	/// Saves the model as the calibration of the channel, it replaces the previous one from the next load.
+	/// Linear models are saved as linear transformations, the other models one term per line.
+	pub async fn save_model(
+		&mut self,
+		adc: AdcDevice,
+		channel: Channel,
+		model: CalibrationModel<ChannelValue>,
+	) -> Result<(), SdCardError> {
+		if let CalibrationModel::Linear { scale, offset } = model {
+			return self.save_transformation(LinearTransformation { adc, channel, scale, offset }).await;
+		}
+
+		let model_type = model.model_type();
+		let term_count = model.term_count() as u8;
+		{
+			let mut sd_card_service = self.sd_card_service.lock().await;
+			let path = FileName::from_str(self.file_name).unwrap();
+			if !(sd_card_service.file_exists(OperationScope::Root, path.clone())?) {
+				sd_card_service.write(
+					OperationScope::Root,
+					path.clone(),
+					LinearTransformation::<Channel, ChannelValue>::get_csv_header(),
+				)?;
+			}
+
+			for (index, (input, output)) in model.terms().enumerate() {
+				let term = CalibrationTerm {
+					adc,
+					channel,
+					model: model_type,
+					index: index as u8,
+					term_count,
+					input,
+					output,
+				};
+				info!("Saving calibration term: {:?}", term);
+				sd_card_service.write(OperationScope::Root, path.clone(), term.to_csv_line())?;
+			}
+		}
+		self.register_model(adc, channel, model);
+
+		Ok(())
+	}
+}
+
+// A model whose terms are being read from the file
+struct PendingModel<Channel, ChannelValue> {
+	adc: AdcDevice,
+	channel: Channel,
+	term_count: u8,
+	model: CalibrationModel<ChannelValue>,
+}
+
+impl<Channel, ChannelValue> PendingModel<Channel, ChannelValue>
+where
+	Channel: ChannelMarker,
+	ChannelValue: ChannelValueMarker,
+{
+	// Adds the term to the model, returns false if it belongs to another model or doesn't follow the previous term
+	fn push_term(
+		&mut self,
+		term: CalibrationTerm<Channel, ChannelValue>,
+	) -> bool {
+		term.adc == self.adc
+			&& term.channel == self.channel
+			&& term.term_count == self.term_count
+			&& term.model == self.model.model_type()
+			&& self.model.push_term(term.index, term.input, term.output)
+	}
+
+	fn is_complete(&self) -> bool {
+		self.model.term_count() == self.term_count as usize
+	}
+}
+
+// A model whose terms ended before all of them were read is ignored, the previous model of its channel is kept
+fn discard_incomplete_model<Channel, ChannelValue>(pending_model: Option<PendingModel<Channel, ChannelValue>>)
+where
+	Channel: ChannelMarker, {
+	if let Some(incomplete) = pending_model {
+		error!(
+			"Calibration model of {:?} {:?} is missing terms, ignoring it",
+			incomplete.adc, incomplete.channel
+		);
+	}
+}
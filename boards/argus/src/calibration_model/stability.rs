@@ -0,0 +1,80 @@
+use heapless::Deque;
+use libm::sqrt;
+
+use crate::calibration_model::types::{StabilityCriteria, StableReading};
+
+/// Sliding window over the latest readings of a channel, used to wait for the calibration reference to settle
+/// before a data point is taken. Once full, each new reading replaces the oldest one.
+#[derive(Default)]
+pub struct StabilityWindow<const WINDOW_SIZE: usize> {
+	readings: Deque<f64, WINDOW_SIZE>,
+}
+
+impl<const WINDOW_SIZE: usize> StabilityWindow<WINDOW_SIZE> {
+	pub fn push(
+		&mut self,
+		reading: f64,
+	) {
+		if self.readings.is_full() {
+			self.readings.pop_front();
+		}
+		let _ = self.readings.push_back(reading);
+	}
+
+	pub fn is_full(&self) -> bool {
+		self.readings.is_full()
+	}
+
+	pub fn mean(&self) -> f64 {
+		mean(self.readings.iter())
+	}
+
+	/// Sample standard deviation of the readings in the window, 0 with fewer than two readings.
+	pub fn standard_deviation(&self) -> f64 {
+		let count = self.readings.len();
+		if count < 2 {
+			return 0.0;
+		}
+		let mean = self.mean();
+		let sum_of_squares: f64 = self.readings.iter().map(|reading| (reading - mean) * (reading - mean)).sum();
+		sqrt(sum_of_squares / (count - 1) as f64)
+	}
+
+	/// Difference between the mean of the newest half and the oldest half of the window.
+	/// A reference that is still moving shows up here even when the readings around the trend are quiet.
+	pub fn drift(&self) -> f64 {
+		let half = self.readings.len() / 2;
+		if half == 0 {
+			return 0.0;
+		}
+		mean(self.readings.iter().skip(self.readings.len() - half)) - mean(self.readings.iter().take(half))
+	}
+
+	/// Whether the window is full and its standard deviation and drift are within the criteria.
+	pub fn is_stable(
+		&self,
+		criteria: &StabilityCriteria,
+	) -> bool {
+		self.is_full() && self.standard_deviation() <= criteria.max_standard_deviation && self.drift().abs() <= criteria.max_drift
+	}
+
+	/// Averages the window into a single reading, its uncertainty is the standard error of the mean.
+	pub fn to_stable_reading(&self) -> StableReading {
+		let count = self.readings.len();
+		let standard_deviation = self.standard_deviation();
+		StableReading {
+			mean: self.mean(),
+			standard_deviation,
+			uncertainty: if count > 0 { standard_deviation / sqrt(count as f64) } else { 0.0 },
+			reading_count: count as u8,
+		}
+	}
+}
+
+fn mean<'a>(readings: impl Iterator<Item = &'a f64>) -> f64 {
+	let (count, sum) = readings.fold((0usize, 0.0), |(count, sum), reading| (count + 1, sum + reading));
+	if count == 0 {
+		return 0.0;
+	}
+	sum / count as f64
+}
@@ -0,0 +1,465 @@
+use core::fmt::Debug;
+use core::hash::Hash;
+use core::str::FromStr;
+
+use defmt::Format;
+use heapless::{format, String, Vec};
+use num_traits::Float;
+use serde::{Deserialize, Serialize};
+use strum::EnumCount;
+use uor_utils::csv::SerializeCSV;
+use uor_utils::messages::argus::calibration::calibration_model::CalibrationModel as CalibrationModelProtobuf;
+
+use crate::adc::types::AdcDevice;
+use crate::calibration_model::config::{MAX_CALIBRATION_NOTES_LENGTH, MAX_CALIBRATION_TERMS, MAX_LOOKUP_TABLE_POINTS, MAX_POLYNOMIAL_COEFFICIENTS};
+use crate::calibration_model::fit::compute_r_squared;
+use crate::sd::config::MAX_LINE_LENGTH;
+use crate::sd::types::Line;
+
+// Represents a linear transformation applied to a sensor reading
+// corrected_value = value_with_error * scale + offset
+#[derive(Debug, Clone, Copy, Format, Serialize, Deserialize)]
+pub struct LinearTransformation<Channel, ChannelValue> {
+	pub adc: AdcDevice,
+	pub channel: Channel,
+	pub scale: ChannelValue,
+	pub offset: ChannelValue,
+}
+
+impl<Channel, ChannelValue> LinearTransformation<Channel, ChannelValue>
+where
+	Channel: ChannelMarker,
+	ChannelValue: ChannelValueMarker,
+{
+	pub fn apply(
+		&self,
+		raw_value: ChannelValue,
+	) -> ChannelValue {
+		raw_value * self.scale + self.offset
+	}
+}
+
+impl<Channel, ChannelValue> Default for LinearTransformation<Channel, ChannelValue>
+where
+	Channel: ChannelMarker,
+	ChannelValue: ChannelValueMarker,
+{
+	fn default() -> Self {
+		Self {
+			adc: AdcDevice::AdcDevice1, // Default to ADC1
+			channel: Channel::default(),
+			scale: ChannelValue::one(),   // Default to unity gain
+			offset: ChannelValue::zero(), // Default to zero offset
+		}
+	}
+}
+
+impl<Channel, ChannelValue> SerializeCSV<MAX_LINE_LENGTH> for LinearTransformation<Channel, ChannelValue>
+where
+	Channel: ChannelMarker,
+	ChannelValue: ChannelValueMarker,
+{
+	fn get_csv_header() -> Line {
+		Line::from_str("ADC Index,Channel Index,Scale,Offset").unwrap()
+	}
+}
+
+// Calibration applied to the readings of a single channel
+#[derive(Debug, Clone)]
+pub enum CalibrationModel<ChannelValue> {
+	// corrected_value = value_with_error * scale + offset
+	Linear { scale: ChannelValue, offset: ChannelValue },
+
+	// corrected_value = Σ coefficients[i] * value_with_error^i
+	Polynomial { coefficients: Vec<ChannelValue, MAX_POLYNOMIAL_COEFFICIENTS> },
+
+	// Piecewise-linear interpolation between (value_with_error, corrected_value) points sorted by value_with_error
+	// Values outside the table are extrapolated from the first or last segment
+	LookupTable { points: Vec<(ChannelValue, ChannelValue), MAX_LOOKUP_TABLE_POINTS> },
+}
+
+impl<ChannelValue> CalibrationModel<ChannelValue>
+where
+	ChannelValue: ChannelValueMarker,
+{
+	/// Starts a model of the given type, its terms are added in order with `push_term`.
+	/// Returns None for the types that aren't models of the channel, see CalibrationModelType.
+	pub fn with_type(model_type: CalibrationModelType) -> Option<Self> {
+		match model_type {
+			CalibrationModelType::Linear => Some(CalibrationModel::Linear {
+				scale: ChannelValue::one(),
+				offset: ChannelValue::zero(),
+			}),
+			CalibrationModelType::Polynomial => Some(CalibrationModel::Polynomial { coefficients: Vec::new() }),
+			CalibrationModelType::LookupTable => Some(CalibrationModel::LookupTable { points: Vec::new() }),
+			CalibrationModelType::TemperatureCompensated => None,
+		}
+	}
+
+	pub fn model_type(&self) -> CalibrationModelType {
+		match self {
+			CalibrationModel::Linear { .. } => CalibrationModelType::Linear,
+			CalibrationModel::Polynomial { .. } => CalibrationModelType::Polynomial,
+			CalibrationModel::LookupTable { .. } => CalibrationModelType::LookupTable,
+		}
+	}
+
+	/// Adds the term at index to the model, see `CalibrationTerm` for the meaning of input and output.
+	/// Returns false if the term doesn't follow the previous term of the model.
+	pub fn push_term(
+		&mut self,
+		index: u8,
+		input: ChannelValue,
+		output: ChannelValue,
+	) -> bool {
+		match self {
+			CalibrationModel::Linear { scale, offset } if index == 0 => {
+				*scale = input;
+				*offset = output;
+				true
+			}
+			CalibrationModel::Polynomial { coefficients } if coefficients.len() == index as usize => coefficients.push(output).is_ok(),
+			CalibrationModel::LookupTable { points } if points.len() == index as usize => points.push((input, output)).is_ok(),
+			_ => false,
+		}
+	}
+
+	/// Number of terms the model is stored as, see `CalibrationTerm`.
+	pub fn term_count(&self) -> usize {
+		self.terms().count()
+	}
+
+	/// Terms of the model as (input, output) pairs in the order they are stored, see `CalibrationTerm`.
+	pub fn terms(&self) -> impl Iterator<Item = (ChannelValue, ChannelValue)> + Clone + '_ {
+		let linear = match self {
+			CalibrationModel::Linear { scale, offset } => Some((*scale, *offset)),
+			_ => None,
+		};
+		let coefficients: &[ChannelValue] = match self {
+			CalibrationModel::Polynomial { coefficients } => coefficients,
+			_ => &[],
+		};
+		let points: &[(ChannelValue, ChannelValue)] = match self {
+			CalibrationModel::LookupTable { points } => points,
+			_ => &[],
+		};
+		linear
+			.into_iter()
+			.chain(coefficients.iter().map(|coefficient| (ChannelValue::zero(), *coefficient)))
+			.chain(points.iter().copied())
+	}
+
+	/// Coefficient of determination of the model over (value_with_error, corrected_value) points.
+	/// Returns None if the corrected values don't vary, or if the model has as many parameters as there are points.
+	/// Such a model passes through every point, e.g. a lookup table or a line through two points, so its R² is always 1 and says nothing.
+	pub fn r_squared(
+		&self,
+		points: &[(ChannelValue, ChannelValue)],
+	) -> Option<ChannelValue> {
+		if points.len() <= self.parameter_count() {
+			return None;
+		}
+		compute_r_squared(points.iter().map(|&(measured, expected)| (expected, self.apply(measured))))
+	}
+
+	// Number of values fitted to the data points
+	fn parameter_count(&self) -> usize {
+		self.model_type().parameter_count(self.term_count())
+	}
+
+	pub fn apply(
+		&self,
+		raw_value: ChannelValue,
+	) -> ChannelValue {
+		match self {
+			CalibrationModel::Linear { scale, offset } => raw_value * *scale + *offset,
+			CalibrationModel::Polynomial { coefficients } => coefficients
+				.iter()
+				.rev()
+				.fold(ChannelValue::zero(), |accumulator, coefficient| accumulator * raw_value + *coefficient),
+			CalibrationModel::LookupTable { points } => {
+				if points.len() < 2 {
+					return raw_value; // A single point doesn't define a segment
+				}
+
+				// First segment whose upper end is above the value, or the last segment to extrapolate past the table
+				let segment = points.windows(2).position(|segment| raw_value <= segment[1].0).unwrap_or(points.len() - 2);
+				let (x0, y0) = points[segment];
+				let (x1, y1) = points[segment + 1];
+				y0 + (raw_value - x0) * (y1 - y0) / (x1 - x0)
+			}
+		}
+	}
+}
+
+impl<Channel, ChannelValue> From<LinearTransformation<Channel, ChannelValue>> for CalibrationModel<ChannelValue> {
+	fn from(transformation: LinearTransformation<Channel, ChannelValue>) -> Self {
+		CalibrationModel::Linear {
+			scale: transformation.scale,
+			offset: transformation.offset,
+		}
+	}
+}
+
+// Type of a calibration model, the polynomials and lookup tables are stored one term per line
+// Temperature compensations of the pressure transducers are only recorded in the history, the pressure service keeps them in their own file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Format, Serialize, Deserialize)]
+pub enum CalibrationModelType {
+	Linear,
+	Polynomial,
+	LookupTable,
+	TemperatureCompensated,
+}
+
+impl CalibrationModelType {
+	pub fn to_protobuf(&self) -> CalibrationModelProtobuf {
+		match self {
+			CalibrationModelType::Linear => CalibrationModelProtobuf::Linear,
+			CalibrationModelType::Polynomial => CalibrationModelProtobuf::Polynomial,
+			CalibrationModelType::LookupTable => CalibrationModelProtobuf::LookupTable,
+			CalibrationModelType::TemperatureCompensated => CalibrationModelProtobuf::TemperatureCompensated,
+		}
+	}
+
+	/// Name of the model in the calibration prompts.
+	pub fn label(&self) -> &'static str {
+		match self {
+			CalibrationModelType::Linear => "Linear",
+			CalibrationModelType::Polynomial => "Polynomial",
+			CalibrationModelType::LookupTable => "Lookup table",
+			CalibrationModelType::TemperatureCompensated => "Temperature compensated",
+		}
+	}
+
+	/// Number of values fitted to the data points by a calibration of this type stored as term_count terms.
+	/// A linear model is a single term holding two values, a lookup table holds every point it was built from.
+	pub fn parameter_count(
+		&self,
+		term_count: usize,
+	) -> usize {
+		match self {
+			CalibrationModelType::Linear => 2,
+			_ => term_count,
+		}
+	}
+}
+
+// A single term of a calibration model, stored in the same file as the linear transformations.
+// Linear: a single term, input is the scale and output is the offset. Linear models are saved as linear transformations instead.
+// Polynomial: output is the coefficient of value_with_error^index, input is unused.
+// Lookup table: input and output are the point at index, in increasing order of input.
+// Temperature compensated: output is the zero, zero temperature coefficient, span and span temperature coefficient in that order,
+// input is the reference temperature. Only found in the history file.
+// A term with index 0 starts a new model for the channel, replacing any previous one once all of its terms have been read.
+#[derive(Debug, Clone, Copy, Format, Serialize, Deserialize)]
+pub struct CalibrationTerm<Channel, ChannelValue> {
+	pub adc: AdcDevice,
+	pub channel: Channel,
+	pub model: CalibrationModelType,
+	pub index: u8,
+
+	// Number of terms of the model, a model with fewer terms in the file was cut short while it was written
+	pub term_count: u8,
+
+	pub input: ChannelValue,
+	pub output: ChannelValue,
+}
+
+impl<Channel, ChannelValue> SerializeCSV<MAX_LINE_LENGTH> for CalibrationTerm<Channel, ChannelValue>
+where
+	Channel: ChannelMarker,
+	ChannelValue: ChannelValueMarker,
+{
+	fn get_csv_header() -> Line {
+		Line::from_str("ADC Index,Channel Index,Model,Term Index,Term Count,Input,Output").unwrap()
+	}
+}
+
+// Summary of a saved calibration in the history file, followed by its terms and the residuals of its data points
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CalibrationRecord<Channel, ChannelValue> {
+	// Identifier of the calibration, increases with each calibration saved to the history file
+	pub id: u16,
+
+	pub adc: AdcDevice,
+	pub channel: Channel,
+	pub model: CalibrationModelType,
+
+	// Number of data points the model was fitted to
+	pub point_count: u8,
+
+	// Number of terms of the model following the record, see CalibrationTerm
+	pub term_count: u8,
+
+	// Coefficient of determination of the model over its data points, empty if it isn't defined or the model passes through every point
+	pub r_squared: Option<ChannelValue>,
+
+	// Local session of the device when the calibration was saved
+	pub local_session: Option<i32>,
+
+	// Milliseconds since the board's epoch when the calibration was saved
+	pub created_at: u64,
+
+	// Free text entered by the operator, e.g. the reference instrument used
+	pub notes: String<MAX_CALIBRATION_NOTES_LENGTH>,
+}
+
+impl<Channel, ChannelValue> CalibrationRecord<Channel, ChannelValue>
+where
+	Channel: ChannelMarker,
+	ChannelValue: ChannelValueMarker,
+{
+	/// Single line description of the calibration, used when listing the history over serial.
+	pub fn summary(&self) -> Result<String<192>, core::fmt::Error> {
+		let r_squared: String<24> = match self.r_squared {
+			Some(r_squared) => format!("{:.6}", r_squared.to_f64().unwrap_or(f64::NAN))?,
+			None => String::from_str("n/a").unwrap(),
+		};
+		let session: String<16> = match self.local_session {
+			Some(session) => format!("{}", session)?,
+			None => String::from_str("none").unwrap(),
+		};
+		format!(
+			"#{} {:?}, {} points, R² = {}, session {}, saved at {} ms, notes: {}",
+			self.id,
+			self.model,
+			self.point_count,
+			r_squared,
+			session,
+			self.created_at,
+			self.notes
+		)
+	}
+}
+
+impl<Channel, ChannelValue> SerializeCSV<MAX_LINE_LENGTH> for CalibrationRecord<Channel, ChannelValue>
+where
+	Channel: ChannelMarker,
+	ChannelValue: ChannelValueMarker,
+{
+	fn get_csv_header() -> Line {
+		Line::from_str("Calibration ID,ADC Index,Channel Index,Model,Point Count,Term Count,R Squared,Local Session,Timestamp (ms),Notes").unwrap()
+	}
+}
+
+// A single term of a calibration in the history file, see CalibrationTerm for the meaning of input and output
+#[derive(Debug, Clone, Copy, Format, Serialize, Deserialize)]
+pub struct CalibrationRecordTerm<ChannelValue> {
+	pub id: u16,
+	pub index: u8,
+	pub input: ChannelValue,
+	pub output: ChannelValue,
+}
+
+impl<ChannelValue> SerializeCSV<MAX_LINE_LENGTH> for CalibrationRecordTerm<ChannelValue>
+where
+	ChannelValue: ChannelValueMarker,
+{
+	fn get_csv_header() -> Line {
+		Line::from_str("Calibration ID,Term Index,Input,Output").unwrap()
+	}
+}
+
+// Terms of a calibration read back from the history file, as (input, output) pairs in order
+pub type CalibrationRecordTerms<ChannelValue> = Vec<(ChannelValue, ChannelValue), MAX_CALIBRATION_TERMS>;
+
+// A data point of a calibration to be recorded in the history file, see CalibrationModelService::record_calibration
+#[derive(Debug, Clone, Copy, Format)]
+pub struct CalibrationPoint<ChannelValue> {
+	// Value with error read from the channel
+	pub measured: ChannelValue,
+
+	// Value from the calibration instrument
+	pub expected: ChannelValue,
+
+	// Measured value once calibrated
+	pub calibrated: ChannelValue,
+
+	// Standard error of the measured value, 0 when the point wasn't taken from a window of readings
+	pub uncertainty: ChannelValue,
+}
+
+// A data point of a calibration in the history file, with the error left after applying the calibration
+#[derive(Debug, Clone, Copy, Format, Serialize, Deserialize)]
+pub struct CalibrationResidual<ChannelValue> {
+	pub id: u16,
+	pub index: u8,
+
+	// Value with error read from the channel
+	pub measured: ChannelValue,
+
+	// Value from the calibration instrument
+	pub expected: ChannelValue,
+
+	// expected - calibrated measured
+	pub residual: ChannelValue,
+
+	// Standard error of the measured value, 0 when the point wasn't taken from a window of readings
+	pub uncertainty: ChannelValue,
+}
+
+impl<ChannelValue> SerializeCSV<MAX_LINE_LENGTH> for CalibrationResidual<ChannelValue>
+where
+	ChannelValue: ChannelValueMarker,
+{
+	fn get_csv_header() -> Line {
+		Line::from_str("Calibration ID,Point Index,Measured,Expected,Residual,Uncertainty").unwrap()
+	}
+}
+
+// Calibration model of a channel while it is deregistered to be measured raw, registered again unless a new calibration is saved
+#[derive(Debug, Clone)]
+pub struct PreviousModel<Channel, ChannelValue> {
+	pub adc: AdcDevice,
+	pub channel: Channel,
+	pub model: Option<CalibrationModel<ChannelValue>>,
+}
+
+// What to do with a fit whose R² is below the minimum configured for the board
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Format)]
+pub enum LowQualityFitAction {
+	// Tell the operator and save the calibration anyway
+	Warn,
+
+	// Tell the operator and discard the calibration
+	Refuse,
+}
+
+// Limits a window of readings must be within before a calibration data point is taken from it
+#[derive(Debug, Clone, Copy, Format)]
+pub struct StabilityCriteria {
+	// Maximum standard deviation of the readings in the window, in the units of the readings
+	pub max_standard_deviation: f64,
+
+	// Maximum difference between the mean of the newest and oldest half of the window, in the units of the readings
+	pub max_drift: f64,
+
+	// Time between the readings of the window, in milliseconds. The window spans WINDOW_SIZE * reading_interval
+	pub reading_interval: u64,
+
+	// Time the reading is given to settle before the data point is rejected, in milliseconds
+	pub timeout: u64,
+}
+
+// Average of a window of readings that met the stability criteria, taken as the measured value of a calibration data point
+#[derive(Debug, Clone, Copy, Format)]
+pub struct StableReading {
+	pub mean: f64,
+	pub standard_deviation: f64,
+
+	// Standard error of the mean, standard_deviation / sqrt(reading_count)
+	pub uncertainty: f64,
+
+	pub reading_count: u8,
+}
+
+pub trait ChannelMarker: EnumCount + Default + Debug + Clone + Copy + Eq + PartialEq + Hash + Format + Serialize + for<'de> Deserialize<'de> {}
+
+impl<T> ChannelMarker for T where
+	T: EnumCount + Default + Debug + Clone + Copy + Eq + PartialEq + Hash + Format + Serialize + for<'de> Deserialize<'de>
+{
+}
+
+pub trait ChannelValueMarker: Float + Serialize + for<'de> Deserialize<'de> + Format {}
+
+impl<T> ChannelValueMarker for T where T: Float + Serialize + for<'de> Deserialize<'de> + Format {}
@@ -0,0 +1,4 @@
+use crate::calibration_model::config::MAX_LOOKUP_TABLE_POINTS;
+
+// Maximum number of data points captured in a calibration session, enough for the largest lookup table
+pub const MAX_SESSION_DATA_POINTS: usize = MAX_LOOKUP_TABLE_POINTS;
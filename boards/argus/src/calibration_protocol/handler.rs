@@ -0,0 +1,167 @@
+use defmt::{error, info};
+use uor_utils::messages::argus::calibration::calibration_command::calibration_command::Command;
+use uor_utils::messages::argus::calibration::calibration_command::{Commit, List, StartSession};
+use uor_utils::messages::argus::calibration::calibration_model::CalibrationModel as CalibrationModelProtobuf;
+use uor_utils::messages::argus::calibration::calibration_response::{CalibrationErrorCode, CalibrationResponse};
+
+use crate::calibration_protocol::session::{
+	aborted_response, calibration_list_response, committed_response, parse_channel, session_started_response, CalibrationSession, SessionError,
+};
+use crate::sensor::calibration::{capture_stable_reading, set_calibration_session_kind};
+use crate::sensor::types::{CalibratedSensorService, SensorServiceError};
+
+// Calibration protocol commands shared by the sensor services, each service only provides its readings, models and fit
+// See the README of the module for the commands of a calibration run
+
+/// Answers a calibration command from the ground application with a calibration response, see receive_calibration_command.
+/// None stands for a message that isn't a calibration command, answered with a CalibrationError like the commands that can't be carried out.
+pub async fn handle_calibration_command<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service,
+	command: Option<Command>,
+) -> Result<(), Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	let Some(command) = command else {
+		let error = SessionError::new(CalibrationErrorCode::InvalidCommand, "Expected a calibration command");
+		return service.send_calibration_response(error.to_response()).await;
+	};
+
+	let response = match run_calibration_command(service, command).await {
+		Ok(response) => response,
+		Err(e) => match e.session_error() {
+			Some(error) => error.to_response(),
+			None => {
+				error!("Calibration command failed: {:?}", e);
+				SessionError::new(CalibrationErrorCode::ServiceError, "Board error, see the board logs").to_response()
+			}
+		},
+	};
+	set_calibration_session_kind(Service::CHANNEL_KIND, service.calibration_session().is_some()).await;
+	service.send_calibration_response(response).await
+}
+
+/// Fits the model of the session to its data points, the fit is judged against MIN_CALIBRATION_R_SQUARED and LOW_QUALITY_FIT_ACTION.
+pub fn compute_calibration_fit<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service
+) -> Result<CalibrationResponse, Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	let session = active_calibration_session(service)?;
+	let model = session.fit_model()?;
+	let points = session.points();
+	let uncertainties = session.uncertainties();
+	Ok(session.set_fit(model, &points, &uncertainties, Service::MIN_CALIBRATION_R_SQUARED, Service::LOW_QUALITY_FIT_ACTION))
+}
+
+pub fn active_calibration_session<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service
+) -> Result<&mut CalibrationSession<Service::Channel>, Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	service
+		.calibration_session()
+		.as_mut()
+		.ok_or(SessionError::new(CalibrationErrorCode::NoSession, "Start a session first").into())
+}
+
+async fn run_calibration_command<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service,
+	command: Command,
+) -> Result<CalibrationResponse, Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	match command {
+		Command::StartSession(start_session) => start_calibration_session(service, start_session).await,
+		Command::SubmitReferenceValue(submit) => Ok(active_calibration_session(service)?.submit_reference_value(submit.value)),
+		Command::CapturePoint(_) => capture_calibration_point(service).await,
+		Command::ComputeFit(_) => service.compute_calibration_fit(),
+		Command::Commit(commit) => commit_calibration_session(service, commit).await,
+		Command::Abort(_) => abort_calibration_session(service),
+		Command::List(list) => list_calibration_history(service, list).await,
+	}
+}
+
+async fn start_calibration_session<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service,
+	start_session: StartSession,
+) -> Result<CalibrationResponse, Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	if service.calibration_session().is_some() {
+		return Err(SessionError::new(CalibrationErrorCode::SessionInProgress, "Commit or abort the current session first").into());
+	}
+	let (adc, channel) = parse_channel::<Service::Channel>(start_session.adc_device, start_session.channel)?;
+	if !service.adc_service().is_available_for(adc, Service::CHANNEL_KIND).await {
+		return Err(SessionError::new(CalibrationErrorCode::InvalidChannel, "ADC not available").into());
+	}
+	let model = match CalibrationModelProtobuf::try_from(start_session.model) {
+		Ok(model) if Service::PROTOCOL_MODELS.contains(&model) => model,
+		_ => return Err(SessionError::new(CalibrationErrorCode::UnsupportedModel, "Model not supported by this board").into()),
+	};
+
+	*service.calibration_session() = Some(CalibrationSession::new(adc, channel, model, start_session.polynomial_order)?);
+	info!("Calibration session started for {:?} {:?}", adc, channel);
+
+	// Same as the text prompts, the channel is measured without its calibration
+	service.deregister_calibration(adc, channel);
+	Ok(session_started_response())
+}
+
+async fn capture_calibration_point<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service
+) -> Result<CalibrationResponse, Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	let session = active_calibration_session(service)?;
+	session.next_reference_value()?;
+	let (adc, channel) = (session.adc, session.channel);
+
+	let Some(reading) = capture_stable_reading(service, adc, channel, Service::calibration_value).await? else {
+		return Err(SessionError::new(CalibrationErrorCode::UnstableReading, "Reading not stable, point rejected").into());
+	};
+	Ok(active_calibration_session(service)?.add_data_point(reading)?)
+}
+
+async fn commit_calibration_session<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service,
+	commit: Commit,
+) -> Result<CalibrationResponse, Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	let session = active_calibration_session(service)?;
+	let (adc, channel) = (session.adc, session.channel);
+	let fit = session.fit_to_commit()?.clone();
+
+	let local_session = service.session_service().lock().await.current_session;
+	let record = service
+		.calibration_model_service()
+		.save_calibration(adc, channel, fit.model, &fit.points, &fit.uncertainties, local_session, commit.notes.as_str())
+		.await?;
+	*service.calibration_session() = None;
+	service.replace_previous_calibration(adc, channel).await?;
+	Ok(committed_response(record.id))
+}
+
+fn abort_calibration_session<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service
+) -> Result<CalibrationResponse, Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	let session = active_calibration_session(service)?;
+	let (adc, channel) = (session.adc, session.channel);
+	*service.calibration_session() = None;
+	service.reregister_calibration();
+	info!("Calibration session aborted for {:?} {:?}", adc, channel);
+	Ok(aborted_response())
+}
+
+async fn list_calibration_history<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service,
+	list: List,
+) -> Result<CalibrationResponse, Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	let (adc, channel) = parse_channel::<Service::Channel>(list.adc_device, list.channel)?;
+	let records = service.calibration_model_service().load_calibration_history(adc, channel).await?;
+	Ok(calibration_list_response(&records, list.channel))
+}
@@ -0,0 +1,4 @@
+pub mod config;
+pub mod handler;
+pub mod session;
+pub mod tasks;
@@ -0,0 +1,317 @@
+use defmt::{write, Format, Formatter};
+use heapless::Vec;
+use strum::EnumCount;
+use uor_utils::messages::argus::calibration::calibration_model::CalibrationModel as CalibrationModelProtobuf;
+use uor_utils::messages::argus::calibration::calibration_response::calibration_response::Response;
+use uor_utils::messages::argus::calibration::calibration_response::{
+	Aborted, CalibrationError, CalibrationErrorCode, CalibrationList, CalibrationRecord as CalibrationRecordProtobuf, CalibrationResponse,
+	CalibrationTerm as CalibrationTermProtobuf, Committed, FitComputed, LiveReading, PointCaptured, ReferenceValueAccepted, SessionStarted,
+};
+
+use crate::adc::types::AdcDevice;
+use crate::calibration_model::config::MAX_POLYNOMIAL_ORDER;
+use crate::calibration_model::fit::{build_lookup_table, fit_polynomial};
+use crate::calibration_model::types::{CalibrationModel, CalibrationRecord, LowQualityFitAction, StableReading};
+use crate::calibration_protocol::config::MAX_SESSION_DATA_POINTS;
+
+/// A calibration of a single channel driven by commands from a ground application instead of the text prompts.
+/// The board keeps one session at a time, from the StartSession command until it is committed, aborted or the board leaves the Calibrating state.
+pub struct CalibrationSession<Channel> {
+	pub adc: AdcDevice,
+	pub channel: Channel,
+	pub model: CalibrationModelProtobuf,
+
+	// Order of the fitted polynomial, the other models are fitted with the order of a line
+	pub polynomial_order: usize,
+
+	// Reference value paired with the next captured point
+	pub reference_value: Option<f64>,
+
+	pub data_points: Vec<SessionDataPoint, MAX_SESSION_DATA_POINTS>,
+
+	// Fit of the data points, cleared whenever another point is captured
+	pub fit: Option<SessionFit>,
+}
+
+// A captured point, the reference value and the stable reading of the channel taken for it
+#[derive(Debug, Clone, Copy)]
+pub struct SessionDataPoint {
+	pub expected: f64,
+	pub measured: f64,
+
+	// Standard error of the measured value
+	pub uncertainty: f64,
+}
+
+// Model fitted to the data points of a session, waiting to be committed
+#[derive(Debug, Clone)]
+pub struct SessionFit {
+	pub model: CalibrationModel<f64>,
+
+	// (measured, expected) points the fit is judged against and recorded with in the calibration history
+	pub points: Vec<(f64, f64), MAX_SESSION_DATA_POINTS>,
+
+	// Standard errors of the measured values of the points
+	pub uncertainties: Vec<f64, MAX_SESSION_DATA_POINTS>,
+
+	pub r_squared: Option<f64>,
+
+	// False when the R² is below the minimum of the board and LOW_QUALITY_FIT_ACTION refuses it
+	pub accepted: bool,
+}
+
+// Reason a command couldn't be carried out, sent back to the ground application as a CalibrationError
+#[derive(Debug, Clone, Copy)]
+pub struct SessionError {
+	pub code: CalibrationErrorCode,
+	pub message: &'static str,
+}
+
+impl<Channel> CalibrationSession<Channel>
+where
+	Channel: Copy,
+{
+	pub fn new(
+		adc: AdcDevice,
+		channel: Channel,
+		model: CalibrationModelProtobuf,
+		polynomial_order: u32,
+	) -> Result<Self, SessionError> {
+		let polynomial_order = match model {
+			CalibrationModelProtobuf::Polynomial => polynomial_order as usize,
+			_ => 1,
+		};
+		if polynomial_order < 1 || polynomial_order > MAX_POLYNOMIAL_ORDER {
+			return Err(SessionError::new(CalibrationErrorCode::UnsupportedModel, "Invalid polynomial order"));
+		}
+
+		Ok(Self {
+			adc,
+			channel,
+			model,
+			polynomial_order,
+			reference_value: None,
+			data_points: Vec::new(),
+			fit: None,
+		})
+	}
+
+	pub fn submit_reference_value(
+		&mut self,
+		value: f64,
+	) -> CalibrationResponse {
+		self.reference_value = Some(value);
+		to_response(Response::ReferenceValueAccepted(ReferenceValueAccepted { value }))
+	}
+
+	/// Checks that a point can be captured before the channel is read.
+	/// Returns the reference value the point will be paired with.
+	pub fn next_reference_value(&self) -> Result<f64, SessionError> {
+		if self.data_points.is_full() {
+			return Err(SessionError::new(CalibrationErrorCode::TooManyPoints, "Session is full"));
+		}
+		self.reference_value
+			.ok_or(SessionError::new(CalibrationErrorCode::NoReferenceValue, "Submit a reference value first"))
+	}
+
+	/// Pairs the stable reading with the submitted reference value, each reference value is used for a single point.
+	pub fn add_data_point(
+		&mut self,
+		reading: StableReading,
+	) -> Result<CalibrationResponse, SessionError> {
+		let expected = self.next_reference_value()?;
+		let data_point = SessionDataPoint {
+			expected,
+			measured: reading.mean,
+			uncertainty: reading.uncertainty,
+		};
+		self.data_points.push(data_point).unwrap(); // Safe due to prior checks
+		self.reference_value = None;
+		self.fit = None;
+
+		Ok(to_response(Response::PointCaptured(PointCaptured {
+			index: (self.data_points.len() - 1) as u32,
+			expected,
+			measured: reading.mean,
+			uncertainty: reading.uncertainty,
+			reading_count: reading.reading_count as u32,
+		})))
+	}
+
+	/// Captured points as (measured, expected) pairs, in the order they were captured.
+	pub fn points(&self) -> Vec<(f64, f64), MAX_SESSION_DATA_POINTS> {
+		self.data_points.iter().map(|data_point| (data_point.measured, data_point.expected)).collect()
+	}
+
+	/// Standard errors of the measured values of the captured points, in the same order as the points.
+	pub fn uncertainties(&self) -> Vec<f64, MAX_SESSION_DATA_POINTS> {
+		self.data_points.iter().map(|data_point| data_point.uncertainty).collect()
+	}
+
+	/// Fits the linear, polynomial and lookup table models to the captured points.
+	/// Shunt calibrations depend on the bridge of the channel, the strain board fits them itself.
+	/// Temperature compensations need the manifold temperature of each point, they are only fitted through the text prompts.
+	pub fn fit_model(&self) -> Result<CalibrationModel<f64>, SessionError> {
+		let points = self.points();
+		let not_enough_points = SessionError::new(CalibrationErrorCode::NotEnoughPoints, "Captured points don't determine the fit");
+		match self.model {
+			CalibrationModelProtobuf::Linear => {
+				let coefficients = fit_polynomial(&points, 1).ok_or(not_enough_points)?;
+				Ok(CalibrationModel::Linear {
+					scale: coefficients[1],
+					offset: coefficients[0],
+				})
+			}
+			CalibrationModelProtobuf::Polynomial => {
+				let coefficients = fit_polynomial(&points, self.polynomial_order).ok_or(not_enough_points)?;
+				Ok(CalibrationModel::Polynomial { coefficients })
+			}
+			CalibrationModelProtobuf::LookupTable => {
+				if points.len() < 2 {
+					return Err(not_enough_points);
+				}
+				let table = build_lookup_table(&points)
+					.ok_or(SessionError::new(CalibrationErrorCode::NotEnoughPoints, "Two points have the same measured value"))?;
+				Ok(CalibrationModel::LookupTable { points: table })
+			}
+			CalibrationModelProtobuf::Shunt | CalibrationModelProtobuf::TemperatureCompensated => {
+				Err(SessionError::new(CalibrationErrorCode::UnsupportedModel, "Model not supported by this board"))
+			}
+		}
+	}
+
+	/// Keeps the fit to be committed and judges its R² against the minimum of the board.
+	pub fn set_fit(
+		&mut self,
+		model: CalibrationModel<f64>,
+		points: &[(f64, f64)],
+		uncertainties: &[f64],
+		min_r_squared: f64,
+		low_quality_fit_action: LowQualityFitAction,
+	) -> CalibrationResponse {
+		let r_squared = model.r_squared(points);
+		let accepted = match r_squared {
+			Some(r_squared) if r_squared < min_r_squared => low_quality_fit_action == LowQualityFitAction::Warn,
+			_ => true,
+		};
+
+		let response = FitComputed {
+			model: model.model_type().to_protobuf() as i32,
+			terms: model
+				.terms()
+				.map(|(input, output)| CalibrationTermProtobuf { input, output })
+				.collect(),
+			r_squared,
+			residuals: points.iter().map(|&(measured, expected)| expected - model.apply(measured)).collect(),
+			accepted,
+		};
+		self.fit = Some(SessionFit {
+			model,
+			points: Vec::from_slice(points).unwrap_or_default(),
+			uncertainties: Vec::from_slice(uncertainties).unwrap_or_default(),
+			r_squared,
+			accepted,
+		});
+		to_response(Response::FitComputed(response))
+	}
+
+	/// Fit to save when the session is committed.
+	pub fn fit_to_commit(&self) -> Result<&SessionFit, SessionError> {
+		match &self.fit {
+			None => Err(SessionError::new(CalibrationErrorCode::NoFit, "Compute the fit first")),
+			Some(fit) if !fit.accepted => Err(SessionError::new(CalibrationErrorCode::LowQualityFit, "R squared is below the minimum")),
+			Some(fit) => Ok(fit),
+		}
+	}
+}
+
+impl SessionError {
+	pub const fn new(
+		code: CalibrationErrorCode,
+		message: &'static str,
+	) -> Self {
+		Self { code, message }
+	}
+
+	pub fn to_response(&self) -> CalibrationResponse {
+		to_response(Response::Error(CalibrationError {
+			code: self.code as i32,
+			message: self.message.into(),
+		}))
+	}
+}
+
+impl Format for SessionError {
+	fn format(
+		&self,
+		f: Formatter,
+	) {
+		write!(f, "SessionError {{ code: {}, message: {} }}", self.code as i32, self.message)
+	}
+}
+
+/// Parses the ADC and channel indexes of a command.
+pub fn parse_channel<Channel>(
+	adc_device: i32,
+	channel: u32,
+) -> Result<(AdcDevice, Channel), SessionError>
+where
+	Channel: From<usize> + EnumCount,
+{
+	if adc_device < 0 || adc_device as usize >= AdcDevice::COUNT || channel as usize >= Channel::COUNT {
+		return Err(SessionError::new(CalibrationErrorCode::InvalidChannel, "Invalid ADC or channel index"));
+	}
+	Ok((AdcDevice::from(adc_device as usize), Channel::from(channel as usize)))
+}
+
+pub fn session_started_response() -> CalibrationResponse {
+	to_response(Response::SessionStarted(SessionStarted {}))
+}
+
+pub fn live_reading_response(
+	value: f64,
+	standard_deviation: f64,
+	drift: f64,
+) -> CalibrationResponse {
+	to_response(Response::LiveReading(LiveReading {
+		value,
+		standard_deviation,
+		drift,
+	}))
+}
+
+pub fn committed_response(calibration_id: u16) -> CalibrationResponse {
+	to_response(Response::Committed(Committed {
+		calibration_id: calibration_id as u32,
+	}))
+}
+
+pub fn aborted_response() -> CalibrationResponse {
+	to_response(Response::Aborted(Aborted {}))
+}
+
+/// Lists the records of a channel, the channel index is taken from the List command since the records are of a board's channel type.
+pub fn calibration_list_response<Channel>(
+	records: &[CalibrationRecord<Channel, f64>],
+	channel: u32,
+) -> CalibrationResponse {
+	let records = records
+		.iter()
+		.map(|record| CalibrationRecordProtobuf {
+			calibration_id: record.id as u32,
+			adc_device: record.adc.to_protobuf() as i32,
+			channel,
+			model: record.model.to_protobuf() as i32,
+			point_count: record.point_count as u32,
+			r_squared: record.r_squared,
+			local_session: record.local_session,
+			created_at: record.created_at,
+			notes: record.notes.as_str().into(),
+		})
+		.collect();
+	to_response(Response::CalibrationList(CalibrationList { records }))
+}
+
+fn to_response(response: Response) -> CalibrationResponse {
+	CalibrationResponse { response: Some(response) }
+}
@@ -0,0 +1,3 @@
+mod route_calibration_commands;
+
+pub use route_calibration_commands::*;
@@ -0,0 +1,27 @@
+use defmt::error;
+use embassy_executor::task;
+use uor_peripherals::serial::peripheral::UORSerialRx;
+use uor_utils::utils::types::AsyncMutex;
+
+use crate::sensor::calibration::route_calibration_command;
+use crate::state_machine::service::StateMachineWorker;
+use crate::state_machine::types::States;
+
+// Task that reads the calibration commands from the ground application and routes them to the calibrate task of their kind
+// It alone reads the serial port, so the calibrate tasks never wait for input while holding it, see sensor::calibration
+#[task]
+pub async fn route_calibration_commands(
+	mut worker: StateMachineWorker,
+	serial_input_mutex: &'static AsyncMutex<UORSerialRx>,
+) {
+	worker
+		.run_while(&[States::Calibrating], async |_| -> Result<(), ()> {
+			match route_calibration_command(serial_input_mutex).await {
+				Err(e) => error!("Failed to read a calibration command: {:?}", e),
+				_ => {}
+			}
+			Ok(())
+		})
+		.await
+		.unwrap();
+}
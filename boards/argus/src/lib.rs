@@ -3,13 +3,19 @@
 #![no_main]
 
 pub mod adc;
+pub mod board_health;
+pub mod calibration_model;
+pub mod calibration_protocol;
 pub mod led_indicator;
-pub mod linear_transformation;
 pub mod node;
+pub mod oversampling;
+pub mod scan_sequencer;
 pub mod sd;
+pub mod sensor;
 pub mod session;
 pub mod state_machine;
 pub mod strain;
+pub mod tare;
 
 #[cfg(feature = "temperature")]
 pub mod temperature;
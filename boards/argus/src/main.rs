@@ -3,20 +3,25 @@
 #![no_main]
 
 #[cfg(not(any(feature = "pressure", feature = "temperature", feature = "strain")))]
-compile_error!("You must enable exactly one of the features: 'pressure', 'temperature', or 'strain'.");
+compile_error!("You must enable at least one of the features: 'pressure', 'temperature', or 'strain'.");
 
 use argus::adc::service::{AdcConfig, AdcService};
 use argus::adc::types::AdcDevice;
+use argus::board_health::service::BoardHealthService;
+use argus::board_health::tasks::monitor_board_health;
 use argus::led_indicator::service::LedIndicatorService;
 use argus::node::node::CURRENT_NODE;
 use argus::sd::service::SDCardService;
 use argus::sd::task::sd_card_task;
+use argus::sensor::setup::{mark_kind_faulted, prepare_adcs, save_register_snapshots};
+use argus::sensor::types::ChannelKind;
 use argus::session::service::SessionService;
 use argus::state_machine::service::{StateMachineOrchestrator, StateMachineWorker};
 use argus::state_machine::types::Events;
-use defmt::info;
+use defmt::{error, info};
 use defmt_rtt as _;
 use embassy_executor::Spawner;
+use embassy_stm32::exti::Channel;
 use embassy_stm32::gpio::Pin;
 use embassy_stm32::usart::Uart;
 use embassy_stm32::{bind_interrupts, peripherals, usart};
@@ -36,11 +41,14 @@ bind_interrupts!(struct InterruptRequests {
 // All services are singletons held in a static cell to initialize after peripherals are available
 // And wrapped around a mutex so they can be accessed safely from multiple async tasks
 static SD_CARD_SERVICE: StaticCell<AsyncMutex<SDCardService>> = StaticCell::new();
-static ADC_SERVICE: StaticCell<AsyncMutex<AdcService<{ AdcDevice::COUNT }>>> = StaticCell::new();
+static ADC_SERVICE: StaticCell<AdcService<{ AdcDevice::COUNT }>> = StaticCell::new();
 static SERIAL_SERVICE: StaticCell<AsyncMutex<UORSerial>> = StaticCell::new();
 static SESSION_SERVICE: StaticCell<AsyncMutex<SessionService>> = StaticCell::new();
 static LED_INDICATOR_SERVICE: StaticCell<AsyncMutex<LedIndicatorService<2>>> = StaticCell::new();
+static BOARD_HEALTH_SERVICE: StaticCell<AsyncMutex<BoardHealthService<{ AdcDevice::COUNT }>>> = StaticCell::new();
 static STATE_MACHINE_ORCHESTRATOR: StaticCell<AsyncMutex<StateMachineOrchestrator>> = StaticCell::new();
+#[cfg(feature = "calibration-protocol")]
+static SERIAL_INPUT: StaticCell<AsyncMutex<uor_peripherals::serial::peripheral::UORSerialRx>> = StaticCell::new();
 // static CURRENT_NODE: StaticCell<Node> = StaticCell::new();
 
 #[cfg(feature = "temperature")]
@@ -75,7 +83,8 @@ async fn main(spawner: Spawner) {
 		peripherals.PA3.degrade(),
 		peripherals.PA2.degrade(),
 	])));
-	let adc_service = ADC_SERVICE.init(AsyncMutex::new(AdcService::new(
+	let adc_service = ADC_SERVICE.init(AdcService::new(
+		sd_card_service,
 		peripherals.SPI4,
 		peripherals.PE2,
 		peripherals.PE6,
@@ -86,17 +95,19 @@ async fn main(spawner: Spawner) {
 			AdcConfig {
 				chip_select: peripherals.PE1.degrade(),
 				data_ready: peripherals.PB9.degrade(),
+				data_ready_exti: peripherals.EXTI9.degrade(),
 				reset: peripherals.PE0.degrade(),
 				start: peripherals.PB0.degrade(),
 			},
 			AdcConfig {
 				chip_select: peripherals.PB8.degrade(),
 				data_ready: peripherals.PB6.degrade(),
+				data_ready_exti: peripherals.EXTI6.degrade(),
 				reset: peripherals.PB7.degrade(),
 				start: peripherals.PB1.degrade(),
 			},
 		],
-	)));
+	));
 	let serial_service = SERIAL_SERVICE.init(AsyncMutex::new(
 		UORSerial::new(
 			peripherals.UART7,
@@ -110,10 +121,22 @@ async fn main(spawner: Spawner) {
 		.unwrap(),
 	));
 
+	let board_health_service = BOARD_HEALTH_SERVICE.init(AsyncMutex::new(BoardHealthService::new(adc_service, session_service)));
+
 	let state_machine_orchestrator = STATE_MACHINE_ORCHESTRATOR.init(AsyncMutex::new(StateMachineOrchestrator::new()));
 
 	// General tasks that must run regardless of board type
 	spawner.must_spawn(sd_card_task(sd_card_service, led_indicator_service));
+	spawner.must_spawn(monitor_board_health(
+		StateMachineWorker::new(state_machine_orchestrator),
+		board_health_service,
+		serial_service,
+		sd_card_service,
+		session_service,
+	));
+
+	// Probe and prepare the ADCs once, each sensor service then configures the ADCs wired to its kind, see ADC_CHANNEL_KINDS
+	prepare_adcs(adc_service, serial_service, session_service).await;
 
 	// Spawn tasks needed for temperature board
 	#[cfg(feature = "temperature")]
@@ -129,8 +152,14 @@ async fn main(spawner: Spawner) {
 			session_service,
 		)));
 
-		// Setup the temperature service before starting the tasks
-		temperature_service.lock().await.setup().await.unwrap();
+		// Setup the temperature service before starting the tasks. If it fails, its ADCs are skipped and the other kinds keep running
+		match temperature_service.lock().await.setup().await {
+			Err(e) => {
+				error!("Failed to set up the temperature service: {:?}", e);
+				mark_kind_faulted(adc_service, ChannelKind::Thermocouple).await;
+			}
+			_ => {}
+		}
 
 		spawner.must_spawn(tasks::measure_rtds(
 			StateMachineWorker::new(state_machine_orchestrator),
@@ -143,6 +172,7 @@ async fn main(spawner: Spawner) {
 		));
 		spawner.must_spawn(tasks::log_measurements(
 			StateMachineWorker::new(state_machine_orchestrator),
+			adc_service,
 			serial_service,
 			sd_card_service,
 			session_service,
@@ -167,8 +197,14 @@ async fn main(spawner: Spawner) {
 			session_service,
 		)));
 
-		// Setup the pressure service before starting the tasks
-		pressure_service.lock().await.setup().await.unwrap();
+		// Setup the pressure service before starting the tasks. If it fails, its ADCs are skipped and the other kinds keep running
+		match pressure_service.lock().await.setup().await {
+			Err(e) => {
+				error!("Failed to set up the pressure service: {:?}", e);
+				mark_kind_faulted(adc_service, ChannelKind::Pressure).await;
+			}
+			_ => {}
+		}
 
 		spawner.must_spawn(tasks::measure_pressure_sensors(
 			StateMachineWorker::new(state_machine_orchestrator),
@@ -181,6 +217,7 @@ async fn main(spawner: Spawner) {
 		));
 		spawner.must_spawn(tasks::log_measurements(
 			StateMachineWorker::new(state_machine_orchestrator),
+			adc_service,
 			serial_service,
 			sd_card_service,
 			session_service,
@@ -189,6 +226,10 @@ async fn main(spawner: Spawner) {
 			StateMachineWorker::new(state_machine_orchestrator),
 			pressure_service,
 		));
+		spawner.must_spawn(tasks::tare_on_recording(
+			StateMachineWorker::new(state_machine_orchestrator),
+			pressure_service,
+		));
 	}
 
 	// Spawn tasks needed for strain board
@@ -205,8 +246,14 @@ async fn main(spawner: Spawner) {
 			session_service,
 		)));
 
-		// Setup the strain service before starting the tasks
-		strain_service.lock().await.setup().await.unwrap();
+		// Setup the strain service before starting the tasks. If it fails, its ADCs are skipped and the other kinds keep running
+		match strain_service.lock().await.setup().await {
+			Err(e) => {
+				error!("Failed to set up the strain service: {:?}", e);
+				mark_kind_faulted(adc_service, ChannelKind::Strain).await;
+			}
+			_ => {}
+		}
 
 		spawner.must_spawn(tasks::measure_strain(
 			StateMachineWorker::new(state_machine_orchestrator),
@@ -215,10 +262,39 @@ async fn main(spawner: Spawner) {
 		));
 		spawner.must_spawn(tasks::log_measurements(
 			StateMachineWorker::new(state_machine_orchestrator),
+			adc_service,
 			serial_service,
 			sd_card_service,
 			session_service,
 		));
+		spawner.must_spawn(tasks::calibrate_strain_gauges(
+			StateMachineWorker::new(state_machine_orchestrator),
+			strain_service,
+		));
+		spawner.must_spawn(tasks::tare_on_recording(
+			StateMachineWorker::new(state_machine_orchestrator),
+			strain_service,
+		));
+	}
+
+	// Document the exact ADC configuration the session runs with, once every service has configured its ADCs
+	// The snapshots only document the session, a failure to save them doesn't stop the board
+	match save_register_snapshots(adc_service, session_service).await {
+		Err(e) => error!("Failed to save the ADC register snapshots: {:?}", e),
+		_ => {}
+	}
+
+	// The calibration commands are read by a task of their own and routed to the calibrate task of their kind
+	// It takes the input of the serial port, so the other tasks write to it without waiting for the next command
+	#[cfg(feature = "calibration-protocol")]
+	{
+		use argus::calibration_protocol::tasks::route_calibration_commands;
+
+		let serial_input = SERIAL_INPUT.init(AsyncMutex::new(serial_service.lock().await.take_rx_component().unwrap()));
+		spawner.must_spawn(route_calibration_commands(
+			StateMachineWorker::new(state_machine_orchestrator),
+			serial_input,
+		));
 	}
 
 	#[cfg(not(feature = "calibration"))]
@@ -1,19 +1,30 @@
 use uor_utils::messages::argus::envelope::{Node, NodeType};
 
-#[cfg(feature = "temperature")]
+#[cfg(all(feature = "temperature", not(any(feature = "pressure", feature = "strain"))))]
 pub static CURRENT_NODE: Node = Node {
 	r#type: NodeType::ArgusTemperature as i32,
 	id: Some(0),
 };
 
-#[cfg(feature = "pressure")]
+#[cfg(all(feature = "pressure", not(any(feature = "temperature", feature = "strain"))))]
 pub static CURRENT_NODE: Node = Node {
 	r#type: NodeType::ArgusPressure as i32,
 	id: Some(0),
 };
 
-#[cfg(feature = "strain")]
+#[cfg(all(feature = "strain", not(any(feature = "temperature", feature = "pressure"))))]
 pub static CURRENT_NODE: Node = Node {
 	r#type: NodeType::ArgusStrain as i32,
 	id: Some(0),
 };
+
+// Boards built with several sensor features split their ADCs between the kinds, see ADC_CHANNEL_KINDS
+#[cfg(any(
+	all(feature = "temperature", feature = "pressure"),
+	all(feature = "temperature", feature = "strain"),
+	all(feature = "pressure", feature = "strain"),
+))]
+pub static CURRENT_NODE: Node = Node {
+	r#type: NodeType::ArgusMixed as i32,
+	id: Some(0),
+};
@@ -0,0 +1,57 @@
+use heapless::Vec;
+
+use crate::adc::driver::types::{AnalogChannel, Voltage};
+use crate::adc::service::{AdcError, AdcService};
+use crate::oversampling::config::MAX_OVERSAMPLING_COUNT;
+use crate::oversampling::types::{Oversampling, SampleStatistics};
+
+// The oversampled reads of the ADC service are kept with the rest of the oversampling
+impl<const ADC_COUNT: usize> AdcService<ADC_COUNT> {
+	/// Converts one input pair on every given ADC as many times as its oversampling asks for, and returns the sample statistics indexed by ADC.
+	/// Every round of conversions is synchronized across the ADCs, ADCs that already have all of their samples sit out the remaining rounds.
+	/// ADCs without a pair, or that are missing or faulted, are skipped and return None. A failed conversion fails the reading of its ADC.
+	pub async fn read_differential_oversampled(
+		&self,
+		pairs: [Option<(AnalogChannel, AnalogChannel)>; ADC_COUNT],
+		oversampling: [Oversampling; ADC_COUNT],
+	) -> [Option<Result<SampleStatistics, AdcError>>; ADC_COUNT] {
+		let sample_counts: [usize; ADC_COUNT] =
+			core::array::from_fn(|adc_index| (oversampling[adc_index].sample_count as usize).clamp(1, MAX_OVERSAMPLING_COUNT));
+		let rounds = (0..ADC_COUNT).filter(|&adc_index| pairs[adc_index].is_some()).map(|adc_index| sample_counts[adc_index]).max();
+
+		let mut samples: [Vec<Voltage, MAX_OVERSAMPLING_COUNT>; ADC_COUNT] = core::array::from_fn(|_| Vec::new());
+		let mut errors: [Option<AdcError>; ADC_COUNT] = core::array::from_fn(|_| None);
+		for round in 0..rounds.unwrap_or(0) {
+			let round_pairs = core::array::from_fn(|adc_index| match pairs[adc_index] {
+				Some(pair) if errors[adc_index].is_none() && round < sample_counts[adc_index] => Some(pair),
+				_ => None,
+			});
+
+			let voltages = self.read_differential_synchronized(round_pairs).await;
+			for (adc_index, voltage) in voltages.into_iter().enumerate() {
+				match voltage {
+					Some(Ok(voltage)) => {
+						let _ = samples[adc_index].push(voltage);
+					}
+					Some(Err(e)) => errors[adc_index] = Some(e),
+					None => {}
+				}
+			}
+		}
+
+		let mut results = samples.into_iter().zip(errors);
+		core::array::from_fn(|adc_index| {
+			let (samples, error) = results.next().unwrap();
+			if let Some(e) = error {
+				return Some(Err(e));
+			}
+
+			// Not converted at all, i.e. the ADC wasn't given a pair or is missing or faulted
+			if samples.is_empty() {
+				return None;
+			}
+
+			Some(Ok(SampleStatistics::from_samples(&samples, oversampling[adc_index].outlier_threshold)))
+		})
+	}
+}
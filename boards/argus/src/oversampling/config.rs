@@ -0,0 +1,2 @@
+// Maximum number of conversions that can be averaged into a single reading
+pub const MAX_OVERSAMPLING_COUNT: usize = 64;
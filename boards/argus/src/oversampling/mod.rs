@@ -0,0 +1,4 @@
+pub mod acquisition;
+pub mod config;
+pub mod service;
+pub mod types;
@@ -0,0 +1,95 @@
+use core::str::FromStr;
+
+use defmt::{error, info};
+use heapless::LinearMap;
+use uor_utils::csv::SerializeCSV;
+use uor_utils::utils::types::AsyncMutex;
+
+use crate::adc::types::AdcDevice;
+use crate::calibration_model::types::ChannelMarker;
+use crate::oversampling::types::{Oversampling, OversamplingConfiguration};
+use crate::sd::service::SDCardService;
+use crate::sd::types::{FileName, OperationScope, SdCardError};
+
+pub struct OversamplingService<Channel, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>
+where
+	Channel: ChannelMarker, {
+	pub sd_card_service: &'static AsyncMutex<SDCardService>,
+	pub file_name: &'static str,
+
+	// Oversampling of each ADC and channel that has one configured, the others are read with a single conversion
+	pub configurations: LinearMap<AdcDevice, LinearMap<Channel, Oversampling, CHANNEL_COUNT>, ADC_COUNT>,
+}
+
+impl<Channel, const ADC_COUNT: usize, const CHANNEL_COUNT: usize> OversamplingService<Channel, ADC_COUNT, CHANNEL_COUNT>
+where
+	Channel: ChannelMarker,
+{
+	pub fn new(
+		sd_card_service: &'static AsyncMutex<SDCardService>,
+		file_name: &'static str,
+	) -> Self {
+		Self {
+			sd_card_service,
+			file_name,
+			configurations: LinearMap::default(),
+		}
+	}
+
+	pub async fn load_configurations(&mut self) -> Result<(), SdCardError> {
+		let result = self
+			.sd_card_service
+			.lock()
+			.await
+			.read(OperationScope::Root, FileName::from_str(self.file_name).unwrap(), |line| {
+				if *line == OversamplingConfiguration::<Channel>::get_csv_header() {
+					return true; // Skip header line
+				}
+
+				let result = OversamplingConfiguration::<Channel>::from_csv_line(line);
+				match result {
+					Ok(configuration) => {
+						self.register_configuration(configuration);
+						info!("Loaded oversampling configuration: {:?}", configuration);
+					}
+					Err(e) => {
+						error!("Error parsing oversampling configuration for line '{}': {:?}", line.as_str(), e);
+					}
+				}
+				true // Continue reading
+			});
+
+		match result {
+			Ok(_) => (),
+			Err(SdCardError::NotFound) => {
+				// If no oversampling is configured, keep reading every channel with a single conversion and ignore this error.
+				info!("Oversampling configurations file not found, using defaults. Sample count = 1");
+			}
+			Err(e) => return Err(e),
+		}
+		Ok(())
+	}
+
+	pub fn register_configuration(
+		&mut self,
+		configuration: OversamplingConfiguration<Channel>,
+	) {
+		if !self.configurations.contains_key(&configuration.adc) {
+			let _ = self.configurations.insert(configuration.adc, LinearMap::new());
+		}
+		let map = self.configurations.get_mut(&configuration.adc).unwrap();
+		let _ = map.insert(configuration.channel, configuration.to_oversampling());
+	}
+
+	pub fn get_oversampling(
+		&self,
+		adc: AdcDevice,
+		channel: Channel,
+	) -> Oversampling {
+		self.configurations
+			.get(&adc)
+			.and_then(|channel_map| channel_map.get(&channel))
+			.copied()
+			.unwrap_or_default() // If no oversampling configured, read a single conversion
+	}
+}
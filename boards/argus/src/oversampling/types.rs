@@ -0,0 +1,164 @@
+use core::str::FromStr;
+
+use defmt::Format;
+use heapless::Vec;
+use libm::sqrtf;
+use serde::{Deserialize, Serialize};
+use uor_utils::csv::SerializeCSV;
+
+use crate::adc::driver::types::Voltage;
+use crate::adc::types::AdcDevice;
+use crate::calibration_model::types::ChannelMarker;
+use crate::oversampling::config::MAX_OVERSAMPLING_COUNT;
+use crate::sd::config::MAX_LINE_LENGTH;
+use crate::sd::types::Line;
+
+// Oversampling configured for a single channel of an ADC
+#[derive(Debug, Clone, Copy, Format, Serialize, Deserialize)]
+pub struct OversamplingConfiguration<Channel> {
+	pub adc: AdcDevice,
+	pub channel: Channel,
+
+	// Number of conversions averaged into one reading
+	pub sample_count: u8,
+
+	// Samples further than this many standard deviations from the median are rejected before averaging. 0 disables the rejection
+	pub outlier_threshold: f32,
+}
+
+impl<Channel> OversamplingConfiguration<Channel> {
+	pub fn to_oversampling(&self) -> Oversampling {
+		Oversampling {
+			sample_count: self.sample_count,
+			outlier_threshold: self.outlier_threshold,
+		}
+	}
+}
+
+impl<Channel> SerializeCSV<MAX_LINE_LENGTH> for OversamplingConfiguration<Channel>
+where
+	Channel: ChannelMarker,
+{
+	fn get_csv_header() -> Line {
+		Line::from_str("ADC Index,Channel Index,Sample Count,Outlier Threshold").unwrap()
+	}
+}
+
+// How many conversions are averaged into a single reading, and how outliers among them are rejected
+#[derive(Debug, Clone, Copy, PartialEq, Format, Serialize, Deserialize)]
+pub struct Oversampling {
+	// Number of conversions averaged into one reading, clamped to 1..=MAX_OVERSAMPLING_COUNT
+	pub sample_count: u8,
+
+	// Samples further than this many standard deviations from the median are rejected before averaging. 0 disables the rejection
+	pub outlier_threshold: f32,
+}
+
+impl Default for Oversampling {
+	fn default() -> Self {
+		Self {
+			sample_count: 1, // A single conversion per reading
+			outlier_threshold: 0.0,
+		}
+	}
+}
+
+// Scales the median absolute deviation to the standard deviation of normally distributed samples
+const MEDIAN_ABSOLUTE_DEVIATION_SCALE: f32 = 1.4826;
+
+// Scales the mean absolute deviation to the standard deviation of normally distributed samples, used when over half of the samples are equal
+const MEAN_ABSOLUTE_DEVIATION_SCALE: f32 = 1.2533;
+
+// Statistics of the conversions that were averaged into a reading
+#[derive(Debug, Clone, Copy, PartialEq, Format)]
+pub struct SampleStatistics {
+	// Number of samples averaged, after the outliers were rejected
+	pub sample_count: u8,
+	pub mean: Voltage,
+	pub standard_deviation: Voltage,
+	pub min: Voltage,
+	pub max: Voltage,
+}
+
+impl SampleStatistics {
+	/// Computes the statistics of the samples, rejecting the outliers first if the threshold is set. The samples must not be empty.
+	pub fn from_samples(
+		samples: &[Voltage],
+		outlier_threshold: f32,
+	) -> Self {
+		let statistics = Self::compute(samples.iter().copied());
+		if outlier_threshold <= 0.0 || statistics.standard_deviation == 0.0 {
+			return statistics;
+		}
+
+		// The spread is estimated around the median so that the outliers being rejected cannot inflate it and hide themselves
+		let median_sample = median(samples.iter().copied());
+		let deviations = samples.iter().map(|sample| (sample - median_sample).abs());
+		let median_absolute_deviation = median(deviations.clone());
+		let spread = if median_absolute_deviation > 0.0 {
+			MEDIAN_ABSOLUTE_DEVIATION_SCALE * median_absolute_deviation
+		} else {
+			MEAN_ABSOLUTE_DEVIATION_SCALE * deviations.sum::<f32>() / samples.len() as f32
+		};
+
+		let limit = outlier_threshold * spread;
+		let is_inlier = |sample: &Voltage| (sample - median_sample).abs() <= limit;
+
+		// With a threshold below one standard deviation every sample can be rejected, keep them all in that case
+		match samples.iter().any(is_inlier) {
+			true => Self::compute(samples.iter().copied().filter(is_inlier)),
+			false => statistics,
+		}
+	}
+
+	/// Multiplies every value by the factor, e.g. to convert the statistics from volts to millivolts
+	pub fn scale(
+		&self,
+		factor: f32,
+	) -> Self {
+		Self {
+			sample_count: self.sample_count,
+			mean: self.mean * factor,
+			standard_deviation: self.standard_deviation * factor,
+			min: self.min * factor,
+			max: self.max * factor,
+		}
+	}
+
+	fn compute(samples: impl Iterator<Item = Voltage> + Clone) -> Self {
+		let mut sample_count: u8 = 0;
+		let mut sum = 0.0;
+		let mut min = Voltage::MAX;
+		let mut max = Voltage::MIN;
+		for sample in samples.clone() {
+			sample_count += 1;
+			sum += sample;
+			min = min.min(sample);
+			max = max.max(sample);
+		}
+		let mean = sum / sample_count as f32;
+
+		// Population standard deviation of the samples that were averaged
+		let variance = samples.map(|sample| (sample - mean) * (sample - mean)).sum::<f32>() / sample_count as f32;
+
+		Self {
+			sample_count,
+			mean,
+			standard_deviation: sqrtf(variance),
+			min,
+			max,
+		}
+	}
+}
+
+// Median of at most MAX_OVERSAMPLING_COUNT samples, the mean of the two middle samples for an even count
+fn median(samples: impl Iterator<Item = Voltage>) -> Voltage {
+	let mut sorted: Vec<Voltage, MAX_OVERSAMPLING_COUNT> = samples.take(MAX_OVERSAMPLING_COUNT).collect();
+	sorted.sort_unstable_by(|a, b| a.total_cmp(b));
+
+	let middle = sorted.len() / 2;
+	match sorted.len() % 2 {
+		0 => (sorted[middle - 1] + sorted[middle]) / 2.0,
+		_ => sorted[middle],
+	}
+}
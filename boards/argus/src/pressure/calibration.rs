@@ -1,151 +1,253 @@
 use core::fmt::Debug;
-use core::str::FromStr;
 
 use defmt::Format;
 use heapless::{format, String, Vec};
-use strum::EnumCount;
 
 use crate::adc::types::AdcDevice;
-use crate::linear_transformation::types::LinearTransformation;
-use crate::pressure::config::MAX_CALIBRATION_DATA_POINTS;
+use crate::calibration_model::fit::{compute_r_squared, solve_linear_system};
+use crate::calibration_model::types::{CalibrationModel, CalibrationModelType, CalibrationPoint};
+use crate::pressure::config::{COMPENSATION_REFERENCE_TEMPERATURE, MAX_COMPENSATION_DATA_POINTS, MIN_COMPENSATION_TEMPERATURE_SPREAD};
 use crate::pressure::service::PressureService;
-use crate::pressure::types::{PressureChannel, PressureServiceError};
+use crate::pressure::types::{PressureChannel, PressureServiceError, TemperatureCompensation};
+use crate::sensor::calibration::{
+	calibrate_adc, calibrate_model, capture_stable_reading, check_fit_quality, list_calibrations, restore_calibration, select_calibrated_channel,
+	select_calibration_model,
+};
+use crate::sensor::types::CalibratedSensorService;
+use crate::tare::service::{tare_all_channels, tare_channel};
+use crate::tare::types::TareOffset;
 
 // Calibration logic has been separated into its own file for clarity
 impl<const ADC_COUNT: usize> PressureService<ADC_COUNT> {
 	pub async fn calibrate(&mut self) -> Result<(), PressureServiceError> {
-		// Prompt for ADC index
-		let adc_index: usize = self.prompt("Starting pressure calibration. Enter ADC index (Starts from 0):\"\n").await?;
-		if adc_index >= AdcDevice::COUNT {
-			self.send_message("Invalid ADC index.\n").await?;
+		let Some((adc, channel)) = select_calibrated_channel(self).await? else {
 			return Ok(());
+		};
+
+		// Prompt for operation
+		let operation: u8 = self
+			.prompt("Enter operation (0 = Calibrate channel, 1 = Tare channel, 2 = Tare all channels, 3 = History, 4 = Restore calibration):\n")
+			.await?;
+		match operation {
+			0 => {}
+			1 => {
+				let offset = tare_channel(self, adc, channel).await?;
+				let message: String<64> =
+					format!("Tare complete. Offset: {:.2} {}\n", offset, Self::UNIT).map_err(|_| PressureServiceError::FormatError)?;
+				self.send_message(message.as_str()).await?;
+				return Ok(());
+			}
+			2 => {
+				tare_all_channels(self).await;
+				self.send_message("Tare complete.\n").await?;
+				return Ok(());
+			}
+			3 => return list_calibrations(self, adc, channel).await,
+			4 => return restore_calibration(self, adc, channel).await,
+			_ => {
+				self.send_message("Invalid operation.\n").await?;
+				return Ok(());
+			}
 		}
-		let adc = AdcDevice::from(adc_index);
 
-		// Prompt for channel
-		let channel_index: usize = self.prompt("Enter pressure channel index (Starts from 0):\"\n").await?;
-		if channel_index >= PressureChannel::COUNT {
-			self.send_message("Invalid channel index.\n").await?;
+		// Optionally calibrate the ADC offset and gain before collecting data points
+		if !calibrate_adc(self, adc, channel.to_analog_input_channel_pair()).await? {
 			return Ok(());
 		}
-		let channel = PressureChannel::from(channel_index);
 
-		{
-			let message: String<64> =
-				format!("Calibrating ADC {}, Channel {}\n", adc_index, channel_index).map_err(|_| PressureServiceError::FormatError)?;
-			self.send_message(message.as_str()).await?;
+		match select_calibration_model(self).await? {
+			Some(CalibrationModelType::TemperatureCompensated) => self.calibrate_temperature_compensation(adc, channel).await,
+			Some(model) => calibrate_model(self, adc, channel, model).await,
+			None => Ok(()),
 		}
+	}
 
+	// Fits a pressure transducer whose zero and span drift with the manifold temperature.
+	// Data points are taken at several manifold temperatures, read from the NTC of the same ADC.
+	async fn calibrate_temperature_compensation(
+		&mut self,
+		adc: AdcDevice,
+		channel: PressureChannel,
+	) -> Result<(), PressureServiceError> {
 		// Prompt for number of data points
-		let data_points_count: u8 = self.prompt("Enter number of data points to use for the linear fit:\n").await?;
-		if data_points_count < 2 {
-			self.send_message("Minimum 2 data points is required.\n").await?;
+		let data_points_count: u8 = self.prompt("Enter number of data points to use for the temperature compensated fit:\n").await?;
+		if data_points_count < 4 {
+			self.send_message("Minimum 4 data points is required.\n").await?;
 			return Ok(());
 		}
-		if data_points_count > MAX_CALIBRATION_DATA_POINTS as u8 {
+		if data_points_count > MAX_COMPENSATION_DATA_POINTS as u8 {
 			let error_message: String<64> =
-				format!("Too many data points. Maximum is {}.\n", MAX_CALIBRATION_DATA_POINTS).map_err(|_| PressureServiceError::FormatError)?;
+				format!("Too many data points. Maximum is {}.\n", MAX_COMPENSATION_DATA_POINTS).map_err(|_| PressureServiceError::FormatError)?;
 			self.send_message(error_message.as_str()).await?;
 			return Ok(());
 		}
-
-		// Deregister any existing transformation for this channel
-		self.linear_transformation_service.deregister_transformation(adc, channel);
+		self.send_message("Take data points at several pressures and at several manifold temperatures.\n").await?;
 
 		// Start collecting data points
-		let mut calibration_data_points: Vec<CalibrationDataPoint, MAX_CALIBRATION_DATA_POINTS> = Vec::new();
-		for data_point_index in 0..data_points_count {
-			let message: String<64> =
-				format!("Data Point #{}. Enter expected value:\n", data_point_index + 1).map_err(|_| PressureServiceError::FormatError)?;
+		// Data points whose voltage doesn't settle are rejected and asked for again, unless the operator aborts the calibration
+		let mut calibration_data_points: Vec<CompensationDataPoint, MAX_COMPENSATION_DATA_POINTS> = Vec::new();
+		while calibration_data_points.len() < data_points_count as usize {
+			let message: String<64> = format!("Data Point #{}. Enter expected value:\n", calibration_data_points.len() + 1)
+				.map_err(|_| PressureServiceError::FormatError)?;
 			let expected_pressure: f64 = self.prompt(message.as_str()).await?;
-			let measured_pressure: f64 = self.read_pressure(adc, channel).await?.pressure;
-			let data_point = CalibrationDataPoint {
+			let Some(reading) = capture_stable_reading(self, adc, channel, |reading| reading.voltage as f64).await? else {
+				let retry: u8 = self
+					.prompt("Reading not stable, data point rejected. Enter 0 to take it again once the reference has settled or 1 to abort:\n")
+					.await?;
+				if retry != 0 {
+					self.send_message("Calibration aborted.\n").await?;
+					return Ok(());
+				}
+				continue;
+			};
+
+			self.refresh_ntc_reading(adc).await?;
+			let Some(temperature) = self.last_ntc_reading[adc as usize] else {
+				self.send_message("Manifold temperature not available, aborting calibration.\n").await?;
+				return Ok(());
+			};
+			let data_point = CompensationDataPoint {
 				expected_pressure,
-				measured_pressure,
+				voltage: reading.mean,
+				voltage_uncertainty: reading.uncertainty,
+				temperature: temperature as f64,
 			};
 			calibration_data_points.push(data_point).unwrap(); // Safe due to prior checks
 
-			let confirmation_message: String<64> = format!("Expected = {:.2}, Measured = {:.2}\n", expected_pressure, measured_pressure)
-				.map_err(|_| PressureServiceError::FormatError)?;
+			let confirmation_message: String<128> = format!(
+				"Expected = {:.2}, Voltage = {:.4} ± {:.4} mV, Temperature = {:.2} °C\n",
+				expected_pressure, reading.mean, reading.uncertainty, temperature
+			)
+			.map_err(|_| PressureServiceError::FormatError)?;
 			self.send_message(confirmation_message.as_str()).await?;
 		}
 
-		// Perform Ordinary Least Squares Fit
-		let transformation = self.run_ordinary_least_squares_fit(adc, channel, calibration_data_points);
-		let result_message: String<128> = format!(
-			"Ordinary Least Squares Fit complete. Scale: {:.6}, Offset: {:.2} °C\n",
-			transformation.scale, transformation.offset
+		// The temperature coefficients can't be fitted from data points taken at (nearly) the same temperature
+		let coldest = calibration_data_points.iter().map(|point| point.temperature).fold(f64::MAX, f64::min);
+		let warmest = calibration_data_points.iter().map(|point| point.temperature).fold(f64::MIN, f64::max);
+		if warmest - coldest < MIN_COMPENSATION_TEMPERATURE_SPREAD {
+			let error_message: String<96> = format!(
+				"Temperature spread of {:.2} °C is too small. Minimum is {:.2} °C.\n",
+				warmest - coldest,
+				MIN_COMPENSATION_TEMPERATURE_SPREAD
+			)
+			.map_err(|_| PressureServiceError::FormatError)?;
+			self.send_message(error_message.as_str()).await?;
+			return Ok(());
+		}
+
+		let Some(compensation) = self.run_temperature_compensation_fit(adc, channel, &calibration_data_points) else {
+			self.send_message("Data points don't determine the fit, use more distinct pressures and temperatures.\n").await?;
+			return Ok(());
+		};
+		let result_message: String<192> = format!(
+			"Temperature compensated fit complete. Zero: {:.4} psi + {:.6} psi/°C, Span: {:.6} psi/mV + {:.8} psi/mV/°C\n",
+			compensation.zero, compensation.zero_temperature_coefficient, compensation.span, compensation.span_temperature_coefficient
 		)
 		.map_err(|_| PressureServiceError::FormatError)?;
 		self.send_message(result_message.as_str()).await?;
+		// With only four data points the four coefficients pass through every point, the R² would always be 1
+		let r_squared = if calibration_data_points.len() > 4 {
+			compute_r_squared(calibration_data_points.iter().map(|data_point| {
+				(
+					data_point.expected_pressure,
+					compensation.apply(data_point.voltage, data_point.temperature),
+				)
+			}))
+		} else {
+			None
+		};
+		if !check_fit_quality(self, r_squared).await? {
+			return Ok(());
+		}
 
-		// Update calibration for the channel
-		self.linear_transformation_service.save_transformation(transformation).await?;
+		// Update calibration for the channel, the new zero replaces any tare offset
+		// Temperature compensations are kept in their own file, and recorded in the calibration history so they can be restored
+		let notes: String<256> = self.prompt("Enter calibration notes (optional):\n").await?;
+		let local_session = self.session_service.lock().await.current_session;
+		self.save_temperature_compensation(compensation).await?;
+		self.tare_service.reset_offset(adc, channel).await?;
+
+		let points = calibration_data_points.iter().map(|data_point| CalibrationPoint {
+			measured: data_point.voltage,
+			expected: data_point.expected_pressure,
+			calibrated: compensation.apply(data_point.voltage, data_point.temperature),
+			uncertainty: data_point.voltage_uncertainty,
+		});
+		let record = self
+			.calibration_model_service
+			.record_calibration(
+				adc,
+				channel,
+				CalibrationModelType::TemperatureCompensated,
+				compensation.terms().into_iter(),
+				points,
+				local_session,
+				notes.as_str(),
+			)
+			.await?;
+		let message: String<64> = format!("Calibration #{} saved.\n", record.id).map_err(|_| PressureServiceError::FormatError)?;
+		self.send_message(message.as_str()).await?;
 		Ok(())
 	}
 
-	fn run_ordinary_least_squares_fit(
+	// Least squares fit of pressure = zero + zero_tc * dT + span * V + span_tc * V * dT, solving the normal equations.
+	// Returns None if the data points don't determine all four coefficients.
+	fn run_temperature_compensation_fit(
 		&self,
 		adc: AdcDevice,
 		channel: PressureChannel,
-		data_points: Vec<CalibrationDataPoint, MAX_CALIBRATION_DATA_POINTS>,
-	) -> LinearTransformation<PressureChannel, f64> {
-		// Keeping all types as f64
-		let data_points_count: f64 = data_points.len() as f64;
-
-		// Accumulate sums
-		let mut sum_x: f64 = 0.0; // measured
-		let mut sum_y: f64 = 0.0; // expected
-		let mut sum_xx: f64 = 0.0; // measured^2
-		let mut sum_xy: f64 = 0.0; // measured * expected
-
+		data_points: &[CompensationDataPoint],
+	) -> Option<TemperatureCompensation> {
+		// Normal equations (X^T X) c = X^T y
+		let mut matrix = [[0.0f64; 4]; 4];
+		let mut rhs = [0.0f64; 4];
 		for data_point in data_points.iter() {
-			let x = data_point.measured_pressure;
-			let y = data_point.expected_pressure;
-			sum_x += x;
-			sum_y += y;
-			sum_xx += x * x;
-			sum_xy += x * y;
+			let temperature_difference = data_point.temperature - COMPENSATION_REFERENCE_TEMPERATURE;
+			let features = [1.0, temperature_difference, data_point.voltage, data_point.voltage * temperature_difference];
+			for row in 0..4 {
+				for column in 0..4 {
+					matrix[row][column] += features[row] * features[column];
+				}
+				rhs[row] += features[row] * data_point.expected_pressure;
+			}
 		}
-
-		// Denominator for slope (variance term)
-		let denom = data_points_count * sum_xx - sum_x * sum_x;
-		let scale = (data_points_count * sum_xy - sum_x * sum_y) / denom;
-		let offset = (sum_y - scale * sum_x) / data_points_count;
-
-		LinearTransformation { adc, channel, scale, offset }
-	}
-
-	async fn prompt<T>(
-		&mut self,
-		prompt: &str,
-	) -> Result<T, PressureServiceError>
-	where
-		T: FromStr,
-		<T as FromStr>::Err: core::fmt::Debug, {
-		let mut serial_service = self.serial_service.lock().await;
-		let mut input = String::<256>::new();
-		serial_service.write_str(prompt).await?;
-		serial_service.read_line(&mut input).await?;
-		Ok(input.trim().parse().map_err(|_| PressureServiceError::FormatError)?)
-	}
-
-	async fn send_message(
-		&mut self,
-		message: &str,
-	) -> Result<(), PressureServiceError> {
-		let mut serial_service = self.serial_service.lock().await;
-		serial_service.write_str(message).await?;
-		Ok(())
+		let coefficients = solve_linear_system(matrix, rhs, 4)?;
+
+		Some(TemperatureCompensation {
+			adc,
+			channel,
+			reference_temperature: COMPENSATION_REFERENCE_TEMPERATURE,
+			zero: coefficients[0],
+			zero_temperature_coefficient: coefficients[1],
+			span: coefficients[2],
+			span_temperature_coefficient: coefficients[3],
+		})
 	}
 }
 
-// Represents a single calibration data point used to perform a linear regression
+// Represents a single data point of a temperature compensated calibration
 #[derive(Debug, Clone, Copy, Format)]
-pub struct CalibrationDataPoint {
-	// Expected pressure value in degrees Celsius measured by the calibration instrument
+pub struct CompensationDataPoint {
+	// Expected pressure in psi measured by the calibration instrument
 	pub expected_pressure: f64,
 
-	// Value measured by the ADC
-	pub measured_pressure: f64,
+	// Average of the stable transducer voltages in millivolts measured by the ADC
+	pub voltage: f64,
+
+	// Standard error of the voltage in millivolts
+	pub voltage_uncertainty: f64,
+
+	// Manifold temperature in degrees Celsius measured by the NTC
+	pub temperature: f64,
+}
+
+// Calibration of a channel while it is deregistered to be measured raw, see PressureService::deregister_calibration
+#[derive(Debug, Clone)]
+pub struct PreviousCalibration {
+	adc: AdcDevice,
+	channel: PressureChannel,
+	model: Option<CalibrationModel<f64>>,
+	temperature_compensation: Option<TemperatureCompensation>,
+	tare_offset: Option<TareOffset<PressureChannel>>,
 }
@@ -0,0 +1,119 @@
+use core::str::FromStr;
+
+use defmt::{error, info};
+use strum::EnumCount;
+use uor_utils::csv::SerializeCSV;
+
+use crate::adc::types::AdcDevice;
+use crate::pressure::config::TEMPERATURE_COMPENSATIONS_FILE_NAME;
+use crate::pressure::service::PressureService;
+use crate::pressure::types::{PressureChannel, PressureServiceError, TemperatureCompensation};
+use crate::sd::service::SDCardService;
+use crate::sd::types::{FileName, OperationScope, SdCardError};
+
+// Persistence of the temperature-compensated calibrations, the routine that fits them lives in calibration.rs
+impl<const ADC_COUNT: usize> PressureService<ADC_COUNT> {
+	pub async fn load_temperature_compensations(&mut self) -> Result<(), PressureServiceError> {
+		let mut sd_card_service = self.sd_card_service.lock().await;
+		self.temperature_compensations = Self::read_temperature_compensations(&mut sd_card_service)?;
+		Ok(())
+	}
+
+	// Reads the latest saved compensation of each ADC and channel, later lines take precedence over earlier ones
+	fn read_temperature_compensations(
+		sd_card_service: &mut SDCardService
+	) -> Result<[[Option<TemperatureCompensation>; PressureChannel::COUNT]; ADC_COUNT], PressureServiceError> {
+		let mut compensations = [[None; PressureChannel::COUNT]; ADC_COUNT];
+		let result = sd_card_service.read(
+			OperationScope::Root,
+			FileName::from_str(TEMPERATURE_COMPENSATIONS_FILE_NAME).unwrap(),
+			|line| {
+				if *line == TemperatureCompensation::get_csv_header() {
+					return true; // Skip header line
+				}
+
+				match TemperatureCompensation::from_csv_line(line) {
+					Ok(compensation) => {
+						if (compensation.adc as usize) < ADC_COUNT {
+							compensations[compensation.adc as usize][compensation.channel as usize] = Some(compensation);
+							info!("Loaded temperature compensation: {:?}", compensation);
+						}
+					}
+					Err(e) => {
+						error!("Error parsing temperature compensation for line '{}': {:?}", line.as_str(), e);
+					}
+				}
+				true // Continue reading
+			},
+		);
+
+		match result {
+			Ok(_) => (),
+			Err(SdCardError::NotFound) => {
+				// Without compensations every channel keeps using its linear transformation, ignore this error.
+				info!("Temperature compensations file not found, using linear transformations only");
+			}
+			Err(e) => return Err(e.into()),
+		}
+		Ok(compensations)
+	}
+
+	/// Removes the temperature compensation of the channel, e.g. once a new calibration replaces it.
+	/// The file is rewritten with the compensations of the other channels, so the removed one isn't loaded again on the next restart.
+	pub async fn remove_temperature_compensation(
+		&mut self,
+		adc: AdcDevice,
+		channel: PressureChannel,
+	) -> Result<(), PressureServiceError> {
+		self.temperature_compensations[adc as usize][channel as usize] = None;
+
+		let mut sd_card_service = self.sd_card_service.lock().await;
+		let mut saved_compensations = Self::read_temperature_compensations(&mut sd_card_service)?;
+		if saved_compensations[adc as usize][channel as usize].take().is_none() {
+			return Ok(()); // Nothing saved for the channel
+		}
+
+		info!("Removing temperature compensation of {:?} {:?}", adc, channel);
+		let path = FileName::from_str(TEMPERATURE_COMPENSATIONS_FILE_NAME).unwrap();
+		sd_card_service.delete(OperationScope::Root, path.clone())?;
+		sd_card_service.write(OperationScope::Root, path.clone(), TemperatureCompensation::get_csv_header())?;
+		for compensation in saved_compensations.iter().flatten().flatten() {
+			sd_card_service.write(OperationScope::Root, path.clone(), compensation.to_csv_line())?;
+		}
+		Ok(())
+	}
+
+	pub async fn save_temperature_compensation(
+		&mut self,
+		compensation: TemperatureCompensation,
+	) -> Result<(), PressureServiceError> {
+		info!("Saving temperature compensation: {:?}", compensation);
+		let mut sd_card_service = self.sd_card_service.lock().await;
+		let path = FileName::from_str(TEMPERATURE_COMPENSATIONS_FILE_NAME).unwrap();
+		if !(sd_card_service.file_exists(OperationScope::Root, path.clone())?) {
+			sd_card_service.write(OperationScope::Root, path.clone(), TemperatureCompensation::get_csv_header())?;
+		}
+
+		sd_card_service.write(OperationScope::Root, path.clone(), compensation.to_csv_line())?;
+		self.temperature_compensations[compensation.adc as usize][compensation.channel as usize] = Some(compensation);
+
+		Ok(())
+	}
+
+	/// Converts the transducer voltage in millivolts to a pressure in psi.
+	/// The temperature compensation of the channel takes precedence over its linear transformation, but needs a manifold temperature.
+	/// Until the NTC has been read, or while it can't be read, the linear transformation is used instead. Such readings carry a NaN
+	/// manifold temperature and refresh_ntc_reading warns about the fallback.
+	pub fn compute_pressure(
+		&self,
+		adc: AdcDevice,
+		channel: PressureChannel,
+		voltage: f64,
+	) -> f64 {
+		let compensation = self.temperature_compensations[adc as usize][channel as usize];
+		match (compensation, self.last_ntc_reading[adc as usize]) {
+			(Some(compensation), Some(temperature)) => compensation.apply(voltage, temperature as f64),
+			_ => self.calibration_model_service.apply_model(adc, channel, voltage),
+		}
+	}
+}
@@ -1,15 +1,98 @@
+use strum::EnumCount;
+
+use crate::adc::driver::types::{AnalogChannel, Gain, SensorBiasMagnitude};
+use crate::adc::types::AdcDevice;
+use crate::calibration_model::types::{LowQualityFitAction, StabilityCriteria};
+use crate::pressure::types::{NtcConfiguration, NtcModel, PressureChannel};
+use crate::scan_sequencer::types::{scan_every_channel, ScanEntry};
+
 // Size of the queue used to send pressure readings from the pressure service to the SD card service
 pub const PRESSURE_READING_QUEUE_SIZE: usize = 16;
 
-// Maximum number of calibration data points allowed to be collected during a calibration session per pressure channel
-pub const MAX_CALIBRATION_DATA_POINTS: usize = 10;
+// Minimum R² of a pressure calibration fit over its data points
+pub const MIN_CALIBRATION_R_SQUARED: f64 = 0.999;
+
+// Whether a pressure calibration fit below MIN_CALIBRATION_R_SQUARED is still saved after warning the operator, or refused
+pub const LOW_QUALITY_FIT_ACTION: LowQualityFitAction = LowQualityFitAction::Refuse;
+
+// When the readings of a calibration data point are stable. Channels are read without calibration, so the limits are in millivolts
+pub const STABILITY_CRITERIA: StabilityCriteria = StabilityCriteria {
+	max_standard_deviation: 0.02,
+	max_drift: 0.02,
+	reading_interval: 200,
+	timeout: 30000,
+};
+
+// Maximum number of data points collected for a temperature-compensated calibration, spread over several manifold temperatures
+pub const MAX_COMPENSATION_DATA_POINTS: usize = 30;
+
+// Minimum difference between the coldest and warmest data point of a temperature-compensated calibration
+// Below this the temperature coefficients are dominated by noise
+pub const MIN_COMPENSATION_TEMPERATURE_SPREAD: f64 = 10.0; // degrees Celsius
+
+// Temperature the zero and span of a temperature-compensated calibration are referred to
+pub const COMPENSATION_REFERENCE_TEMPERATURE: f64 = 25.0; // degrees Celsius
+
+// File name used to read/write the calibration models applied to pressure readings to/from the SD card
+// Calibration models are stored in CSV format
+pub const CALIBRATION_MODELS_FILE_NAME: &str = "t_pres.csv"; // Cannot be longer than 12 characters
+
+// File name used to read/write the history of the calibrations saved for the pressure channels to/from the SD card
+// Each calibration is kept with its fit quality, data points and operator notes, so previous calibrations can be listed and restored
+pub const CALIBRATION_HISTORY_FILE_NAME: &str = "h_pres.csv"; // Cannot be longer than 12 characters
 
-// File name used to read/write linear transformations that applied to pressure readings to/from the SD card
-// Linear transformations are stored in CSV format
-pub const LINEAR_TRANSFORMATIONS_FILE_NAME: &str = "t_pres.csv"; // Cannot be longer than 12 characters
+// File name used to read/write the temperature-compensated calibrations of the pressure transducers to/from the SD card
+// A channel with a temperature compensation uses it instead of its linear transformation. Later lines take precedence over earlier ones
+pub const TEMPERATURE_COMPENSATIONS_FILE_NAME: &str = "tc_pres.csv"; // Cannot be longer than 12 characters
 
-// Resistance of the NTC at 25 °C.
+// File name used to read the number of conversions averaged into each pressure reading from the SD card
+// Oversampling configurations are stored in CSV format, channels without one are read with a single conversion
+pub const OVERSAMPLING_FILE_NAME: &str = "o_pres.csv"; // Cannot be longer than 12 characters
+
+// File name used to read/write the zero offsets subtracted from the calibrated pressure readings to/from the SD card
+// Tare offsets are stored in CSV format with the session they were taken in, a copy of the offsets in use is written to each session directory.
+pub const TARE_FILE_NAME: &str = "z_pres.csv"; // Cannot be longer than 12 characters
+
+// Number of readings averaged into the offset of a tare
+pub const TARE_READING_COUNT: usize = 20;
+
+// Channels tared automatically each time recording starts, indexed by ADC and then channel
+// Only enable this for pressure transducers that are guaranteed to be unloaded when recording starts
+pub const AUTO_TARE_CHANNELS: [[bool; PressureChannel::COUNT]; AdcDevice::COUNT] = [[false; PressureChannel::COUNT]; AdcDevice::COUNT];
+
+// Resistance of the NTC at 25 °C and its B value (B25/85) from the thermistor datasheet.
 pub const NTC_RESISTANCE_AT_25C: f32 = 10000.0; // Ohms
+pub const NTC_BETA: f32 = 3950.0; // Kelvin
+
+// How the manifold NTC on each ADC is excited and measured.
+// Note: This is based on Argus V2 design as of September 22, 2025, the NTC sits in the footprint the RTD uses on the temperature board
+// The NTC is excited by the board in series with R6 (1k) to ground, and the AIN8-9 sequence is flipped accidentally
+// so AIN9 is before the NTC and AIN8 is after the NTC. The R6 voltage is measured against AINCOM, so the ratio of both gives the
+// NTC resistance independently of the excitation voltage.
+pub const NTC_CONFIGURATION: NtcConfiguration = NtcConfiguration {
+	sense_pair: (AnalogChannel::AIN9, AnalogChannel::AIN8),
+	reference_pair: (AnalogChannel::AIN8, AnalogChannel::AINCOM),
+	reference_resistance: 1000.0,
+	model: NtcModel::Beta {
+		resistance_at_25c: NTC_RESISTANCE_AT_25C,
+		beta: NTC_BETA,
+	},
+	gain: Gain::G1, // The divider produces volts, avoid saturating the ADC
+};
 
 // Measure NTCs at a slower interval than the pressures
 pub const NTC_MEASUREMENT_INTERVAL: u64 = 5000; // milliseconds
+
+// How often each channel is checked for a disconnected pressure transducer
+pub const OPEN_CIRCUIT_CHECK_INTERVAL: u64 = 10000; // milliseconds
+
+// Sensor bias current used for the open-circuit check. Only a small offset develops across the bridge resistance when connected
+pub const OPEN_CIRCUIT_BIAS_MAGNITUDE: SensorBiasMagnitude = SensorBiasMagnitude::Current2uA;
+
+// Which pressure channels are sampled, how often, and in what order, see the scan sequencer. Every channel is sampled as fast as the ADCs allow
+pub const PRESSURE_SCAN_LIST: [ScanEntry<PressureChannel>; AdcDevice::COUNT * PressureChannel::COUNT] = scan_every_channel([
+	PressureChannel::Channel1,
+	PressureChannel::Channel2,
+	PressureChannel::Channel3,
+	PressureChannel::Channel4,
+]);
@@ -1,5 +1,7 @@
 pub mod calibration;
+pub mod compensation;
 pub mod config;
+pub mod ntc;
 pub mod service;
 pub mod tasks;
 pub mod types;
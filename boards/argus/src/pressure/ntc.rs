@@ -0,0 +1,32 @@
+use libm::logf;
+
+// Offset between degrees Celsius and Kelvin
+const KELVIN_OFFSET: f32 = 273.15;
+
+// For NTC thermistors.
+// Uses the Beta equation, a simplified Steinhart-Hart equation that only needs the resistance at 25 °C and the B value from the datasheet.
+// 1/T = 1/T0 + ln(R/R0)/B, with T and T0 = 25 °C in Kelvin
+// Accurate to about ±1 °C within the temperature range the B value was specified for
+pub fn convert_resistance_to_temperature_beta(
+	resistance_at_25c: f32,
+	beta: f32,
+	measured_resistance: f32,
+) -> f32 {
+	let inverse_temperature = 1.0 / (25.0 + KELVIN_OFFSET) + logf(measured_resistance / resistance_at_25c) / beta;
+	1.0 / inverse_temperature - KELVIN_OFFSET
+}
+
+// For NTC thermistors.
+// Uses the Steinhart-Hart equation with coefficients from the thermistor datasheet or fitted to three calibration points.
+// 1/T = A + B*ln(R) + C*ln(R)^3, with T in Kelvin
+// See https://www.ti.com/lit/an/sbaa275a/sbaa275a.pdf
+pub fn convert_resistance_to_temperature_steinhart_hart(
+	a: f32,
+	b: f32,
+	c: f32,
+	measured_resistance: f32,
+) -> f32 {
+	let log_resistance = logf(measured_resistance);
+	let inverse_temperature = a + b * log_resistance + c * log_resistance * log_resistance * log_resistance;
+	1.0 / inverse_temperature - KELVIN_OFFSET
+}
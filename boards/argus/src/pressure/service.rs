@@ -1,35 +1,79 @@
-use defmt::{error, info};
-use embassy_time::{Instant, Timer};
+use defmt::{error, info, warn};
+use embassy_time::Instant;
 use strum::EnumCount;
 use uor_peripherals::serial::peripheral::UORSerial;
+use uor_utils::messages::argus::calibration::calibration_response::CalibrationResponse;
+use uor_utils::messages::argus::envelope::envelope::Message as EnvelopeMessage;
 use uor_utils::utils::types::AsyncMutex;
 
-use crate::adc::driver::types::{DataRate, Filter, Gain, ReferenceRange};
 use crate::adc::service::AdcService;
 use crate::adc::types::AdcDevice;
-use crate::linear_transformation::service::LinearTransformationService;
-use crate::pressure::config::LINEAR_TRANSFORMATIONS_FILE_NAME;
-use crate::pressure::types::{PressureChannel, PressureReading, PressureReadingQueue, PressureServiceError};
+use crate::calibration_model::service::CalibrationModelService;
+use crate::calibration_model::types::{CalibrationModelType, CalibrationRecord, LowQualityFitAction, StabilityCriteria};
+use crate::calibration_protocol::session::CalibrationSession;
+use crate::oversampling::service::OversamplingService;
+use crate::oversampling::types::SampleStatistics;
+use crate::pressure::calibration::PreviousCalibration;
+use crate::pressure::config::{
+	AUTO_TARE_CHANNELS, CALIBRATION_HISTORY_FILE_NAME, CALIBRATION_MODELS_FILE_NAME, LOW_QUALITY_FIT_ACTION, MIN_CALIBRATION_R_SQUARED,
+	NTC_CONFIGURATION, OPEN_CIRCUIT_BIAS_MAGNITUDE, OPEN_CIRCUIT_CHECK_INTERVAL, OVERSAMPLING_FILE_NAME, PRESSURE_SCAN_LIST, STABILITY_CRITERIA,
+	TARE_FILE_NAME, TARE_READING_COUNT,
+};
+use crate::pressure::types::{
+	NtcConfiguration, PressureChannel, PressureReading, PressureReadingQueue, PressureServiceError, TemperatureCompensation,
+};
+use crate::scan_sequencer::service::ScanSequencer;
 use crate::sd::service::SDCardService;
+use crate::sensor::acquisition::{read_channel, OpenCircuitMonitor};
+use crate::sensor::setup::configure_adcs;
+use crate::sensor::types::{CalibratedSensorService, ChannelKind};
 use crate::session::service::SessionService;
+use crate::tare::service::TareService;
+use crate::tare::types::TaredSensorService;
 
 // A channel for buffering the pressure readings and decoupling the logging to sd task from the measurement task
 pub static PRESSURE_READING_QUEUE: PressureReadingQueue = PressureReadingQueue::new();
 
 pub struct PressureService<const ADC_COUNT: usize> {
 	// Other services are passed by a mutex to ensure safe concurrent access
-	pub adc_service: &'static AsyncMutex<AdcService<ADC_COUNT>>,
+	pub adc_service: &'static AdcService<ADC_COUNT>,
 	pub sd_card_service: &'static AsyncMutex<SDCardService>,
 	pub serial_service: &'static AsyncMutex<UORSerial>,
 	pub session_service: &'static AsyncMutex<SessionService>,
 
-	// Linear transformations that are applied on top of the raw readings for each ADC and channel
-	pub linear_transformation_service: LinearTransformationService<PressureChannel, f64, ADC_COUNT, { PressureChannel::COUNT }>,
+	// Store the last NTC reading in Celsius to record the manifold temperature with each pressure reading
+	// This is cached here to avoid reading the NTC for every pressure reading, the manifold temperature changes slowly
+	// We have one NTC per ADC, so we store an array of last readings
+	pub last_ntc_reading: [Option<f32>; ADC_COUNT],
+
+	// Checks the channels for a disconnected sensor before they are read
+	pub open_circuit_monitor: OpenCircuitMonitor<ADC_COUNT, { PressureChannel::COUNT }>,
+
+	// Calibration models that are applied on top of the raw readings for each ADC and channel
+	pub calibration_model_service: CalibrationModelService<PressureChannel, f64, ADC_COUNT, { PressureChannel::COUNT }>,
+
+	// Temperature-compensated calibrations for each ADC and channel, used instead of the linear transformation when present
+	pub temperature_compensations: [[Option<TemperatureCompensation>; PressureChannel::COUNT]; ADC_COUNT],
+
+	// Zero offsets subtracted from the calibrated readings of each ADC and channel
+	pub tare_service: TareService<PressureChannel, ADC_COUNT, { PressureChannel::COUNT }>,
+
+	// Number of conversions averaged into the readings of each ADC and channel
+	pub oversampling_service: OversamplingService<PressureChannel, ADC_COUNT, { PressureChannel::COUNT }>,
+
+	// Decides which channels the measurement task reads and when
+	pub scan_sequencer: ScanSequencer<PressureChannel, ADC_COUNT, { PRESSURE_SCAN_LIST.len() }>,
+
+	// Calibration driven by the ground application over the calibration protocol, None outside of a session
+	pub calibration_session: Option<CalibrationSession<PressureChannel>>,
+
+	// Calibration of the channel being calibrated, registered again unless the new calibration is saved
+	pub previous_calibration: Option<PreviousCalibration>,
 }
 
 impl<const ADC_COUNT: usize> PressureService<ADC_COUNT> {
 	pub fn new(
-		adc_service: &'static AsyncMutex<AdcService<ADC_COUNT>>,
+		adc_service: &'static AdcService<ADC_COUNT>,
 		sd_card_service: &'static AsyncMutex<SDCardService>,
 		serial_service: &'static AsyncMutex<UORSerial>,
 		session_service: &'static AsyncMutex<SessionService>,
@@ -39,28 +83,61 @@ impl<const ADC_COUNT: usize> PressureService<ADC_COUNT> {
 			sd_card_service,
 			serial_service,
 			session_service,
-			linear_transformation_service: LinearTransformationService::new(sd_card_service, LINEAR_TRANSFORMATIONS_FILE_NAME),
+			last_ntc_reading: [None; ADC_COUNT],
+			open_circuit_monitor: OpenCircuitMonitor::new(OPEN_CIRCUIT_CHECK_INTERVAL, OPEN_CIRCUIT_BIAS_MAGNITUDE),
+			calibration_model_service: CalibrationModelService::new(
+				sd_card_service,
+				CALIBRATION_MODELS_FILE_NAME,
+				CALIBRATION_HISTORY_FILE_NAME,
+			),
+			temperature_compensations: [[None; PressureChannel::COUNT]; ADC_COUNT],
+			tare_service: TareService::new(sd_card_service, session_service, TARE_FILE_NAME, AUTO_TARE_CHANNELS),
+			oversampling_service: OversamplingService::new(sd_card_service, OVERSAMPLING_FILE_NAME),
+			scan_sequencer: ScanSequencer::new(PRESSURE_SCAN_LIST),
+			calibration_session: None,
+			previous_calibration: None,
 		}
 	}
 
+	/// Configures the ADCs wired to its kind and loads the files of the service, see sensor::setup::prepare_adcs for the steps run before.
 	pub async fn setup(&mut self) -> Result<(), PressureServiceError> {
-		// Delay for 100ms to ensure ADCs are powered up
-		Timer::after_millis(100).await;
-
-		for driver in self.adc_service.lock().await.drivers.iter_mut() {
-			driver.reference_range = ReferenceRange::Avdd;
-			driver.data_rate = DataRate::Sps100;
-			driver.filter = Filter::Sinc3;
-			driver.enable_internal_reference = true;
-			driver.gain = Gain::G32;
-			driver.delay_after_setting_channel = 50; // 50 ms delay to allow the ADC to stabilize after switching channels
-			driver.apply_configurations().await?;
+		// The NTC inputs are converted at their own gain, the pressure transducers use the configuration shared by every kind
+		configure_adcs(self.adc_service, ChannelKind::Pressure, |driver| {
+			let ntc_profile = NTC_CONFIGURATION.acquisition_profile(driver.current_profile());
+			let (sense_positive, sense_negative) = NTC_CONFIGURATION.sense_pair;
+			let (reference_positive, reference_negative) = NTC_CONFIGURATION.reference_pair;
+			driver.set_acquisition_profile(sense_positive, sense_negative, ntc_profile)?;
+			driver.set_acquisition_profile(reference_positive, reference_negative, ntc_profile)
+		})
+		.await;
+
+		match self.calibration_model_service.load_models().await {
+			Err(e) => error!("Failed to load calibration models: {:?}", e),
+			_ => {}
 		}
 
-		match self.linear_transformation_service.load_transformations().await {
-			Err(e) => error!("Failed to load linear transformations: {:?}", e),
+		match self.load_temperature_compensations().await {
+			Err(e) => error!("Failed to load temperature compensations: {:?}", e),
 			_ => {}
 		}
+
+		match self.oversampling_service.load_configurations().await {
+			Err(e) => error!("Failed to load oversampling configurations: {:?}", e),
+			_ => {}
+		}
+
+		match self.tare_service.load_offsets().await {
+			Err(e) => error!("Failed to load tare offsets: {:?}", e),
+			_ => {}
+		}
+
+		// Record the offsets in use alongside the session data. Skipped if the session couldn't be started
+		if self.session_service.lock().await.current_session.is_some() {
+			match self.tare_service.save_session_offsets().await {
+				Err(e) => error!("Failed to record tare offsets in the session: {:?}", e),
+				_ => {}
+			}
+		}
 		Ok(())
 	}
 
@@ -69,29 +146,280 @@ impl<const ADC_COUNT: usize> PressureService<ADC_COUNT> {
 		adc: AdcDevice,
 		channel: PressureChannel,
 	) -> Result<PressureReading, PressureServiceError> {
-		let mut adc_service = self.adc_service.lock().await;
+		let mut adcs = [false; ADC_COUNT];
+		adcs[adc as usize] = true;
 
-		// Get the respective "adc channel" pair for the "pressure channel"
-		let (positive_channel, negative_channel) = channel.to_analog_input_channel_pair();
+		let mut readings = self.read_pressures(channel, adcs).await;
+		readings[adc as usize].take().unwrap_or(Err(PressureServiceError::AdcUnavailable))
+	}
 
-		// Read the voltage from the ADC in millivolts
-		let voltage = adc_service.drivers[adc as usize]
-			.read_differential(positive_channel, negative_channel)
-			.await? * 1000.0; // Convert to millivolts
+	/// Reads the pressure channel on the given ADCs at the same instant, indexed by ADC.
+	/// ADCs that aren't given, that are wired to another kind of sensor, or that are missing or faulted, return None.
+	/// See sensor::acquisition::read_channel for how disconnected sensors are rejected and the conversions are synchronized.
+	pub async fn read_pressures(
+		&mut self,
+		channel: PressureChannel,
+		adcs: [bool; ADC_COUNT],
+	) -> [Option<Result<PressureReading, PressureServiceError>>; ADC_COUNT] {
+		let oversampling = core::array::from_fn(|adc_index| self.oversampling_service.get_oversampling(AdcDevice::from(adc_index), channel));
+		let statistics: [Option<Result<SampleStatistics, PressureServiceError>>; ADC_COUNT] = read_channel(
+			self.adc_service,
+			&mut self.open_circuit_monitor,
+			ChannelKind::Pressure,
+			channel as usize,
+			channel.to_analog_input_channel_pair(),
+			adcs,
+			oversampling,
+		)
+		.await;
+		let local_session = self.session_service.lock().await.current_session.clone();
 
-		// Apply any linear transformations to get the pressure in psi
-		let pressure = self.linear_transformation_service.apply_transformation(adc, channel, voltage as f64);
+		let mut statistics = statistics.into_iter();
+		core::array::from_fn(|adc_index| {
+			let statistics = statistics.next().unwrap()?;
+			Some(statistics.map(|statistics| self.build_pressure_reading(AdcDevice::from(adc_index), channel, statistics, local_session)))
+		})
+	}
+
+	// Converts the averaged transducer voltage (in volts) to a pressure reading
+	fn build_pressure_reading(
+		&self,
+		adc: AdcDevice,
+		channel: PressureChannel,
+		statistics: SampleStatistics,
+		local_session: Option<i32>,
+	) -> PressureReading {
+		let statistics = statistics.scale(1000.0); // Convert to millivolts
+		let voltage = statistics.mean;
+
+		// Apply the temperature compensation or linear transformation to get the pressure in psi, then remove the tare offset
+		let pressure = self.tare_service.apply_tare(adc, channel, self.compute_pressure(adc, channel, voltage as f64));
 
-		let pressure_reading = PressureReading {
-			local_session: self.session_service.lock().await.current_session.clone(),
+		PressureReading {
+			local_session,
 			adc_device: adc,
 			pressure_channel: channel,
 			recorded_at: Instant::now().as_millis(),
 			voltage,
 			pressure,
-			temperature: 0.0, // SHOULD DO: replace once NTC temperature measurement is implemented
+			// NaN until the NTC has been read, or once reading it failed, so a missing manifold temperature can't pass for a real one
+			temperature: self.last_ntc_reading[adc as usize].map_or(f64::NAN, |temperature| temperature as f64),
+			sample_count: statistics.sample_count,
+			voltage_standard_deviation: statistics.standard_deviation,
+			voltage_min: statistics.min,
+			voltage_max: statistics.max,
+		}
+	}
+
+	pub async fn read_ntc(
+		&mut self,
+		adc: AdcDevice,
+		configuration: &NtcConfiguration,
+	) -> Result<f32, PressureServiceError> {
+		let mut driver = self.adc_service.drivers[adc as usize].lock().await;
+
+		// The gain is switched by the NTC acquisition profile once its inputs are selected
+		let (sense_positive, sense_negative) = configuration.sense_pair;
+		let (reference_positive, reference_negative) = configuration.reference_pair;
+		let ntc_voltage = driver.read_differential(sense_positive, sense_negative).await?;
+		let reference_voltage = driver.read_differential(reference_positive, reference_negative).await?;
+
+		// Both voltages have the same sign when the divider is connected. Anything else, e.g. an open NTC or series resistor, or a
+		// reference voltage of 0 V, has no resistance to convert
+		let ratio = ntc_voltage / reference_voltage;
+		if !(ratio.is_finite() && ratio > 0.0) {
+			return Err(PressureServiceError::NtcOutOfRange);
+		}
+
+		let measured_resistance = configuration.compute_resistance(ratio);
+		let estimated_temperature = configuration.convert_resistance_to_temperature(measured_resistance);
+
+		Ok(estimated_temperature)
+	}
+
+	pub async fn refresh_ntc_reading(
+		&mut self,
+		adc: AdcDevice,
+	) -> Result<(), PressureServiceError> {
+		// Missing or faulted ADCs and the ADCs wired to another kind are skipped, their pressure transducers aren't read either
+		if !self.adc_service.is_available_for(adc, ChannelKind::Pressure).await {
+			return Ok(());
+		}
+
+		// Drop the cached temperature when the NTC can't be read, the readings would otherwise keep reporting a stale one
+		let ntc_temperature = match self.read_ntc(adc, &NTC_CONFIGURATION).await {
+			Ok(ntc_temperature) => ntc_temperature,
+			Err(e) => {
+				self.last_ntc_reading[adc as usize] = None;
+				if self.temperature_compensations[adc as usize].iter().any(Option::is_some) {
+					warn!(
+						"Manifold temperature unavailable on {:?}, temperature-compensated channels fall back to their linear transformation",
+						adc
+					);
+				}
+				return Err(e);
+			}
+		};
+		self.last_ntc_reading[adc as usize] = Some(ntc_temperature);
+		info!("NTC Temperature {}: {}C", adc, ntc_temperature);
+		Ok(())
+	}
+}
+
+impl<const ADC_COUNT: usize> TaredSensorService<ADC_COUNT, { PressureChannel::COUNT }> for PressureService<ADC_COUNT> {
+	type Channel = PressureChannel;
+	type Error = PressureServiceError;
+
+	const CHANNEL_KIND: ChannelKind = ChannelKind::Pressure;
+	const TARE_READING_COUNT: usize = TARE_READING_COUNT;
+
+	fn adc_service(&self) -> &'static AdcService<ADC_COUNT> {
+		self.adc_service
+	}
+
+	fn tare_service(&mut self) -> &mut TareService<PressureChannel, ADC_COUNT, { PressureChannel::COUNT }> {
+		&mut self.tare_service
+	}
+
+	async fn read_tared_value(
+		&mut self,
+		adc: AdcDevice,
+		channel: PressureChannel,
+	) -> Result<f64, PressureServiceError> {
+		Ok(self.read_pressure(adc, channel).await?.pressure)
+	}
+}
+
+impl<const ADC_COUNT: usize> CalibratedSensorService<ADC_COUNT, { PressureChannel::COUNT }> for PressureService<ADC_COUNT> {
+	type Channel = PressureChannel;
+	type Error = PressureServiceError;
+	type Reading = PressureReading;
+
+	// Temperature compensations are fitted by the pressure service itself, see PressureService::calibrate
+	const CALIBRATION_MODELS: &'static [CalibrationModelType] = &[
+		CalibrationModelType::Linear,
+		CalibrationModelType::TemperatureCompensated,
+		CalibrationModelType::Polynomial,
+		CalibrationModelType::LookupTable,
+	];
+	const CHANNEL_KIND: ChannelKind = ChannelKind::Pressure;
+	const LOW_QUALITY_FIT_ACTION: LowQualityFitAction = LOW_QUALITY_FIT_ACTION;
+	const MIN_CALIBRATION_R_SQUARED: f64 = MIN_CALIBRATION_R_SQUARED;
+	const STABILITY_CRITERIA: StabilityCriteria = STABILITY_CRITERIA;
+	const UNIT: &'static str = "psi";
+
+	fn adc_service(&self) -> &'static AdcService<ADC_COUNT> {
+		self.adc_service
+	}
+
+	fn serial_service(&self) -> &'static AsyncMutex<UORSerial> {
+		self.serial_service
+	}
+
+	fn session_service(&self) -> &'static AsyncMutex<SessionService> {
+		self.session_service
+	}
+
+	fn calibration_model_service(&mut self) -> &mut CalibrationModelService<PressureChannel, f64, ADC_COUNT, { PressureChannel::COUNT }> {
+		&mut self.calibration_model_service
+	}
+
+	fn has_calibration_session(&self) -> bool {
+		self.calibration_session.is_some()
+	}
+
+	fn calibration_session(&mut self) -> &mut Option<CalibrationSession<PressureChannel>> {
+		&mut self.calibration_session
+	}
+
+	fn calibration_value(reading: &PressureReading) -> f64 {
+		reading.pressure
+	}
+
+	// The temperature compensation takes precedence over the model and the tare offset would be fitted into it, so neither can stay
+	fn deregister_calibration(
+		&mut self,
+		adc: AdcDevice,
+		channel: PressureChannel,
+	) {
+		self.previous_calibration = Some(PreviousCalibration {
+			adc,
+			channel,
+			model: self.calibration_model_service.deregister_model(adc, channel),
+			temperature_compensation: self.temperature_compensations[adc as usize][channel as usize].take(),
+			tare_offset: self.tare_service.deregister_offset(adc, channel),
+		});
+	}
+
+	fn reregister_calibration(&mut self) {
+		let Some(previous_calibration) = self.previous_calibration.take() else {
+			return;
 		};
+		let (adc, channel) = (previous_calibration.adc, previous_calibration.channel);
+		if let Some(model) = previous_calibration.model {
+			self.calibration_model_service.register_model(adc, channel, model);
+		}
+		self.temperature_compensations[adc as usize][channel as usize] = previous_calibration.temperature_compensation;
+		if let Some(tare_offset) = previous_calibration.tare_offset {
+			self.tare_service.register_offset(tare_offset);
+		}
+	}
+
+	async fn read_calibration_reading(
+		&mut self,
+		adc: AdcDevice,
+		channel: PressureChannel,
+	) -> Result<PressureReading, PressureServiceError> {
+		self.read_pressure(adc, channel).await
+	}
+
+	async fn send_calibration_response(
+		&mut self,
+		response: CalibrationResponse,
+	) -> Result<(), PressureServiceError> {
+		let mut serial_service = self.serial_service.lock().await;
+		serial_service.write_envelope_message(EnvelopeMessage::CalibrationResponse(response)).await?;
+		Ok(())
+	}
 
-		Ok(pressure_reading)
+	// Temperature compensations are saved to their own file again and replace the tare offset like a new one, the model of the channel is
+	// kept for the readings without a manifold temperature. Restored models replace the temperature compensation like a new calibration
+	async fn restore_recorded_calibration(
+		&mut self,
+		adc: AdcDevice,
+		channel: PressureChannel,
+		id: u16,
+	) -> Result<Option<CalibrationRecord<PressureChannel, f64>>, PressureServiceError> {
+		let Some((record, terms)) = self.calibration_model_service.load_calibration(adc, channel, id).await? else {
+			return Ok(None);
+		};
+		if record.model != CalibrationModelType::TemperatureCompensated {
+			let restored = self.calibration_model_service.restore_model(adc, channel, record, &terms).await?;
+			if restored.is_some() {
+				self.replace_previous_calibration(adc, channel).await?;
+			}
+			return Ok(restored);
+		}
+
+		let Some(compensation) = TemperatureCompensation::from_terms(adc, channel, &terms) else {
+			error!("Temperature compensation #{} doesn't have four coefficients", record.id);
+			return Ok(None);
+		};
+		info!("Restoring temperature compensation #{} from the history", record.id);
+		self.save_temperature_compensation(compensation).await?;
+		self.tare_service.reset_offset(adc, channel).await?;
+		Ok(Some(record))
+	}
+
+	// The new calibration replaces the temperature compensation and fits the zero the tare offset held, so both are removed from their files
+	async fn replace_previous_calibration(
+		&mut self,
+		adc: AdcDevice,
+		channel: PressureChannel,
+	) -> Result<(), PressureServiceError> {
+		self.previous_calibration = None;
+		self.remove_temperature_compensation(adc, channel).await?;
+		self.tare_service.reset_offset(adc, channel).await?;
+		Ok(())
 	}
 }
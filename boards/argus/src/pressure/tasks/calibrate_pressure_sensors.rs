@@ -5,7 +5,15 @@ use strum::EnumCount;
 use uor_utils::utils::types::AsyncMutex;
 
 use crate::adc::types::AdcDevice;
+#[cfg(feature = "calibration-protocol")]
+use crate::calibration_protocol::handler::handle_calibration_command;
 use crate::pressure::service::PressureService;
+use crate::sensor::calibration::{end_calibration_turn, end_interrupted_calibration};
+#[cfg(feature = "calibration-protocol")]
+use crate::sensor::calibration::receive_calibration_command;
+#[cfg(not(feature = "calibration-protocol"))]
+use crate::sensor::calibration::take_calibration_turn;
+use crate::sensor::types::ChannelKind;
 use crate::state_machine::service::StateMachineWorker;
 use crate::state_machine::types::States;
 
@@ -14,27 +22,56 @@ pub async fn calibrate_pressure_sensors(
 	mut worker: StateMachineWorker,
 	pressure_service_mutex: &'static AsyncMutex<PressureService<{ AdcDevice::COUNT }>>,
 ) {
-	worker
-		.run_while(&[States::Calibrating], async |_| -> Result<(), ()> {
-			let mut pressure_service = pressure_service_mutex.lock().await;
-			// SHOULD DO: Refresh temperature readings for all ADCs once NTC reading is implemented
-			// for adc_index in 0..AdcDevice::COUNT {
-			// 	let adc = AdcDevice::from(adc_index);
-			// 	match pressure_service.refresh_temperature_readings(adc).await {
-			// 		Err(e) => {
-			// 			error!("Failed to read NTC on {:?} during calibration: {:?}", adc, e);
-			// 		}
-			// 		_ => {}
-			// 	}
-			// }
+	loop {
+		worker
+			.run_until_exit(&[States::Calibrating], async |_| -> Result<(), ()> {
+				// The calibrate tasks of each kind take turns on the serial port, see sensor::calibration
+				#[cfg(not(feature = "calibration-protocol"))]
+				{
+					let serial_service = pressure_service_mutex.lock().await.serial_service;
+					match take_calibration_turn(ChannelKind::Pressure, serial_service).await {
+						Err(e) => {
+							error!("Failed to select the sensors to calibrate: {:?}", e);
+							return Ok(());
+						}
+						_ => {}
+					}
+				}
+				#[cfg(feature = "calibration-protocol")]
+				let command = receive_calibration_command(ChannelKind::Pressure).await;
 
-			match pressure_service.calibrate().await {
-				Ok(_) => {}
-				Err(e) => error!("Pressure calibration failed: {:?}", e),
-			}
-			yield_now().await;
-			Ok(())
-		})
-		.await
-		.unwrap();
+				let mut pressure_service = pressure_service_mutex.lock().await;
+				for adc_index in 0..AdcDevice::COUNT {
+					let adc = AdcDevice::from(adc_index);
+					match pressure_service.refresh_ntc_reading(adc).await {
+						Err(e) => {
+							error!("Failed to read NTC on {:?} during calibration: {:?}", adc, e);
+						}
+						_ => {}
+					}
+				}
+
+				// With the calibration-protocol feature the calibration is driven by protobuf commands instead of the text prompts
+				#[cfg(not(feature = "calibration-protocol"))]
+				let result = pressure_service.calibrate().await;
+				#[cfg(feature = "calibration-protocol")]
+				let result = handle_calibration_command(&mut *pressure_service, command).await;
+
+				match result {
+					Ok(_) => {}
+					Err(e) => error!("Pressure calibration failed: {:?}", e),
+				}
+				#[cfg(not(feature = "calibration-protocol"))]
+				end_calibration_turn().await;
+				yield_now().await;
+				Ok(())
+			})
+			.await
+			.unwrap();
+
+		// Leaving the state drops a calibration wherever it was waiting, so the calibration it put aside is registered again
+		// The turns of the kinds start over the next time the state is entered
+		end_interrupted_calibration(&mut *pressure_service_mutex.lock().await);
+		end_calibration_turn().await;
+	}
 }
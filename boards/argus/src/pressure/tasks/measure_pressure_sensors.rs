@@ -1,17 +1,20 @@
 use defmt::{error, info};
 use embassy_executor::task;
 use embassy_futures::yield_now;
+use embassy_time::{Instant, Timer};
 use strum::EnumCount;
 use uor_utils::utils::types::AsyncMutex;
 
+use crate::adc::service::AdcError;
 use crate::adc::types::AdcDevice;
 use crate::led_indicator::service::LedIndicatorService;
 use crate::pressure::service::{PressureService, PRESSURE_READING_QUEUE};
-use crate::pressure::types::PressureChannel;
+use crate::pressure::types::PressureServiceError;
 use crate::state_machine::service::StateMachineWorker;
 use crate::state_machine::types::States;
+use crate::tare::config::AUTO_TARE_POLL_INTERVAL;
 
-// Task that iterates through the ADCs and channels, measures the pressure, and enqueues the readings to a channel
+// Task that measures the pressure channels of the scan list as they become due, and enqueues the readings to a channel
 #[task]
 pub async fn measure_pressure_sensors(
 	mut worker: StateMachineWorker,
@@ -20,16 +23,39 @@ pub async fn measure_pressure_sensors(
 ) {
 	worker
 		.run_while(&[States::Recording], async |_| -> Result<(), ()> {
-			for adc_index in 0..AdcDevice::COUNT {
-				for channel_index in 0..PressureChannel::COUNT {
+			// Wait for the next channel to be due without holding the service, so the other tasks can use it in the meantime
+			let next_due_at = pressure_service_mutex.lock().await.scan_sequencer.next_due_at();
+			Timer::at(next_due_at).await;
+
+			// Hold the readings back until the tare of the recording has completed, see tare::service::tare_on_recording
+			if pressure_service_mutex.lock().await.tare_service.auto_tare_pending {
+				Timer::after_millis(AUTO_TARE_POLL_INTERVAL).await;
+				return Ok(());
+			}
+			let scan_steps = pressure_service_mutex.lock().await.scan_sequencer.take_due(Instant::now().as_millis());
+
+			for scan_step in scan_steps.iter() {
+				let channel = scan_step.channel;
+				let readings = pressure_service_mutex.lock().await.read_pressures(channel, scan_step.adcs).await;
+				for (adc_index, data) in readings.into_iter().enumerate() {
+					// ADCs the channel wasn't due on, or that are missing or faulted, are skipped
+					let Some(data) = data else {
+						continue;
+					};
 					let adc = AdcDevice::from(adc_index);
-					let channel = PressureChannel::from(channel_index);
-					let data = pressure_service_mutex.lock().await.read_pressure(adc, channel).await;
 					match data {
 						Ok(pressure_reading) => {
 							info!("{}", pressure_reading);
 							PRESSURE_READING_QUEUE.send(pressure_reading).await;
 						}
+						Err(PressureServiceError::OpenCircuit) => {
+							error!("Open circuit on ADC {} Channel {}. Is the pressure transducer connected?", adc, channel);
+							continue;
+						}
+						Err(PressureServiceError::AdcError(AdcError::DataReadyTimeout)) => {
+							error!("Timed out waiting for data from ADC {} Channel {}. Is the ADC connected?", adc, channel);
+							continue;
+						}
 						Err(err) => {
 							error!("Error reading ADC {} Channel {}: {:?}", adc, channel, err);
 							continue;
@@ -38,8 +64,10 @@ pub async fn measure_pressure_sensors(
 				}
 			}
 
-			// Blink LED to indicate measurement cycle complete
-			led_indicator_service_mutex.lock().await.blink(1).await;
+			// Blink LED once per pass over the scan list to indicate the measurements are running
+			if scan_steps.iter().any(|scan_step| scan_step.starts_cycle) {
+				led_indicator_service_mutex.lock().await.blink(1).await;
+			}
 
 			// Yield to allow other tasks to run, especially the NTC measurement task
 			yield_now().await;
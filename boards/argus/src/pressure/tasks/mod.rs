@@ -2,8 +2,10 @@ mod calibrate_pressure_sensors;
 mod log_measurements;
 mod measure_manifold_temperature;
 mod measure_pressure_sensors;
+mod tare_on_recording;
 
 pub use calibrate_pressure_sensors::*;
 pub use log_measurements::*;
 pub use measure_manifold_temperature::*;
 pub use measure_pressure_sensors::*;
+pub use tare_on_recording::*;
@@ -0,0 +1,17 @@
+use embassy_executor::task;
+use strum::EnumCount;
+use uor_utils::utils::types::AsyncMutex;
+
+use crate::adc::types::AdcDevice;
+use crate::pressure::service::PressureService;
+use crate::state_machine::service::StateMachineWorker;
+use crate::tare::service;
+
+// Task that tares the AUTO_TARE_CHANNELS once each time recording starts, the measurements wait for it to complete
+#[task]
+pub async fn tare_on_recording(
+	worker: StateMachineWorker,
+	pressure_service_mutex: &'static AsyncMutex<PressureService<{ AdcDevice::COUNT }>>,
+) {
+	service::tare_on_recording(worker, pressure_service_mutex).await;
+}
@@ -3,7 +3,9 @@ use derive_more::From;
 use uor_peripherals::serial::peripheral::UsartError;
 
 use crate::adc::service::AdcError;
+use crate::calibration_protocol::session::SessionError;
 use crate::sd::types::SdCardError;
+use crate::sensor::types::SensorServiceError;
 
 #[derive(Debug, Format, From)]
 pub enum PressureServiceError {
@@ -11,4 +13,25 @@ pub enum PressureServiceError {
 	UsartError(UsartError),
 	SdCardError(SdCardError),
 	FormatError,
+	OpenCircuit, // The sensor was found to be disconnected during the last open-circuit check
+	AdcUnavailable, // The ADC is missing or faulted, see AdcService::detect_devices
+	NtcOutOfRange, // The NTC voltage ratio isn't positive, e.g. the NTC or its series resistor is disconnected
+	CalibrationSessionError(SessionError), // The calibration command can't be carried out, it is answered with a CalibrationError
+}
+
+impl SensorServiceError for PressureServiceError {
+	fn format_error() -> Self {
+		PressureServiceError::FormatError
+	}
+
+	fn open_circuit() -> Self {
+		PressureServiceError::OpenCircuit
+	}
+
+	fn session_error(&self) -> Option<SessionError> {
+		match self {
+			PressureServiceError::CalibrationSessionError(error) => Some(*error),
+			_ => None,
+		}
+	}
 }
@@ -1,9 +1,15 @@
 pub mod error;
+pub mod ntc_configuration;
 pub mod pressure_channel;
 pub mod pressure_reading;
 pub mod queue;
+pub mod sensor_kind;
+pub mod temperature_compensation;
 
 pub use error::*;
+pub use ntc_configuration::*;
 pub use pressure_channel::*;
 pub use pressure_reading::*;
 pub use queue::*;
+pub use sensor_kind::*;
+pub use temperature_compensation::*;
@@ -0,0 +1,53 @@
+use crate::adc::driver::types::{AcquisitionProfile, AnalogChannel, Gain};
+use crate::pressure::ntc;
+
+/// Equation used to convert the NTC resistance to a temperature
+#[derive(Debug, Clone, Copy)]
+pub enum NtcModel {
+	// Only needs the values from the datasheet, accurate to about ±1 °C within the range the B value was specified for
+	Beta { resistance_at_25c: f32, beta: f32 },
+	// Accurate over a wide range when the coefficients are fitted to the thermistor
+	SteinhartHart { a: f32, b: f32, c: f32 },
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct NtcConfiguration {
+	// Input pair measuring the voltage across the NTC
+	pub sense_pair: (AnalogChannel, AnalogChannel),
+
+	// Input pair measuring the voltage across the resistor in series with the NTC
+	pub reference_pair: (AnalogChannel, AnalogChannel),
+	pub reference_resistance: f32, // Ohms
+
+	pub model: NtcModel,
+	pub gain: Gain,
+}
+
+impl NtcConfiguration {
+	/// Acquisition profile for the NTC inputs, overriding the gain of the base profile
+	pub fn acquisition_profile(
+		&self,
+		base: AcquisitionProfile,
+	) -> AcquisitionProfile {
+		AcquisitionProfile { gain: self.gain, ..base }
+	}
+
+	/// Computes the NTC resistance from the ratio of the NTC voltage to the series resistor voltage.
+	/// The same current flows through both, so drift in the excitation voltage cancels out.
+	pub fn compute_resistance(
+		&self,
+		ratio: f32,
+	) -> f32 {
+		ratio * self.reference_resistance
+	}
+
+	pub fn convert_resistance_to_temperature(
+		&self,
+		resistance: f32,
+	) -> f32 {
+		match self.model {
+			NtcModel::Beta { resistance_at_25c, beta } => ntc::convert_resistance_to_temperature_beta(resistance_at_25c, beta, resistance),
+			NtcModel::SteinhartHart { a, b, c } => ntc::convert_resistance_to_temperature_steinhart_hart(a, b, c, resistance),
+		}
+	}
+}
@@ -31,8 +31,21 @@ pub struct PressureReading {
 	// Pressure reading in psi
 	pub pressure: f64,
 
-	// Temperature of the manifold from the NTC resistor at the time of the recording in degrees Celsius
+	// Temperature of the manifold from the NTC resistor at the time of the recording in degrees Celsius, NaN when it isn't available
+	// Without it a temperature-compensated channel falls back to its linear transformation, so NaN also flags that fallback
 	pub temperature: f64,
+
+	// Number of conversions averaged into the voltage, after the outliers were rejected
+	pub sample_count: u8,
+
+	// Standard deviation of the averaged conversions in millivolts
+	pub voltage_standard_deviation: f32,
+
+	// Lowest of the averaged conversions in millivolts
+	pub voltage_min: f32,
+
+	// Highest of the averaged conversions in millivolts
+	pub voltage_max: f32,
 }
 
 impl SerializeCSV<MAX_LINE_LENGTH> for PressureReading {
@@ -44,7 +57,11 @@ impl SerializeCSV<MAX_LINE_LENGTH> for PressureReading {
 			Timestamp (ms),\
 			Voltage (mV),\
 			Pressure (psi),\
-			Manifold Temperature (C)",
+			Manifold Temperature (C),\
+			Sample Count,\
+			Voltage Standard Deviation (mV),\
+			Voltage Min (mV),\
+			Voltage Max (mV)",
 		)
 		.unwrap()
 	}
@@ -61,6 +78,10 @@ impl PressureReading {
 			voltage: self.voltage,
 			pressure: self.pressure,
 			temperature: self.temperature,
+			sample_count: self.sample_count as u32,
+			voltage_standard_deviation: self.voltage_standard_deviation,
+			voltage_min: self.voltage_min,
+			voltage_max: self.voltage_max,
 		}
 	}
 }
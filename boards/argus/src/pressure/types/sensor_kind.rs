@@ -0,0 +1,28 @@
+use uor_utils::messages::argus::envelope::envelope::Message as EnvelopeMessage;
+
+use crate::adc::types::AdcDevice;
+use crate::pressure::types::{PressureChannel, PressureReading};
+use crate::sensor::types::{ChannelKind, SensorKind};
+
+// Marker for the pressure transducers in the shared sensor pipeline, see SensorKind
+pub struct PressureTransducers;
+
+impl SensorKind for PressureTransducers {
+	type Channel = PressureChannel;
+	type Reading = PressureReading;
+
+	const CHANNEL_KIND: ChannelKind = ChannelKind::Pressure;
+	const FILE_PREFIX: &'static str = "P";
+
+	fn adc_device(reading: &PressureReading) -> AdcDevice {
+		reading.adc_device
+	}
+
+	fn channel_index(reading: &PressureReading) -> usize {
+		reading.pressure_channel as usize
+	}
+
+	fn to_envelope_message(reading: &PressureReading) -> EnvelopeMessage {
+		EnvelopeMessage::PressureReading(reading.to_protobuf())
+	}
+}
@@ -0,0 +1,86 @@
+use core::str::FromStr;
+
+use defmt::Format;
+use serde::{Deserialize, Serialize};
+use uor_utils::csv::SerializeCSV;
+
+use crate::adc::types::AdcDevice;
+use crate::pressure::types::PressureChannel;
+use crate::sd::config::MAX_LINE_LENGTH;
+use crate::sd::types::Line;
+
+// Temperature-compensated calibration of a pressure transducer, whose zero and span drift with the manifold temperature
+// pressure = (zero + zero_temperature_coefficient * dT) + (span + span_temperature_coefficient * dT) * voltage
+// where dT is the manifold temperature minus the reference temperature
+#[derive(Debug, Clone, Copy, Format, Serialize, Deserialize)]
+pub struct TemperatureCompensation {
+	pub adc: AdcDevice,
+	pub channel: PressureChannel,
+
+	// Temperature at which the zero and span are specified, in degrees Celsius
+	pub reference_temperature: f64,
+
+	// Pressure at zero bridge voltage in psi, and its drift in psi per degree Celsius
+	pub zero: f64,
+	pub zero_temperature_coefficient: f64,
+
+	// Pressure per millivolt of bridge voltage in psi/mV, and its drift in psi/mV per degree Celsius
+	pub span: f64,
+	pub span_temperature_coefficient: f64,
+}
+
+impl TemperatureCompensation {
+	/// Converts the bridge voltage in millivolts to a pressure in psi at the given manifold temperature in degrees Celsius
+	pub fn apply(
+		&self,
+		voltage: f64,
+		temperature: f64,
+	) -> f64 {
+		let temperature_difference = temperature - self.reference_temperature;
+		let zero = self.zero + self.zero_temperature_coefficient * temperature_difference;
+		let span = self.span + self.span_temperature_coefficient * temperature_difference;
+		zero + span * voltage
+	}
+
+	/// Terms the compensation is recorded as in the calibration history, see CalibrationTerm.
+	/// Each coefficient is the output of a term, with the reference temperature as its input.
+	pub fn terms(&self) -> [(f64, f64); 4] {
+		[self.zero, self.zero_temperature_coefficient, self.span, self.span_temperature_coefficient]
+			.map(|coefficient| (self.reference_temperature, coefficient))
+	}
+
+	/// Reads a compensation back from the terms it was recorded as, None if there aren't exactly four of them.
+	pub fn from_terms(
+		adc: AdcDevice,
+		channel: PressureChannel,
+		terms: &[(f64, f64)],
+	) -> Option<Self> {
+		let &[(reference_temperature, zero), (_, zero_temperature_coefficient), (_, span), (_, span_temperature_coefficient)] = terms else {
+			return None;
+		};
+		Some(Self {
+			adc,
+			channel,
+			reference_temperature,
+			zero,
+			zero_temperature_coefficient,
+			span,
+			span_temperature_coefficient,
+		})
+	}
+}
+
+impl SerializeCSV<MAX_LINE_LENGTH> for TemperatureCompensation {
+	fn get_csv_header() -> Line {
+		Line::from_str(
+			"ADC Index,\
+			Channel Index,\
+			Reference Temperature (C),\
+			Zero (psi),\
+			Zero Temperature Coefficient (psi/C),\
+			Span (psi/mV),\
+			Span Temperature Coefficient (psi/mV/C)",
+		)
+		.unwrap()
+	}
+}
@@ -0,0 +1,73 @@
+use embassy_time::Instant;
+use heapless::Vec;
+
+use crate::calibration_model::types::ChannelMarker;
+use crate::scan_sequencer::types::{ScanEntry, ScanStep};
+
+/// Schedules the entries of a scan list according to their intervals.
+/// The sequencer doesn't wait by itself so it can live inside a service without holding its lock while idle,
+/// callers wait until next_due_at() and then read the steps returned by take_due().
+pub struct ScanSequencer<Channel, const ADC_COUNT: usize, const ENTRY_COUNT: usize>
+where
+	Channel: ChannelMarker, {
+	pub scan_list: [ScanEntry<Channel>; ENTRY_COUNT],
+
+	// Next time each entry of the scan list is due, in milliseconds since the board's epoch
+	next_due: [u64; ENTRY_COUNT],
+}
+
+impl<Channel, const ADC_COUNT: usize, const ENTRY_COUNT: usize> ScanSequencer<Channel, ADC_COUNT, ENTRY_COUNT>
+where
+	Channel: ChannelMarker,
+{
+	/// Every entry is due immediately, so the first scan reads the whole scan list
+	pub fn new(scan_list: [ScanEntry<Channel>; ENTRY_COUNT]) -> Self {
+		Self {
+			scan_list,
+			next_due: [0; ENTRY_COUNT],
+		}
+	}
+
+	/// Time at which the next entry is due. Never if the scan list is empty
+	pub fn next_due_at(&self) -> Instant {
+		self.next_due.iter().copied().min().map_or(Instant::MAX, Instant::from_millis)
+	}
+
+	/// Returns the entries that are due at the given time and schedules their next sample.
+	/// Entries of the same channel are merged into a single step, steps are ordered by their first entry in the scan list.
+	pub fn take_due(
+		&mut self,
+		now: u64,
+	) -> Vec<ScanStep<Channel, ADC_COUNT>, ENTRY_COUNT> {
+		let mut steps: Vec<ScanStep<Channel, ADC_COUNT>, ENTRY_COUNT> = Vec::new();
+		for (entry_index, entry) in self.scan_list.iter().enumerate() {
+			if self.next_due[entry_index] > now {
+				continue;
+			}
+
+			// Keep the rate of the entry when the scan runs on time, but skip the missed samples instead of catching up in a burst when it falls behind
+			let next_due = self.next_due[entry_index] + entry.interval;
+			self.next_due[entry_index] = if next_due > now { next_due } else { now + entry.interval };
+
+			let starts_cycle = entry_index == 0;
+			match steps.iter_mut().find(|step| step.channel == entry.channel) {
+				Some(step) => {
+					step.adcs[entry.adc as usize] = true;
+					step.starts_cycle |= starts_cycle;
+				}
+				None => {
+					let mut adcs = [false; ADC_COUNT];
+					adcs[entry.adc as usize] = true;
+
+					// Can't overflow since there are at most as many steps as entries
+					let _ = steps.push(ScanStep {
+						channel: entry.channel,
+						adcs,
+						starts_cycle,
+					});
+				}
+			}
+		}
+		steps
+	}
+}
@@ -0,0 +1,59 @@
+use defmt::Format;
+use strum::EnumCount;
+
+use crate::adc::types::AdcDevice;
+
+// A channel of an ADC that is sampled by a measurement task, and how often
+#[derive(Debug, Clone, Copy, Format)]
+pub struct ScanEntry<Channel> {
+	pub adc: AdcDevice,
+	pub channel: Channel,
+
+	// Time between two samples of the channel. 0 samples the channel on every scan, i.e. as fast as the ADCs allow
+	pub interval: u64, // milliseconds
+}
+
+impl<Channel> ScanEntry<Channel> {
+	pub const fn new(
+		adc: AdcDevice,
+		channel: Channel,
+		interval: u64,
+	) -> Self {
+		Self { adc, channel, interval }
+	}
+}
+
+/// Scan list that samples every channel of every ADC as fast as the ADCs allow, in ADC order then in the order of the channels.
+/// The length of the list must be the number of ADCs times the number of channels, which is checked when the constant is evaluated.
+pub const fn scan_every_channel<Channel: Copy, const CHANNEL_COUNT: usize, const LENGTH: usize>(
+	channels: [Channel; CHANNEL_COUNT]
+) -> [ScanEntry<Channel>; LENGTH] {
+	assert!(
+		LENGTH == AdcDevice::COUNT * CHANNEL_COUNT,
+		"The scan list must hold every channel of every ADC"
+	);
+
+	let mut entries = [ScanEntry::new(AdcDevice::AdcDevice1, channels[0], 0); LENGTH];
+	let mut index = 0;
+	while index < LENGTH {
+		let adc = match index / CHANNEL_COUNT {
+			0 => AdcDevice::AdcDevice1,
+			_ => AdcDevice::AdcDevice2,
+		};
+		entries[index] = ScanEntry::new(adc, channels[index % CHANNEL_COUNT], 0);
+		index += 1;
+	}
+	entries
+}
+
+// A channel that is due to be read on one or more ADCs at the same time
+#[derive(Debug, Clone, Copy, Format)]
+pub struct ScanStep<Channel, const ADC_COUNT: usize> {
+	pub channel: Channel,
+
+	// Which ADCs the channel is due on, indexed by ADC
+	pub adcs: [bool; ADC_COUNT],
+
+	// Whether the step contains the first entry of the scan list, which marks the start of a new pass over the scan list
+	pub starts_cycle: bool,
+}
@@ -0,0 +1,104 @@
+use embassy_time::Instant;
+
+use crate::adc::driver::types::{AnalogChannel, SensorBiasMagnitude};
+use crate::adc::service::{AdcError, AdcService};
+use crate::adc::types::{AdcDevice, OpenCircuitCheck};
+use crate::oversampling::types::{Oversampling, SampleStatistics};
+use crate::sensor::config::ADC_CHANNEL_KINDS;
+use crate::sensor::types::{ChannelKind, SensorServiceError};
+
+// Acquisition shared by the sensor services. Each service reads its channels through read_channel() and only converts
+// the resulting statistics to the readings of its kind
+
+/// Checks the channels of one kind of sensor for a disconnected sensor.
+/// The check briefly injects a sensor bias current, so a channel is only checked every interval and the result is cached in between.
+pub struct OpenCircuitMonitor<const ADC_COUNT: usize, const CHANNEL_COUNT: usize> {
+	// Result of the last open-circuit check for each ADC and channel
+	checks: [[Option<OpenCircuitCheck>; CHANNEL_COUNT]; ADC_COUNT],
+
+	// How often each channel is checked, in milliseconds
+	interval: u64,
+
+	// Sensor bias current injected during the check, only a small offset develops across a connected sensor
+	bias_magnitude: SensorBiasMagnitude,
+}
+
+impl<const ADC_COUNT: usize, const CHANNEL_COUNT: usize> OpenCircuitMonitor<ADC_COUNT, CHANNEL_COUNT> {
+	pub fn new(
+		interval: u64,
+		bias_magnitude: SensorBiasMagnitude,
+	) -> Self {
+		Self {
+			checks: [[None; CHANNEL_COUNT]; ADC_COUNT],
+			interval,
+			bias_magnitude,
+		}
+	}
+
+	/// Returns whether the sensor on the channel is disconnected, re-checking it if the cached result is older than the interval
+	pub async fn check(
+		&mut self,
+		adc_service: &AdcService<ADC_COUNT>,
+		adc: AdcDevice,
+		channel_index: usize,
+		pair: (AnalogChannel, AnalogChannel),
+	) -> Result<bool, AdcError> {
+		let now = Instant::now().as_millis();
+		if let Some(last_check) = self.checks[adc as usize][channel_index] {
+			if now - last_check.checked_at < self.interval {
+				return Ok(last_check.is_open);
+			}
+		}
+
+		let (positive_channel, negative_channel) = pair;
+		let is_open = adc_service.drivers[adc as usize]
+			.lock()
+			.await
+			.detect_open_circuit(positive_channel, negative_channel, self.bias_magnitude)
+			.await?;
+		self.checks[adc as usize][channel_index] = Some(OpenCircuitCheck { checked_at: now, is_open });
+		Ok(is_open)
+	}
+}
+
+/// Reads one channel on the given ADCs at the same instant and returns the sample statistics, indexed by ADC.
+/// ADCs that aren't given, that are wired to another kind of sensor, or that are missing or faulted, return None.
+/// Readings of disconnected sensors are rejected instead of returning whatever the floating inputs convert to.
+/// See AdcService::read_differential_oversampled for how the conversions are synchronized and averaged.
+pub async fn read_channel<E, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	adc_service: &AdcService<ADC_COUNT>,
+	open_circuit_monitor: &mut OpenCircuitMonitor<ADC_COUNT, CHANNEL_COUNT>,
+	kind: ChannelKind,
+	channel_index: usize,
+	pair: (AnalogChannel, AnalogChannel),
+	adcs: [bool; ADC_COUNT],
+	oversampling: [Oversampling; ADC_COUNT],
+) -> [Option<Result<SampleStatistics, E>>; ADC_COUNT]
+where
+	E: SensorServiceError + From<AdcError>, {
+	// ADCs wired to another kind of sensor are read by its own service, see ADC_CHANNEL_KINDS
+	let adcs: [bool; ADC_COUNT] = core::array::from_fn(|adc_index| adcs[adc_index] && ADC_CHANNEL_KINDS[adc_index] == kind);
+
+	let mut open_circuits: [Result<bool, AdcError>; ADC_COUNT] = core::array::from_fn(|_| Ok(false));
+	for (adc_index, open_circuit) in open_circuits.iter_mut().enumerate() {
+		let adc = AdcDevice::from(adc_index);
+		if adcs[adc_index] && adc_service.is_available(adc).await {
+			*open_circuit = open_circuit_monitor.check(adc_service, adc, channel_index, pair).await;
+		}
+	}
+
+	let pairs = core::array::from_fn(|adc_index| adcs[adc_index].then_some(pair));
+	let statistics = adc_service.read_differential_oversampled(pairs, oversampling).await;
+
+	let mut results = open_circuits.into_iter().zip(statistics);
+	core::array::from_fn(|_| {
+		let (open_circuit, statistics) = results.next().unwrap();
+		// ADCs that weren't read have no statistics and are skipped
+		statistics.map(|statistics| {
+			if open_circuit? {
+				return Err(E::open_circuit());
+			}
+			statistics.map_err(E::from)
+		})
+	})
+}
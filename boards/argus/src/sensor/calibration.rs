@@ -0,0 +1,570 @@
+use core::fmt::Write;
+
+use defmt::info;
+use embassy_sync::channel::Channel;
+use embassy_time::{Instant, Timer};
+use heapless::{format, String, Vec};
+use strum::EnumCount;
+use uor_peripherals::serial::peripheral::{UORSerial, UORSerialRx, UsartError};
+use uor_utils::messages::argus::calibration::calibration_command::calibration_command::Command;
+use uor_utils::messages::argus::calibration::calibration_command::CalibrationCommand;
+use uor_utils::messages::argus::envelope::envelope::Message as EnvelopeMessage;
+use uor_utils::utils::types::AsyncMutex;
+
+use crate::adc::driver::types::{AnalogChannel, CalibrationType};
+use crate::adc::service::AdcError;
+use crate::adc::types::AdcDevice;
+use crate::calibration_model::config::{MAX_POLYNOMIAL_ORDER, STABILITY_WINDOW_SIZE};
+use crate::calibration_model::fit::{build_lookup_table, fit_polynomial};
+use crate::calibration_model::stability::StabilityWindow;
+use crate::calibration_model::types::{CalibrationModel, CalibrationModelType, CalibrationRecord, LowQualityFitAction, StableReading};
+use crate::calibration_protocol::session::live_reading_response;
+use crate::sensor::config::{ADC_CHANNEL_KINDS, CALIBRATION_TURN_POLL_INTERVAL, MAX_CALIBRATION_DATA_POINTS};
+use crate::sensor::types::{CalibratedSensorService, CalibrationCommandQueue, CalibrationDataPoint, ChannelKind, SensorServiceError};
+
+// Calibration steps shared by the text prompts and the calibration protocol of the sensor services
+
+/// Prompts for the ADC and channel to calibrate. Returns None if an ADC not wired to the sensors of the service or an invalid channel was entered.
+pub async fn select_calibrated_channel<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service
+) -> Result<Option<(AdcDevice, Service::Channel)>, Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	// Prompt for ADC index
+	let prompt: String<96> = format!("Starting {} calibration. Enter ADC index (Starts from 0):\n", Service::CHANNEL_KIND.name())
+		.map_err(|_| Service::Error::format_error())?;
+	let adc_index: usize = service.prompt(prompt.as_str()).await?;
+	if adc_index >= AdcDevice::COUNT {
+		service.send_message("Invalid ADC index.\n").await?;
+		return Ok(None);
+	}
+	let adc = AdcDevice::from(adc_index);
+	if !service.adc_service().is_available_for(adc, Service::CHANNEL_KIND).await {
+		service.send_message("ADC not available.\n").await?;
+		return Ok(None);
+	}
+
+	// Prompt for channel
+	let prompt: String<64> =
+		format!("Enter {} channel index (Starts from 0):\n", Service::CHANNEL_KIND.name()).map_err(|_| Service::Error::format_error())?;
+	let channel_index: usize = service.prompt(prompt.as_str()).await?;
+	if channel_index >= Service::Channel::COUNT {
+		service.send_message("Invalid channel index.\n").await?;
+		return Ok(None);
+	}
+
+	let message: String<64> =
+		format!("Calibrating ADC {}, Channel {}\n", adc_index, channel_index).map_err(|_| Service::Error::format_error())?;
+	service.send_message(message.as_str()).await?;
+	Ok(Some((adc, Service::Channel::from(channel_index))))
+}
+
+/// Reads the channel every STABILITY_CRITERIA.reading_interval until the last STABILITY_WINDOW_SIZE readings are stable,
+/// showing the live values meanwhile, as LiveReading responses during a calibration protocol session.
+/// The value is the quantity of each reading the data point is taken from.
+/// Returns None if the readings don't settle within STABILITY_CRITERIA.timeout.
+pub async fn capture_stable_reading<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service,
+	adc: AdcDevice,
+	channel: Service::Channel,
+	value: fn(&Service::Reading) -> f64,
+) -> Result<Option<StableReading>, Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	let mut window: StabilityWindow<STABILITY_WINDOW_SIZE> = StabilityWindow::default();
+	let started_at = Instant::now();
+	while started_at.elapsed().as_millis() < Service::STABILITY_CRITERIA.timeout {
+		let reading = value(&service.read_calibration_reading(adc, channel).await?);
+		window.push(reading);
+		if service.has_calibration_session() {
+			let response = live_reading_response(reading, window.standard_deviation(), window.drift());
+			service.send_calibration_response(response).await?;
+		} else {
+			let message: String<96> = format!(
+				"Live: {:.4}, Standard deviation = {:.4}, Drift = {:.4}\n",
+				reading,
+				window.standard_deviation(),
+				window.drift()
+			)
+			.map_err(|_| Service::Error::format_error())?;
+			service.send_message(message.as_str()).await?;
+		}
+
+		if window.is_stable(&Service::STABILITY_CRITERIA) {
+			return Ok(Some(window.to_stable_reading()));
+		}
+		Timer::after_millis(Service::STABILITY_CRITERIA.reading_interval).await;
+	}
+	Ok(None)
+}
+
+/// Prompts for the ADC offset and gain calibrations and runs them on the input pair of the channel, saving them so they are restored on the next boot.
+/// Both are shared by all channels of the ADC at the gain of the channel, so the calibrations fitted on top of them may need to be redone.
+/// Returns false if an invalid option was entered.
+pub async fn calibrate_adc<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service,
+	adc: AdcDevice,
+	(positive, negative): (AnalogChannel, AnalogChannel),
+) -> Result<bool, Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>,
+	Service::Error: From<AdcError>, {
+	let offset_calibration_option: u8 = service
+		.prompt("Select ADC offset calibration. 0 = Skip, 1 = Self offset, 2 = System offset (short the inputs of the channel first):\n")
+		.await?;
+	let offset_calibration_type = match offset_calibration_option {
+		0 => None,
+		1 => Some(CalibrationType::SelfOffset),
+		2 => Some(CalibrationType::SystemOffset),
+		_ => {
+			service.send_message("Invalid offset calibration option.\n").await?;
+			return Ok(false);
+		}
+	};
+	if let Some(calibration_type) = offset_calibration_type {
+		let adc_calibration = service.adc_service().calibrate(adc, calibration_type, positive, negative).await?;
+		service.adc_service().save_calibration(adc_calibration).await?;
+
+		let message: String<64> =
+			format!("ADC offset calibration complete. Offset: {}\n", adc_calibration.offset).map_err(|_| Service::Error::format_error())?;
+		service.send_message(message.as_str()).await?;
+	}
+
+	// The gain calibration runs after the offset calibration, it is taken relative to the offset
+	let gain_calibration_option: u8 = service
+		.prompt("Select ADC gain calibration. 0 = Skip, 1 = Gain (apply a full-scale signal, the reference voltage divided by the gain, first):\n")
+		.await?;
+	match gain_calibration_option {
+		0 => {}
+		1 => {
+			let adc_calibration = service.adc_service().calibrate(adc, CalibrationType::Gain, positive, negative).await?;
+			service.adc_service().save_calibration(adc_calibration).await?;
+
+			let message: String<64> =
+				format!("ADC gain calibration complete. Full scale: {}\n", adc_calibration.full_scale).map_err(|_| Service::Error::format_error())?;
+			service.send_message(message.as_str()).await?;
+		}
+		_ => {
+			service.send_message("Invalid gain calibration option.\n").await?;
+			return Ok(false);
+		}
+	}
+	Ok(true)
+}
+
+/// Prompts for the model of the calibration among CALIBRATION_MODELS. Returns None if an invalid option was entered.
+pub async fn select_calibration_model<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service
+) -> Result<Option<CalibrationModelType>, Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	let mut prompt: String<128> = String::new();
+	write!(prompt, "Enter calibration model (").map_err(|_| Service::Error::format_error())?;
+	for (option, model) in Service::CALIBRATION_MODELS.iter().enumerate() {
+		let separator = if option == 0 { "" } else { ", " };
+		write!(prompt, "{}{} = {}", separator, option, model.label()).map_err(|_| Service::Error::format_error())?;
+	}
+	write!(prompt, "):\n").map_err(|_| Service::Error::format_error())?;
+
+	let option: usize = service.prompt(prompt.as_str()).await?;
+	let Some(model) = Service::CALIBRATION_MODELS.get(option) else {
+		service.send_message("Invalid calibration model.\n").await?;
+		return Ok(None);
+	};
+	Ok(Some(*model))
+}
+
+/// Prompts for the polynomial order and the number of data points, then collects the data points and saves the fitted model as the
+/// calibration of the channel. The channel is measured without its calibration meanwhile, it is registered again unless the new one is saved.
+pub async fn calibrate_model<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service,
+	adc: AdcDevice,
+	channel: Service::Channel,
+	model: CalibrationModelType,
+) -> Result<(), Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	// Prompt for polynomial order, the other models are fitted with the order of a line
+	let order: usize = match model {
+		CalibrationModelType::Polynomial => service.prompt("Enter polynomial order:\n").await?,
+		_ => 1,
+	};
+	if order < 1 || order > MAX_POLYNOMIAL_ORDER {
+		let error_message: String<64> =
+			format!("Invalid polynomial order. Maximum is {}.\n", MAX_POLYNOMIAL_ORDER).map_err(|_| Service::Error::format_error())?;
+		service.send_message(error_message.as_str()).await?;
+		return Ok(());
+	}
+
+	// Prompt for number of data points
+	let data_points_count: u8 = service.prompt("Enter number of data points to use for the fit:\n").await?;
+	if (data_points_count as usize) < order + 1 {
+		let error_message: String<64> = format!("Minimum {} data points is required.\n", order + 1).map_err(|_| Service::Error::format_error())?;
+		service.send_message(error_message.as_str()).await?;
+		return Ok(());
+	}
+	if data_points_count > MAX_CALIBRATION_DATA_POINTS as u8 {
+		let error_message: String<64> =
+			format!("Too many data points. Maximum is {}.\n", MAX_CALIBRATION_DATA_POINTS).map_err(|_| Service::Error::format_error())?;
+		service.send_message(error_message.as_str()).await?;
+		return Ok(());
+	}
+
+	// Measure the channel without its calibration, it is registered again unless the new calibration is saved
+	service.deregister_calibration(adc, channel);
+	match fit_calibration(service, adc, channel, model, order, data_points_count).await {
+		Ok(true) => service.replace_previous_calibration(adc, channel).await,
+		result => {
+			service.reregister_calibration();
+			result.map(|_| ())
+		}
+	}
+}
+
+// Collects the data points, fits the model and saves it as the calibration of the channel. Returns whether it was saved.
+async fn fit_calibration<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service,
+	adc: AdcDevice,
+	channel: Service::Channel,
+	model: CalibrationModelType,
+	order: usize,
+	data_points_count: u8,
+) -> Result<bool, Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	// Start collecting data points
+	// Data points whose reading doesn't settle are rejected and asked for again, unless the operator aborts the calibration
+	let mut calibration_data_points: Vec<CalibrationDataPoint, MAX_CALIBRATION_DATA_POINTS> = Vec::new();
+	while calibration_data_points.len() < data_points_count as usize {
+		let message: String<64> = format!("Data Point #{}. Enter expected value in {}:\n", calibration_data_points.len() + 1, Service::UNIT)
+			.map_err(|_| Service::Error::format_error())?;
+		let expected: f64 = service.prompt(message.as_str()).await?;
+		let Some(reading) = capture_stable_reading(service, adc, channel, Service::calibration_value).await? else {
+			let retry: u8 = service
+				.prompt("Reading not stable, data point rejected. Enter 0 to take it again once the reference has settled or 1 to abort:\n")
+				.await?;
+			if retry != 0 {
+				service.send_message("Calibration aborted.\n").await?;
+				return Ok(false);
+			}
+			continue;
+		};
+		let data_point = CalibrationDataPoint {
+			expected,
+			measured: reading.mean,
+			uncertainty: reading.uncertainty,
+		};
+		calibration_data_points.push(data_point).unwrap(); // Safe due to prior checks
+
+		let confirmation_message: String<128> = format!(
+			"Expected = {:.2} {}, Measured = {:.4} ± {:.4} {} ({} readings)\n",
+			expected,
+			Service::UNIT,
+			reading.mean,
+			reading.uncertainty,
+			Service::UNIT,
+			reading.reading_count
+		)
+		.map_err(|_| Service::Error::format_error())?;
+		service.send_message(confirmation_message.as_str()).await?;
+	}
+
+	let points: Vec<(f64, f64), MAX_CALIBRATION_DATA_POINTS> = calibration_data_points
+		.iter()
+		.map(|data_point| (data_point.measured, data_point.expected))
+		.collect();
+	let uncertainties: Vec<f64, MAX_CALIBRATION_DATA_POINTS> = calibration_data_points.iter().map(|data_point| data_point.uncertainty).collect();
+	let calibration_model = match model {
+		CalibrationModelType::Linear => {
+			// Perform Ordinary Least Squares Fit, a line is the polynomial of order 1
+			let Some(coefficients) = fit_polynomial(&points, 1) else {
+				service.send_message("Data points don't determine the fit, use more distinct values.\n").await?;
+				return Ok(false);
+			};
+			let (scale, offset) = (coefficients[1], coefficients[0]);
+			let result_message: String<128> = format!(
+				"Ordinary Least Squares Fit complete. Scale: {:.6}, Offset: {:.2} {}\n",
+				scale,
+				offset,
+				Service::UNIT
+			)
+			.map_err(|_| Service::Error::format_error())?;
+			service.send_message(result_message.as_str()).await?;
+			CalibrationModel::Linear { scale, offset }
+		}
+		CalibrationModelType::Polynomial => {
+			let Some(coefficients) = fit_polynomial(&points, order) else {
+				service.send_message("Data points don't determine the fit, use more distinct values.\n").await?;
+				return Ok(false);
+			};
+			service.send_message("Polynomial fit complete.\n").await?;
+			for (power, coefficient) in coefficients.iter().enumerate() {
+				let message: String<64> = format!("c{} = {:e}\n", power, coefficient).map_err(|_| Service::Error::format_error())?;
+				service.send_message(message.as_str()).await?;
+			}
+			CalibrationModel::Polynomial { coefficients }
+		}
+		CalibrationModelType::LookupTable => {
+			let Some(table) = build_lookup_table(&points) else {
+				service.send_message("Two data points have the same measured value.\n").await?;
+				return Ok(false);
+			};
+			service.send_message("Lookup table complete.\n").await?;
+			CalibrationModel::LookupTable { points: table }
+		}
+		// Fitted by the services that offer them, from data points of their own
+		CalibrationModelType::TemperatureCompensated => {
+			service.send_message("Invalid calibration model.\n").await?;
+			return Ok(false);
+		}
+	};
+	if !check_fit_quality(service, calibration_model.r_squared(&points)).await? {
+		return Ok(false);
+	}
+
+	// Update calibration for the channel and record it in the calibration history
+	let notes: String<256> = service.prompt("Enter calibration notes (optional):\n").await?;
+	let local_session = service.session_service().lock().await.current_session;
+	let record = service
+		.calibration_model_service()
+		.save_calibration(adc, channel, calibration_model, &points, &uncertainties, local_session, notes.as_str())
+		.await?;
+	let message: String<64> = format!("Calibration #{} saved.\n", record.id).map_err(|_| Service::Error::format_error())?;
+	service.send_message(message.as_str()).await?;
+	Ok(true)
+}
+
+/// Reports the R² of a fit, fits below MIN_CALIBRATION_R_SQUARED are warned about or refused depending on LOW_QUALITY_FIT_ACTION.
+/// Returns whether the fit can be saved.
+pub async fn check_fit_quality<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service,
+	r_squared: Option<f64>,
+) -> Result<bool, Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	let Some(r_squared) = r_squared else {
+		service
+			.send_message("R² not defined for this fit, the quality check is skipped.\n")
+			.await?;
+		return Ok(true);
+	};
+	let message: String<64> = format!("R² = {:.6}\n", r_squared).map_err(|_| Service::Error::format_error())?;
+	service.send_message(message.as_str()).await?;
+	if r_squared >= Service::MIN_CALIBRATION_R_SQUARED {
+		return Ok(true);
+	}
+
+	match Service::LOW_QUALITY_FIT_ACTION {
+		LowQualityFitAction::Warn => {
+			let message: String<96> = format!("Warning: R² is below the minimum of {:.6}.\n", Service::MIN_CALIBRATION_R_SQUARED)
+				.map_err(|_| Service::Error::format_error())?;
+			service.send_message(message.as_str()).await?;
+			Ok(true)
+		}
+		LowQualityFitAction::Refuse => {
+			let message: String<96> = format!(
+				"R² is below the minimum of {:.6}, calibration refused.\n",
+				Service::MIN_CALIBRATION_R_SQUARED
+			)
+			.map_err(|_| Service::Error::format_error())?;
+			service.send_message(message.as_str()).await?;
+			Ok(false)
+		}
+	}
+}
+
+/// Lists the most recent calibrations of the channel from the calibration history.
+pub async fn list_calibrations<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service,
+	adc: AdcDevice,
+	channel: Service::Channel,
+) -> Result<(), Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	let records = service.calibration_model_service().load_calibration_history(adc, channel).await?;
+	if records.is_empty() {
+		service.send_message("No calibrations recorded for this channel.\n").await?;
+		return Ok(());
+	}
+	for record in records.iter() {
+		let summary = record.summary().map_err(|_| Service::Error::format_error())?;
+		service.send_message(summary.as_str()).await?;
+		service.send_message("\n").await?;
+	}
+	Ok(())
+}
+
+/// Prompts for a calibration of the calibration history and saves it as the calibration of the channel again.
+/// Same as a new calibration, it replaces whatever else the service held for the channel, see restore_recorded_calibration.
+pub async fn restore_calibration<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service,
+	adc: AdcDevice,
+	channel: Service::Channel,
+) -> Result<(), Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	let id: u16 = service.prompt("Enter the number of the calibration to restore:\n").await?;
+	let Some(record) = service.restore_recorded_calibration(adc, channel, id).await? else {
+		service.send_message("Calibration not found for this channel.\n").await?;
+		return Ok(());
+	};
+
+	let message: String<64> = format!("Calibration #{} restored.\n", record.id).map_err(|_| Service::Error::format_error())?;
+	service.send_message(message.as_str()).await?;
+	Ok(())
+}
+
+/// Saves a calibration model from the calibration history as the calibration of the channel again, and drops what it replaces.
+/// The default of CalibratedSensorService::restore_recorded_calibration, for the services whose calibrations are all models.
+pub async fn restore_calibration_model<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service,
+	adc: AdcDevice,
+	channel: Service::Channel,
+	id: u16,
+) -> Result<Option<CalibrationRecord<Service::Channel, f64>>, Service::Error>
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	let Some(record) = service.calibration_model_service().restore_calibration(adc, channel, id).await? else {
+		return Ok(None);
+	};
+	service.replace_previous_calibration(adc, channel).await?;
+	Ok(Some(record))
+}
+
+/// Ends a calibration interrupted by leaving the Calibrating state, the calibration task is dropped wherever it was waiting.
+/// Any protocol session is discarded and the deregistered calibration of the channel is registered again.
+pub fn end_interrupted_calibration<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(service: &mut Service)
+where
+	Service: CalibratedSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	if let Some(session) = service.calibration_session().take() {
+		info!("Calibration session for {:?} {:?} ended by a state change", session.adc, session.channel);
+	}
+	service.reregister_calibration();
+}
+
+// Kind whose calibrate task runs the calibration, the calibrate tasks of a board with several kinds take turns as they share the serial port
+// Selected by the operator before each text calibration, and in the calibration protocol the kind holding the open session
+static CALIBRATED_KIND: AsyncMutex<Option<ChannelKind>> = AsyncMutex::new(None);
+
+// Calibration commands read from the ground application, waiting for the calibrate task of the kind they are routed to
+static THERMOCOUPLE_CALIBRATION_COMMANDS: CalibrationCommandQueue = Channel::new();
+static PRESSURE_CALIBRATION_COMMANDS: CalibrationCommandQueue = Channel::new();
+static STRAIN_CALIBRATION_COMMANDS: CalibrationCommandQueue = Channel::new();
+
+/// Waits until the next text calibration is the turn of the kind, the first waiting calibrate task prompts the operator for the kind.
+/// End the turn with end_calibration_turn once the calibration is done.
+pub async fn take_calibration_turn(
+	kind: ChannelKind,
+	serial_service: &AsyncMutex<UORSerial>,
+) -> Result<(), UsartError> {
+	loop {
+		{
+			let mut calibrated_kind = CALIBRATED_KIND.lock().await;
+			if calibrated_kind.is_none() {
+				*calibrated_kind = Some(select_calibrated_kind(serial_service).await?);
+			}
+			if *calibrated_kind == Some(kind) {
+				return Ok(());
+			}
+		}
+		Timer::after_millis(CALIBRATION_TURN_POLL_INTERVAL).await;
+	}
+}
+
+/// Ends the turn of the kind calibrated last, so the next text calibration prompts for the kind again.
+/// Called as well when leaving the Calibrating state, which ends the sessions of the calibration protocol and drops the commands still waiting.
+pub async fn end_calibration_turn() {
+	*CALIBRATED_KIND.lock().await = None;
+	for kind in [ChannelKind::Thermocouple, ChannelKind::Pressure, ChannelKind::Strain] {
+		while calibration_command_queue(kind).try_receive().is_ok() {}
+	}
+}
+
+/// Reads the next calibration command from the ground application and routes it to the calibrate task of its kind, see receive_calibration_command.
+/// StartSession and List commands are routed to the kind of their ADC, the other commands to the kind of the open session.
+/// The input of the serial port is read apart from the serial port itself, so the calibrate tasks can answer while the command is awaited.
+pub async fn route_calibration_command(serial_input: &AsyncMutex<UORSerialRx>) -> Result<(), UsartError> {
+	let message = serial_input.lock().await.read_envelope_message().await?;
+	// None for a message that isn't a calibration command, answered with a CalibrationError by the kind it is routed to
+	let command = match message {
+		Some(EnvelopeMessage::CalibrationCommand(CalibrationCommand { command })) => command,
+		_ => None,
+	};
+	let kind = calibration_command_kind(command.as_ref()).await;
+	calibration_command_queue(kind).send(command).await;
+	Ok(())
+}
+
+/// Records whether the kind holds the open calibration protocol session once it has handled a command, see route_calibration_command.
+/// Called before the command is answered, so the next command is routed with the session the command left.
+pub async fn set_calibration_session_kind(
+	kind: ChannelKind,
+	has_session: bool,
+) {
+	let mut calibrated_kind = CALIBRATED_KIND.lock().await;
+	if has_session {
+		*calibrated_kind = Some(kind);
+	} else if *calibrated_kind == Some(kind) {
+		*calibrated_kind = None;
+	}
+}
+
+/// Waits for the next calibration command routed to the kind by route_calibration_command.
+/// Returns None for a message that isn't a calibration command, to be answered with a CalibrationError.
+pub async fn receive_calibration_command(kind: ChannelKind) -> Option<Command> {
+	calibration_command_queue(kind).receive().await
+}
+
+// Prompts for the kind calibrated next until a kind wired to an ADC is entered, boards with a single kind skip the prompt
+async fn select_calibrated_kind(serial_service: &AsyncMutex<UORSerial>) -> Result<ChannelKind, UsartError> {
+	if ADC_CHANNEL_KINDS.iter().all(|kind| *kind == ADC_CHANNEL_KINDS[0]) {
+		return Ok(ADC_CHANNEL_KINDS[0]);
+	}
+
+	let mut serial_service = serial_service.lock().await;
+	loop {
+		let mut input = String::<256>::new();
+		serial_service
+			.write_str("Select the sensors to calibrate (0 = Thermocouples, 1 = Pressure transducers, 2 = Strain gauges):\n")
+			.await?;
+		serial_service.read_line(&mut input).await?;
+		let kind = match input.trim().parse::<u8>() {
+			Ok(0) => ChannelKind::Thermocouple,
+			Ok(1) => ChannelKind::Pressure,
+			Ok(2) => ChannelKind::Strain,
+			_ => {
+				serial_service.write_str("Invalid sensor kind.\n").await?;
+				continue;
+			}
+		};
+		if ADC_CHANNEL_KINDS.contains(&kind) {
+			return Ok(kind);
+		}
+		serial_service.write_str("No ADC is wired to these sensors.\n").await?;
+	}
+}
+
+// StartSession and List commands go to the kind of their ADC, the other commands to the kind of the open session
+// A StartSession while a session is open goes to the kind of the session, which answers it with SessionInProgress
+// Without a session or a valid ADC, the first kind answers them with a CalibrationError
+async fn calibration_command_kind(command: Option<&Command>) -> ChannelKind {
+	let session_kind = *CALIBRATED_KIND.lock().await;
+	let default_kind = session_kind.unwrap_or(ADC_CHANNEL_KINDS[0]);
+	match command {
+		Some(Command::StartSession(start_session)) => session_kind.or(adc_channel_kind(start_session.adc_device)).unwrap_or(default_kind),
+		Some(Command::List(list)) => adc_channel_kind(list.adc_device).unwrap_or(default_kind),
+		_ => default_kind,
+	}
+}
+
+fn calibration_command_queue(kind: ChannelKind) -> &'static CalibrationCommandQueue {
+	match kind {
+		ChannelKind::Thermocouple => &THERMOCOUPLE_CALIBRATION_COMMANDS,
+		ChannelKind::Pressure => &PRESSURE_CALIBRATION_COMMANDS,
+		ChannelKind::Strain => &STRAIN_CALIBRATION_COMMANDS,
+	}
+}
+
+fn adc_channel_kind(adc_device: i32) -> Option<ChannelKind> {
+	usize::try_from(adc_device).ok().and_then(|adc| ADC_CHANNEL_KINDS.get(adc)).copied()
+}
@@ -0,0 +1,44 @@
+use strum::EnumCount;
+
+use crate::adc::types::AdcDevice;
+use crate::sensor::types::ChannelKind;
+
+// Kind of sensor the channels of each ADC are wired to, indexed by ADC
+// Each sensor service only configures, reads, calibrates and logs the ADCs wired to its kind
+
+// Boards built with a single sensor feature wire every ADC to that kind
+#[cfg(all(feature = "temperature", not(any(feature = "pressure", feature = "strain"))))]
+pub const ADC_CHANNEL_KINDS: [ChannelKind; AdcDevice::COUNT] = [ChannelKind::Thermocouple; AdcDevice::COUNT];
+
+#[cfg(all(feature = "pressure", not(any(feature = "temperature", feature = "strain"))))]
+pub const ADC_CHANNEL_KINDS: [ChannelKind; AdcDevice::COUNT] = [ChannelKind::Pressure; AdcDevice::COUNT];
+
+// The strain service is built without a sensor feature too, e.g. for the tests of the library
+#[cfg(not(any(feature = "temperature", feature = "pressure")))]
+pub const ADC_CHANNEL_KINDS: [ChannelKind; AdcDevice::COUNT] = [ChannelKind::Strain; AdcDevice::COUNT];
+
+// Boards built with two sensor features wire the first ADC to one kind and the second ADC to the other, in the order
+// thermocouple, pressure, strain. E.g. --features temperature,pressure reads 4 thermocouples on the first ADC and 4 pressure
+// transducers on the second. Swap the kinds below if the board is wired the other way around
+#[cfg(all(feature = "temperature", feature = "pressure", not(feature = "strain")))]
+pub const ADC_CHANNEL_KINDS: [ChannelKind; AdcDevice::COUNT] = [ChannelKind::Thermocouple, ChannelKind::Pressure];
+
+#[cfg(all(feature = "temperature", feature = "strain", not(feature = "pressure")))]
+pub const ADC_CHANNEL_KINDS: [ChannelKind; AdcDevice::COUNT] = [ChannelKind::Thermocouple, ChannelKind::Strain];
+
+#[cfg(all(feature = "pressure", feature = "strain", not(feature = "temperature")))]
+pub const ADC_CHANNEL_KINDS: [ChannelKind; AdcDevice::COUNT] = [ChannelKind::Pressure, ChannelKind::Strain];
+
+// Each ADC reads a single kind, so the two ADCs can't hold all three
+#[cfg(all(feature = "temperature", feature = "pressure", feature = "strain"))]
+compile_error!("An Argus has two ADCs, enable at most two of the features: 'pressure', 'temperature', or 'strain'.");
+
+// Maximum number of calibration data points allowed to be collected during a calibration session per channel
+pub const MAX_CALIBRATION_DATA_POINTS: usize = 10;
+
+// How often the calibrate task of each kind checks whether the next calibration is its turn, see sensor::calibration
+pub const CALIBRATION_TURN_POLL_INTERVAL: u64 = 100; // milliseconds
+
+// Calibration commands of the calibration protocol waiting for the calibrate task of their kind, see sensor::calibration
+// The ground application waits for the response to a command before sending the next one
+pub const CALIBRATION_COMMAND_QUEUE_SIZE: usize = 1;
@@ -0,0 +1,35 @@
+use core::str::FromStr;
+
+use heapless::String;
+use uor_peripherals::serial::peripheral::{UORSerial, UsartError};
+use uor_utils::utils::types::AsyncMutex;
+
+use crate::sensor::types::SensorServiceError;
+
+// Text prompts shared by the calibrations of the sensor services
+
+/// Sends the prompt and parses the line answered to it.
+pub async fn prompt<T, E>(
+	serial_service: &AsyncMutex<UORSerial>,
+	prompt: &str,
+) -> Result<T, E>
+where
+	T: FromStr,
+	E: SensorServiceError, {
+	let mut serial_service = serial_service.lock().await;
+	let mut input = String::<256>::new();
+	serial_service.write_str(prompt).await?;
+	serial_service.read_line(&mut input).await?;
+	input.trim().parse().map_err(|_| E::format_error())
+}
+
+pub async fn send_message<E>(
+	serial_service: &AsyncMutex<UORSerial>,
+	message: &str,
+) -> Result<(), E>
+where
+	E: From<UsartError>, {
+	let mut serial_service = serial_service.lock().await;
+	serial_service.write_str(message).await?;
+	Ok(())
+}
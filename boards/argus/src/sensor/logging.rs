@@ -0,0 +1,89 @@
+use defmt::info;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
+use heapless::format;
+use strum::EnumCount;
+use uor_peripherals::serial::peripheral::UORSerial;
+use uor_utils::csv::SerializeCSV;
+use uor_utils::utils::types::AsyncMutex;
+
+use crate::adc::service::AdcService;
+use crate::adc::types::AdcDevice;
+use crate::sd::service::SDCardService;
+use crate::sd::types::{FileName, OperationScope};
+use crate::sensor::types::SensorKind;
+use crate::session::service::SessionService;
+use crate::state_machine::service::StateMachineWorker;
+use crate::state_machine::types::States;
+
+/// Picks up the readings of a kind of sensor from its queue, logs them to the SD card and streams them over serial.
+/// Embassy tasks can't be generic, each sensor service spawns a task that calls this with its kind.
+pub async fn log_measurements<Kind, const QUEUE_SIZE: usize>(
+	mut worker: StateMachineWorker,
+	reading_queue: &'static Channel<CriticalSectionRawMutex, Kind::Reading, QUEUE_SIZE>,
+	adc_service: &'static AdcService<{ AdcDevice::COUNT }>,
+	serial_service_mutex: &'static AsyncMutex<UORSerial>,
+	sd_card_service_mutex: &'static AsyncMutex<SDCardService>,
+	session_service: &'static AsyncMutex<SessionService>,
+) where
+	Kind: SensorKind, {
+	worker
+		.run_once(&[States::Recording], async |_| -> Result<(), ()> {
+			initialize_csv_files::<Kind>(adc_service, sd_card_service_mutex, session_service).await;
+			Ok(())
+		})
+		.await
+		.unwrap();
+
+	worker
+		.run_while(&[States::Recording], async |_| -> Result<(), ()> {
+			let reading = reading_queue.receive().await;
+			let path = get_path_from_adc_and_channel::<Kind>(Kind::adc_device(&reading) as usize, Kind::channel_index(&reading));
+			let line = reading.to_csv_line();
+			SDCardService::enqueue_write(OperationScope::CurrentSession, path, line).await;
+			let _ = serial_service_mutex
+				.lock()
+				.await
+				.write_envelope_message(Kind::to_envelope_message(&reading))
+				.await;
+			Ok(())
+		})
+		.await
+		.unwrap();
+}
+
+// Create the files and write the CSV headers before starting the logging loop
+async fn initialize_csv_files<Kind>(
+	adc_service: &'static AdcService<{ AdcDevice::COUNT }>,
+	sd_card_service_mutex: &'static AsyncMutex<SDCardService>,
+	session_service: &'static AsyncMutex<SessionService>,
+) where
+	Kind: SensorKind, {
+	// Ensure session is set. Ignore if it errors like SD card not mounted, etc.
+	let _ = session_service.lock().await.ensure_session().await;
+
+	info!("Initializing CSV files for {:?} measurement logging.", Kind::CHANNEL_KIND);
+	let mut sd_card_service = sd_card_service_mutex.lock().await;
+	for adc_index in 0..AdcDevice::COUNT {
+		// Missing or faulted ADCs and the ADCs wired to other kinds don't log anything, so no files are created for them
+		if !adc_service.is_available_for(AdcDevice::from(adc_index), Kind::CHANNEL_KIND).await {
+			continue;
+		}
+
+		for channel in 0..Kind::Channel::COUNT {
+			let path = get_path_from_adc_and_channel::<Kind>(adc_index, channel);
+
+			// Ignore because if the SD card isn't mounted we don't want to panic
+			let _ = sd_card_service.write(OperationScope::CurrentSession, path, Kind::Reading::get_csv_header());
+		}
+	}
+}
+
+fn get_path_from_adc_and_channel<Kind>(
+	adc_index: usize,
+	channel: usize,
+) -> FileName
+where
+	Kind: SensorKind, {
+	format!("{}_{}_{}.csv", Kind::FILE_PREFIX, adc_index, channel).unwrap() as FileName
+}
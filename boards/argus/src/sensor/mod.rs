@@ -0,0 +1,7 @@
+pub mod acquisition;
+pub mod calibration;
+pub mod config;
+pub mod console;
+pub mod logging;
+pub mod setup;
+pub mod types;
@@ -0,0 +1,101 @@
+use defmt::error;
+use embassy_time::Timer;
+use uor_peripherals::serial::peripheral::UORSerial;
+use uor_utils::utils::types::AsyncMutex;
+
+use crate::adc::driver::types::{DataIntegrityCheck, DataRate, Filter, Gain, ReferenceRange};
+use crate::adc::service::{AdcDriver, AdcError, AdcService};
+use crate::adc::types::AdcDevice;
+use crate::sensor::types::ChannelKind;
+use crate::session::service::SessionService;
+
+// ADC setup shared by the sensor services. The steps that apply to every ADC run once in main,
+// each service then configures the ADCs wired to its kind of sensor
+
+/// Probes the ADCs, loads their calibrations and starts the session, before the sensor services are set up.
+#[cfg_attr(feature = "calibration", allow(unused_variables))]
+pub async fn prepare_adcs<const ADC_COUNT: usize>(
+	adc_service: &'static AdcService<ADC_COUNT>,
+	serial_service: &'static AsyncMutex<UORSerial>,
+	session_service: &'static AsyncMutex<SessionService>,
+) {
+	// Delay for 100ms to ensure ADCs are powered up
+	Timer::after_millis(100).await;
+
+	// Probe the ADCs so a missing one is skipped instead of failing the whole board
+	adc_service.detect_devices(serial_service).await;
+
+	// Calibrations are loaded first so apply_configurations() writes them to the ADCs
+	match adc_service.load_calibrations().await {
+		Err(e) => error!("Failed to load ADC calibrations: {:?}", e),
+		_ => {}
+	}
+
+	// The session records the ADC register snapshots and tare offsets. The services are set up without it if the SD card isn't available
+	// Calibration runs don't record anything, so no session directory is created for them
+	#[cfg(not(feature = "calibration"))]
+	match session_service.lock().await.ensure_session().await {
+		Err(e) => error!("Failed to start a session for the ADC register snapshots: {:?}", e),
+		_ => {}
+	}
+}
+
+/// Configures the available ADCs wired to the kind of sensor, ADCs that fail to be configured are marked faulted.
+/// The configuration common to every kind is set before `configure` adds the settings of the kind, e.g. its acquisition profiles.
+pub async fn configure_adcs<const ADC_COUNT: usize>(
+	adc_service: &'static AdcService<ADC_COUNT>,
+	kind: ChannelKind,
+	mut configure: impl FnMut(&mut AdcDriver) -> Result<(), AdcError>,
+) {
+	for (adc_index, driver) in adc_service.drivers.iter().enumerate() {
+		let adc = AdcDevice::from(adc_index);
+		if !adc_service.is_available_for(adc, kind).await {
+			continue;
+		}
+
+		let mut driver = driver.lock().await;
+		driver.reference_range = ReferenceRange::Avdd;
+		driver.data_rate = DataRate::Sps100;
+		driver.filter = Filter::Sinc3;
+		driver.enable_internal_reference = true;
+		driver.gain = Gain::G32;
+		driver.enable_status_byte = true; // Samples taken during PGA/reference alarms or after an unexpected reset are rejected
+		driver.data_integrity_check = DataIntegrityCheck::Crc; // Samples corrupted on the SPI bus are rejected
+		driver.verify_writes = true; // The ADC is marked faulted if any register doesn't hold the value written to it
+		let configured = match configure(&mut *driver) {
+			Ok(()) => driver.apply_configurations().await,
+			Err(e) => Err(e),
+		};
+
+		if let Err(e) = configured {
+			error!("Failed to configure {:?}: {:?}", adc, e);
+			adc_service.mark_faulted(adc).await;
+		}
+	}
+}
+
+/// Marks every available ADC wired to the kind of sensor faulted, e.g. when its service failed to be set up.
+/// The other kinds keep running, the measurement tasks of the kind then skip its ADCs.
+pub async fn mark_kind_faulted<const ADC_COUNT: usize>(
+	adc_service: &'static AdcService<ADC_COUNT>,
+	kind: ChannelKind,
+) {
+	for adc_index in 0..ADC_COUNT {
+		let adc = AdcDevice::from(adc_index);
+		if adc_service.is_available_for(adc, kind).await {
+			adc_service.mark_faulted(adc).await;
+		}
+	}
+}
+
+/// Documents the exact ADC configuration the session runs with, once every sensor service has configured its ADCs.
+/// Skipped if the session couldn't be started, see prepare_adcs.
+pub async fn save_register_snapshots<const ADC_COUNT: usize>(
+	adc_service: &'static AdcService<ADC_COUNT>,
+	session_service: &'static AsyncMutex<SessionService>,
+) -> Result<(), AdcError> {
+	if session_service.lock().await.current_session.is_none() {
+		return Ok(());
+	}
+	adc_service.save_register_snapshots().await
+}
@@ -0,0 +1,214 @@
+use core::future::Future;
+use core::str::FromStr;
+
+use defmt::Format;
+use embassy_sync::blocking_mutex::raw::CriticalSectionRawMutex;
+use embassy_sync::channel::Channel;
+use uor_peripherals::serial::peripheral::{UORSerial, UsartError};
+use uor_utils::csv::SerializeCSV;
+use uor_utils::messages::argus::calibration::calibration_command::calibration_command::Command;
+use uor_utils::messages::argus::calibration::calibration_model::CalibrationModel as CalibrationModelProtobuf;
+use uor_utils::messages::argus::calibration::calibration_response::CalibrationResponse;
+use uor_utils::messages::argus::envelope::envelope::Message as EnvelopeMessage;
+use uor_utils::utils::types::AsyncMutex;
+
+use crate::adc::service::AdcService;
+use crate::adc::types::AdcDevice;
+use crate::calibration_model::service::CalibrationModelService;
+use crate::calibration_model::types::{CalibrationModelType, CalibrationRecord, ChannelMarker, LowQualityFitAction, StabilityCriteria};
+use crate::calibration_protocol::handler;
+use crate::calibration_protocol::session::{CalibrationSession, SessionError};
+use crate::sd::config::MAX_LINE_LENGTH;
+use crate::sd::types::SdCardError;
+use crate::sensor::calibration::restore_calibration_model;
+use crate::sensor::config::CALIBRATION_COMMAND_QUEUE_SIZE;
+use crate::sensor::console;
+use crate::session::service::SessionService;
+
+// Kind of sensor wired to the channels of an ADC, see ADC_CHANNEL_KINDS
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Format)]
+pub enum ChannelKind {
+	Thermocouple,
+	Pressure,
+	Strain,
+}
+
+impl ChannelKind {
+	/// Name of the sensors of the kind in the calibration prompts.
+	pub fn name(&self) -> &'static str {
+		match self {
+			ChannelKind::Thermocouple => "thermocouple",
+			ChannelKind::Pressure => "pressure",
+			ChannelKind::Strain => "strain",
+		}
+	}
+}
+
+// A data point of a calibration through the text prompts, see sensor::calibration
+#[derive(Debug, Clone, Copy, Format)]
+pub struct CalibrationDataPoint {
+	// Value measured by the calibration instrument, in the unit of the service
+	pub expected: f64,
+
+	// Average of the stable readings of the channel
+	pub measured: f64,
+
+	// Standard error of the measured value
+	pub uncertainty: f64,
+}
+
+// Calibration commands routed to the calibrate task of a kind, None for a message that isn't a calibration command
+pub type CalibrationCommandQueue = Channel<CriticalSectionRawMutex, Option<Command>, CALIBRATION_COMMAND_QUEUE_SIZE>;
+
+/// A kind of sensor the channels of an Argus can be wired to, implemented by a marker type of each sensor service.
+/// Describes the channels and readings of the kind so the logging pipeline can be shared between the services.
+pub trait SensorKind {
+	// Identifies the kind in ADC_CHANNEL_KINDS
+	const CHANNEL_KIND: ChannelKind;
+
+	// Prefix of the session files the readings are logged to, e.g. P for P_0_1.csv, the second channel of the first ADC
+	const FILE_PREFIX: &'static str;
+
+	type Channel: ChannelMarker;
+	type Reading: SerializeCSV<MAX_LINE_LENGTH>;
+
+	fn adc_device(reading: &Self::Reading) -> AdcDevice;
+
+	// Index of the channel within its ADC, as in the file names
+	fn channel_index(reading: &Self::Reading) -> usize;
+
+	fn to_envelope_message(reading: &Self::Reading) -> EnvelopeMessage;
+}
+
+/// Errors of the sensor services, the shared text prompts, acquisition and calibration protocol report their failures through them.
+pub trait SensorServiceError: From<UsartError> + From<SessionError> + Format {
+	fn format_error() -> Self;
+
+	fn open_circuit() -> Self;
+
+	/// The reason a calibration command couldn't be carried out, None for the errors of the board itself.
+	fn session_error(&self) -> Option<SessionError>;
+}
+
+/// A sensor service whose channels are calibrated, so the calibration steps of sensor::calibration and the calibration protocol commands of
+/// calibration_protocol::handler can be shared between the services.
+pub trait CalibratedSensorService<const ADC_COUNT: usize, const CHANNEL_COUNT: usize> {
+	// Identifies the ADCs the service calibrates in ADC_CHANNEL_KINDS
+	const CHANNEL_KIND: ChannelKind;
+
+	// When the readings of a calibration data point are stable, in the units of the value the data point is taken from
+	const STABILITY_CRITERIA: StabilityCriteria;
+
+	// Minimum R² of a calibration fit over its data points
+	const MIN_CALIBRATION_R_SQUARED: f64;
+
+	// Whether a calibration fit below MIN_CALIBRATION_R_SQUARED is still saved after warning the operator, or refused
+	const LOW_QUALITY_FIT_ACTION: LowQualityFitAction;
+
+	// Unit of the calibrated values, shown in the calibration prompts
+	const UNIT: &'static str;
+
+	// Models the calibration prompts offer, listed in this order
+	const CALIBRATION_MODELS: &'static [CalibrationModelType] = &[
+		CalibrationModelType::Linear,
+		CalibrationModelType::Polynomial,
+		CalibrationModelType::LookupTable,
+	];
+
+	// Models a calibration protocol session can be started with
+	const PROTOCOL_MODELS: &'static [CalibrationModelProtobuf] = &[
+		CalibrationModelProtobuf::Linear,
+		CalibrationModelProtobuf::Polynomial,
+		CalibrationModelProtobuf::LookupTable,
+	];
+
+	type Channel: ChannelMarker + From<usize>;
+	type Reading;
+	type Error: SensorServiceError + From<SdCardError>;
+
+	fn adc_service(&self) -> &'static AdcService<ADC_COUNT>;
+
+	fn serial_service(&self) -> &'static AsyncMutex<UORSerial>;
+
+	fn session_service(&self) -> &'static AsyncMutex<SessionService>;
+
+	fn calibration_model_service(&mut self) -> &mut CalibrationModelService<Self::Channel, f64, ADC_COUNT, CHANNEL_COUNT>;
+
+	/// Whether a calibration protocol session is running, live readings are then sent as LiveReading responses instead of text.
+	fn has_calibration_session(&self) -> bool;
+
+	/// Calibration driven by the ground application over the calibration protocol, None outside of a session.
+	fn calibration_session(&mut self) -> &mut Option<CalibrationSession<Self::Channel>>;
+
+	/// Quantity of a reading the data points of a calibration protocol session are taken from.
+	fn calibration_value(reading: &Self::Reading) -> f64;
+
+	/// Reads the channel once, with the calibration of the channel deregistered while it is calibrated.
+	fn read_calibration_reading(
+		&mut self,
+		adc: AdcDevice,
+		channel: Self::Channel,
+	) -> impl Future<Output = Result<Self::Reading, Self::Error>>;
+
+	fn send_calibration_response(
+		&mut self,
+		response: CalibrationResponse,
+	) -> impl Future<Output = Result<(), Self::Error>>;
+
+	/// Deregisters the calibration of the channel so its raw values can be measured, until the new calibration is saved or it is
+	/// registered again by reregister_calibration.
+	fn deregister_calibration(
+		&mut self,
+		adc: AdcDevice,
+		channel: Self::Channel,
+	);
+
+	/// Registers the calibration deregistered by deregister_calibration again, when no new calibration was saved.
+	fn reregister_calibration(&mut self);
+
+	/// Fits the model of the calibration protocol session to its data points.
+	fn compute_calibration_fit(&mut self) -> Result<CalibrationResponse, Self::Error>
+	where
+		Self: Sized, {
+		handler::compute_calibration_fit(self)
+	}
+
+	/// Drops the calibration deregistered for the calibration of the channel once a new one is saved or restored,
+	/// along with anything else of the service the new calibration replaces.
+	fn replace_previous_calibration(
+		&mut self,
+		adc: AdcDevice,
+		channel: Self::Channel,
+	) -> impl Future<Output = Result<(), Self::Error>>;
+
+	/// Saves a calibration from the calibration history as the calibration of the channel again. Same as a new calibration, it replaces
+	/// whatever else the service held for the channel, see replace_previous_calibration.
+	/// Returns None if the history has no complete calibration with that id for the channel.
+	fn restore_recorded_calibration(
+		&mut self,
+		adc: AdcDevice,
+		channel: Self::Channel,
+		id: u16,
+	) -> impl Future<Output = Result<Option<CalibrationRecord<Self::Channel, f64>>, Self::Error>>
+	where
+		Self: Sized, {
+		restore_calibration_model(self, adc, channel, id)
+	}
+
+	/// Sends the prompt and parses the line answered to it.
+	fn prompt<T>(
+		&self,
+		prompt: &str,
+	) -> impl Future<Output = Result<T, Self::Error>>
+	where
+		T: FromStr, {
+		console::prompt(self.serial_service(), prompt)
+	}
+
+	fn send_message(
+		&self,
+		message: &str,
+	) -> impl Future<Output = Result<(), Self::Error>> {
+		console::send_message(self.serial_service(), message)
+	}
+}
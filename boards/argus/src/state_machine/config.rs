@@ -1 +1,15 @@
-pub const MAX_CONCURRENT_TASKS: usize = 4;
+// Maximum number of StateMachineWorkers, each task following the state holds a receiver of the current state
+// Sized from the tasks spawned in main for the sensor features the board is built with
+pub const MAX_CONCURRENT_TASKS: usize = GENERAL_TASKS + TEMPERATURE_TASKS + PRESSURE_TASKS + STRAIN_TASKS;
+
+// Board health
+const GENERAL_TASKS: usize = 1;
+
+// RTDs, thermocouples, logging and calibration
+const TEMPERATURE_TASKS: usize = if cfg!(feature = "temperature") { 4 } else { 0 };
+
+// Pressure transducers, manifold temperature, logging, calibration and tare
+const PRESSURE_TASKS: usize = if cfg!(feature = "pressure") { 5 } else { 0 };
+
+// Strain gauges, logging, calibration and tare
+const STRAIN_TASKS: usize = if cfg!(feature = "strain") { 4 } else { 0 };
@@ -87,27 +87,46 @@ impl StateMachineWorker {
 
 		// Runs indefinitely
 		loop {
-			// Wait until we're in one of the desired states
-			if !desired_states.contains(&self.current_state.get().await) {
-				self.current_state.changed_and(|state| desired_states.contains(state)).await;
-			}
+			self.run_until_exit(desired_states, &mut action).await?;
+		}
+	}
 
-			// We're now in a desired state, run the action until the state changes
-			loop {
-				// Race between state change or action completion
-				let state_changed = self.current_state.changed();
-				let action_finished = action(self.orchestrator);
+	/// Waits for one of the desired states and runs the action repeatedly until the state machine leaves them.
+	/// The action is dropped wherever it was waiting when the state changes, so the caller can undo what it left unfinished once this returns.
+	pub async fn run_until_exit<Err, Act, Fut>(
+		&mut self,
+		desired_states: &[States],
+		mut action: Act,
+	) -> Result<(), Err>
+	where
+		Act: FnMut(&'static AsyncMutex<StateMachineOrchestrator>) -> Fut,
+		Fut: Future<Output = Result<(), Err>>, {
+		if desired_states.is_empty() {
+			return Ok(());
+		}
+
+		// Wait until we're in one of the desired states
+		if !desired_states.contains(&self.current_state.get().await) {
+			self.current_state.changed_and(|state| desired_states.contains(state)).await;
+		}
 
-				match select(state_changed, action_finished).await {
-					Either::First(_) => {
-						break;
+		// We're now in a desired state, run the action until the state changes
+		loop {
+			// Race between state change or action completion
+			let state_changed = self.current_state.changed();
+			let action_finished = action(self.orchestrator);
+
+			match select(state_changed, action_finished).await {
+				Either::First(state) => {
+					if !desired_states.contains(&state) {
+						return Ok(());
 					}
-					Either::Second(_) => {
-						let current_state = self.current_state.get().await;
-						if !desired_states.contains(&current_state) {
-							// debug!("State changed while action was running, stopping action.");
-							break;
-						}
+				}
+				Either::Second(_) => {
+					let current_state = self.current_state.get().await;
+					if !desired_states.contains(&current_state) {
+						// debug!("State changed while action was running, stopping action.");
+						return Ok(());
 					}
 				}
 			}
@@ -0,0 +1,51 @@
+// Standard Wheatstone bridge equations for strain gauges, see https://www.ni.com/docs/en-US/bundle/ni-daqmx/page/strainequations.html
+// The voltage ratio is the bridge output divided by the excitation voltage. The bridge is wired so tension gives a positive output,
+// which is the opposite sign of the NI convention, so the equations below have the voltage ratio negated.
+// Lead resistance desensitizes the bridge by Rl/Rg for the 3-wire quarter and half bridges; the full bridge isn't affected.
+
+// One active gauge. The output is non-linear in the strain, which the (1 - 2Vr) term corrects for
+pub fn convert_voltage_ratio_to_strain_quarter_bridge(
+	voltage_ratio: f64,
+	gauge_factor: f64,
+	lead_resistance_ratio: f64,
+) -> f64 {
+	4.0 * voltage_ratio / (gauge_factor * (1.0 - 2.0 * voltage_ratio)) * (1.0 + lead_resistance_ratio)
+}
+
+// Two active gauges, one aligned with the strain and one transverse to it measuring the Poisson effect
+pub fn convert_voltage_ratio_to_strain_half_bridge_poisson(
+	voltage_ratio: f64,
+	gauge_factor: f64,
+	poisson_ratio: f64,
+	lead_resistance_ratio: f64,
+) -> f64 {
+	let denominator = gauge_factor * ((1.0 + poisson_ratio) + 2.0 * voltage_ratio * (poisson_ratio - 1.0));
+	4.0 * voltage_ratio / denominator * (1.0 + lead_resistance_ratio)
+}
+
+// Two active gauges on opposite faces of a bending member, one in tension and one in compression
+pub fn convert_voltage_ratio_to_strain_half_bridge_bending(
+	voltage_ratio: f64,
+	gauge_factor: f64,
+	lead_resistance_ratio: f64,
+) -> f64 {
+	2.0 * voltage_ratio / gauge_factor * (1.0 + lead_resistance_ratio)
+}
+
+// Four active gauges, two in tension and two in compression
+pub fn convert_voltage_ratio_to_strain_full_bridge(
+	voltage_ratio: f64,
+	gauge_factor: f64,
+) -> f64 {
+	voltage_ratio / gauge_factor
+}
+
+// Voltage ratio of the bridge when the shunt resistor is connected across one gauge, with every arm at the nominal gauge resistance.
+// Shunting lowers the arm resistance, which reads as compression. The same output is simulated in quarter, half and full bridges.
+pub fn compute_shunt_voltage_ratio(
+	gauge_resistance: f64,
+	shunt_resistance: f64,
+) -> f64 {
+	let shunted_resistance = gauge_resistance * shunt_resistance / (gauge_resistance + shunt_resistance);
+	shunted_resistance / (shunted_resistance + gauge_resistance) - 0.5
+}
@@ -0,0 +1,159 @@
+use heapless::{format, String};
+
+use crate::adc::types::AdcDevice;
+use crate::calibration_model::types::{CalibrationModel, PreviousModel};
+use crate::sensor::calibration::{calibrate_adc, list_calibrations, restore_calibration, select_calibrated_channel};
+use crate::sensor::types::CalibratedSensorService;
+use crate::strain::config::{BRIDGE_CONFIGURATIONS, SHUNT_CALIBRATION_READING_COUNT};
+use crate::strain::service::StrainService;
+use crate::strain::types::{StrainChannel, StrainServiceError};
+use crate::tare::service::{tare_all_channels, tare_channel};
+
+// Calibration logic has been separated into its own file for clarity
+impl<const ADC_COUNT: usize> StrainService<ADC_COUNT> {
+	/// Shunt calibration: a known resistor across one gauge simulates a known strain, and the ratio of the simulated strain
+	/// to the measured change corrects the span of the channel. The offset of a previous linear calibration is kept, the zero is set with a tare.
+	pub async fn calibrate(&mut self) -> Result<(), StrainServiceError> {
+		let Some((adc, channel)) = select_calibrated_channel(self).await? else {
+			return Ok(());
+		};
+
+		// Prompt for operation
+		let operation: u8 = self
+			.prompt("Enter operation (0 = Shunt calibrate channel, 1 = Tare channel, 2 = Tare all channels, 3 = History, 4 = Restore calibration):\n")
+			.await?;
+		match operation {
+			0 => {}
+			1 => {
+				let offset = tare_channel(self, adc, channel).await?;
+				let message: String<64> =
+					format!("Tare complete. Offset: {:.2} {}\n", offset, Self::UNIT).map_err(|_| StrainServiceError::FormatError)?;
+				self.send_message(message.as_str()).await?;
+				return Ok(());
+			}
+			2 => {
+				tare_all_channels(self).await;
+				self.send_message("Tare complete.\n").await?;
+				return Ok(());
+			}
+			3 => return list_calibrations(self, adc, channel).await,
+			4 => return restore_calibration(self, adc, channel).await,
+			_ => {
+				self.send_message("Invalid operation.\n").await?;
+				return Ok(());
+			}
+		}
+
+		// Optionally calibrate the ADC offset and gain before the shunt calibration
+		if !calibrate_adc(self, adc, channel.to_analog_input_channel_pair()).await? {
+			return Ok(());
+		}
+
+		// Prompt for shunt resistor
+		let shunt_resistance: f64 = self.prompt("Enter shunt resistor value in ohms:\n").await?;
+		if shunt_resistance <= 0.0 {
+			self.send_message("Shunt resistance must be positive.\n").await?;
+			return Ok(());
+		}
+
+		// Deregister any existing transformation for this channel so the bridge equations alone are measured
+		// It is registered again unless the new calibration is saved, so a cancelled run leaves the channel as it was
+		self.deregister_calibration(adc, channel);
+		let result = self.shunt_calibrate(adc, channel, shunt_resistance).await;
+		match result {
+			Ok(true) => self.previous_calibration = None,
+			_ => self.reregister_calibration(),
+		}
+		result.map(|_| ())
+	}
+
+	/// The shunt only measures the span, the offset of the deregistered calibration is kept if it was a linear one.
+	pub fn previous_offset(&self) -> f64 {
+		match self.previous_calibration {
+			Some(PreviousModel {
+				model: Some(CalibrationModel::Linear { offset, .. }),
+				..
+			}) => offset,
+			_ => 0.0,
+		}
+	}
+
+	// Measures the change the shunt resistor causes and saves the span correction. Returns whether the calibration was saved.
+	async fn shunt_calibrate(
+		&mut self,
+		adc: AdcDevice,
+		channel: StrainChannel,
+		shunt_resistance: f64,
+	) -> Result<bool, StrainServiceError> {
+		let _: String<256> = self.prompt("Disconnect the shunt resistor and press enter:\n").await?;
+		let unshunted_strain = self.measure_average_strain(adc, channel).await?;
+
+		let _: String<256> = self
+			.prompt("Connect the shunt resistor across the active gauge and press enter:\n")
+			.await?;
+		let shunted_strain = self.measure_average_strain(adc, channel).await?;
+
+		let bridge_configuration = BRIDGE_CONFIGURATIONS[adc as usize][channel as usize];
+		let expected_change = bridge_configuration.compute_shunt_microstrain(shunt_resistance);
+		let measured_change = shunted_strain - unshunted_strain;
+		{
+			let message: String<128> = format!(
+				"Expected change = {:.2} ue, Measured change = {:.2} ue\n",
+				expected_change, measured_change
+			)
+			.map_err(|_| StrainServiceError::FormatError)?;
+			self.send_message(message.as_str()).await?;
+		}
+		if measured_change.abs() < 1.0 {
+			self.send_message("No change measured, check the shunt resistor connection.\n").await?;
+			return Ok(false);
+		}
+
+		// The sign of the change depends on which bridge arm the gauge sits in, only the magnitude corrects the span
+		let scale = expected_change.abs() / measured_change.abs();
+		let result_message: String<128> = format!(
+			"Shunt calibration complete. Span correction: {:.6}, Effective gauge factor: {:.4}\n",
+			scale,
+			bridge_configuration.gauge_factor / scale
+		)
+		.map_err(|_| StrainServiceError::FormatError)?;
+		self.send_message(result_message.as_str()).await?;
+
+		// The shunt only measures the span, the offset of a previous linear calibration is kept
+		let offset = self.previous_offset();
+
+		// Update calibration for the channel and record it in the calibration history
+		// The shunt is the only data point, a single point doesn't define an R² so there is no quality check
+		// Its strains are plain averages rather than stable windows, so no uncertainty is recorded for it
+		let notes: String<256> = self.prompt("Enter calibration notes (optional):\n").await?;
+		let local_session = self.session_service.lock().await.current_session;
+		let record = self
+			.calibration_model_service
+			.save_calibration(
+				adc,
+				channel,
+				CalibrationModel::Linear { scale, offset },
+				&[(measured_change.abs(), expected_change.abs())],
+				&[],
+				local_session,
+				notes.as_str(),
+			)
+			.await?;
+		let message: String<64> = format!("Calibration #{} saved.\n", record.id).map_err(|_| StrainServiceError::FormatError)?;
+		self.send_message(message.as_str()).await?;
+		Ok(true)
+	}
+
+	// Averages several readings to keep the noise out of the span correction
+	async fn measure_average_strain(
+		&mut self,
+		adc: AdcDevice,
+		channel: StrainChannel,
+	) -> Result<f64, StrainServiceError> {
+		let mut sum = 0.0;
+		for _ in 0..SHUNT_CALIBRATION_READING_COUNT {
+			sum += self.read_strain(adc, channel).await?.strain;
+		}
+		Ok(sum / SHUNT_CALIBRATION_READING_COUNT as f64)
+	}
+}
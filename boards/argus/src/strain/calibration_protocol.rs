@@ -0,0 +1,43 @@
+use libm::sqrt;
+use uor_utils::messages::argus::calibration::calibration_response::{CalibrationErrorCode, CalibrationResponse};
+
+use crate::calibration_model::types::{CalibrationModel, LowQualityFitAction};
+use crate::calibration_protocol::handler::active_calibration_session;
+use crate::calibration_protocol::session::SessionError;
+use crate::strain::config::BRIDGE_CONFIGURATIONS;
+use crate::strain::service::StrainService;
+use crate::strain::types::StrainServiceError;
+
+// Only shunt calibrations are supported, the strain channels have no reference to compare other models against
+// The unshunted point is captured with a reference value of 0 and the shunted point with the shunt resistance as its reference value
+// The other commands are shared by every service, see calibration_protocol::handler
+impl<const ADC_COUNT: usize> StrainService<ADC_COUNT> {
+	/// Same fit as the text prompts, the latest unshunted and shunted points give the measured change of the shunt.
+	pub fn compute_shunt_calibration_fit(&mut self) -> Result<CalibrationResponse, StrainServiceError> {
+		let offset = self.previous_offset();
+		let session = active_calibration_session(self)?;
+		let unshunted_point = session.data_points.iter().rev().find(|data_point| data_point.expected == 0.0);
+		let shunted_point = session.data_points.iter().rev().find(|data_point| data_point.expected > 0.0);
+		let (Some(unshunted_point), Some(shunted_point)) = (unshunted_point, shunted_point) else {
+			return Err(SessionError::new(CalibrationErrorCode::NotEnoughPoints, "Capture an unshunted and a shunted point").into());
+		};
+
+		let bridge_configuration = BRIDGE_CONFIGURATIONS[session.adc as usize][session.channel as usize];
+		let expected_change = bridge_configuration.compute_shunt_microstrain(shunted_point.expected);
+		let measured_change = shunted_point.measured - unshunted_point.measured;
+		if measured_change.abs() < 1.0 {
+			return Err(SessionError::new(CalibrationErrorCode::NotEnoughPoints, "No change measured, check the shunt resistor connection").into());
+		}
+
+		// The sign of the change depends on which bridge arm the gauge sits in, only the magnitude corrects the span
+		// The shunt is the only data point, a single point doesn't define an R² so there is no quality check
+		let model = CalibrationModel::Linear {
+			scale: expected_change.abs() / measured_change.abs(),
+			offset,
+		};
+		let points = [(measured_change.abs(), expected_change.abs())];
+		// The change is the difference of two independent readings, so their standard errors add in quadrature
+		let uncertainty = sqrt(unshunted_point.uncertainty * unshunted_point.uncertainty + shunted_point.uncertainty * shunted_point.uncertainty);
+		Ok(session.set_fit(model, &points, &[uncertainty], 0.0, LowQualityFitAction::Warn))
+	}
+}
@@ -1,6 +1,75 @@
+use strum::EnumCount;
+
+use crate::adc::driver::types::SensorBiasMagnitude;
+use crate::adc::types::AdcDevice;
+use crate::calibration_model::types::StabilityCriteria;
+use crate::scan_sequencer::types::{scan_every_channel, ScanEntry};
+use crate::strain::types::{BridgeConfiguration, BridgeExcitation, BridgeType, StrainChannel};
+
 // Size of the queue used to send strain readings from the strain service to the SD card service
 pub const STRAIN_READING_QUEUE_SIZE: usize = 16;
 
-// File name used to read/write linear transformations that applied to strain readings to/from the SD card
-// Linear transformations are stored in CSV format
-pub const LINEAR_TRANSFORMATIONS_FILE_NAME: &str = "t_strain.csv"; // Cannot be longer than 12 characters
+// File name used to read/write the calibration models applied to strain readings to/from the SD card
+// Calibration models are stored in CSV format, they correct the computed microstrain rather than the bridge voltage.
+// The models of t_strain.csv were fitted against the bridge voltage in millivolts, so they are left on the card but never loaded
+pub const CALIBRATION_MODELS_FILE_NAME: &str = "t_ustrn.csv"; // Cannot be longer than 12 characters
+
+// File name used to read/write the history of the calibrations saved for the strain channels to/from the SD card
+// Each calibration is kept with its fit quality, data points and operator notes, so previous calibrations can be listed and restored.
+// Like the models, the millivolt calibrations of h_strain.csv are left out so they can't be restored onto microstrain readings
+pub const CALIBRATION_HISTORY_FILE_NAME: &str = "h_ustrn.csv"; // Cannot be longer than 12 characters
+
+// File name used to read the number of conversions averaged into each strain reading from the SD card
+// Oversampling configurations are stored in CSV format, channels without one are read with a single conversion
+pub const OVERSAMPLING_FILE_NAME: &str = "o_strain.csv"; // Cannot be longer than 12 characters
+
+// File name used to read/write the zero offsets subtracted from the calibrated strain readings to/from the SD card
+// Tare offsets are stored in CSV format with the session they were taken in, a copy of the offsets in use is written to each session directory.
+pub const TARE_FILE_NAME: &str = "z_strain.csv"; // Cannot be longer than 12 characters
+
+// Number of readings averaged into the offset of a tare
+pub const TARE_READING_COUNT: usize = 20;
+
+// Channels tared automatically each time recording starts, indexed by ADC and then channel
+// Only enable this for strain gauges that are guaranteed to be unloaded when recording starts
+pub const AUTO_TARE_CHANNELS: [[bool; StrainChannel::COUNT]; AdcDevice::COUNT] = [[false; StrainChannel::COUNT]; AdcDevice::COUNT];
+
+// Bridge used by most of the strain channels: a 350 ohm quarter bridge excited from the analog supply of the ADC.
+// Boards whose bridges have their own excitation set BridgeExcitation::Fixed with its voltage instead
+const DEFAULT_BRIDGE_CONFIGURATION: BridgeConfiguration = BridgeConfiguration {
+	bridge_type: BridgeType::Quarter,
+	gauge_factor: 2.0,
+	excitation: BridgeExcitation::AnalogSupply,
+	poisson_ratio: 0.3,
+	gauge_resistance: 350.0,
+	lead_resistance: 0.0,
+};
+
+// Bridge wired to each strain channel, indexed by ADC and then channel
+pub const BRIDGE_CONFIGURATIONS: [[BridgeConfiguration; StrainChannel::COUNT]; AdcDevice::COUNT] =
+	[[DEFAULT_BRIDGE_CONFIGURATION; StrainChannel::COUNT]; AdcDevice::COUNT];
+
+// Number of readings averaged into each of the shunted and unshunted measurements of a shunt calibration
+pub const SHUNT_CALIBRATION_READING_COUNT: usize = 10;
+
+// When the readings of a calibration protocol point are stable. Channels are read without calibration, so the limits are in microstrain
+pub const STABILITY_CRITERIA: StabilityCriteria = StabilityCriteria {
+	max_standard_deviation: 2.0,
+	max_drift: 2.0,
+	reading_interval: 100,
+	timeout: 30000,
+};
+
+// How often each channel is checked for a disconnected strain gauge
+pub const OPEN_CIRCUIT_CHECK_INTERVAL: u64 = 10000; // milliseconds
+
+// Sensor bias current used for the open-circuit check. Only a small offset develops across the bridge resistance when connected
+pub const OPEN_CIRCUIT_BIAS_MAGNITUDE: SensorBiasMagnitude = SensorBiasMagnitude::Current2uA;
+
+// Which strain channels are sampled, how often, and in what order, see the scan sequencer. Every channel is sampled as fast as the ADCs allow
+pub const STRAIN_SCAN_LIST: [ScanEntry<StrainChannel>; AdcDevice::COUNT * StrainChannel::COUNT] = scan_every_channel([
+	StrainChannel::Channel1,
+	StrainChannel::Channel2,
+	StrainChannel::Channel3,
+	StrainChannel::Channel4,
+]);
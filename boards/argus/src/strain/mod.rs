@@ -1,3 +1,6 @@
+pub mod bridge;
+pub mod calibration;
+pub mod calibration_protocol;
 pub mod config;
 pub mod service;
 pub mod tasks;
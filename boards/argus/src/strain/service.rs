@@ -1,35 +1,72 @@
-use defmt::error;
-use embassy_time::{Instant, Timer};
+use defmt::{error, info};
+use embassy_time::Instant;
 use strum::EnumCount;
 use uor_peripherals::serial::peripheral::UORSerial;
+use uor_utils::messages::argus::calibration::calibration_model::CalibrationModel as CalibrationModelProtobuf;
+use uor_utils::messages::argus::calibration::calibration_response::CalibrationResponse;
+use uor_utils::messages::argus::envelope::envelope::Message as EnvelopeMessage;
 use uor_utils::utils::types::AsyncMutex;
 
-use crate::adc::driver::types::{DataRate, Filter, Gain, ReferenceRange};
 use crate::adc::service::AdcService;
 use crate::adc::types::AdcDevice;
-use crate::linear_transformation::service::LinearTransformationService;
+use crate::board_health::config::ANALOG_SUPPLY_NOMINAL_VOLTAGE;
+use crate::calibration_model::service::CalibrationModelService;
+use crate::calibration_model::types::{LowQualityFitAction, PreviousModel, StabilityCriteria};
+use crate::calibration_protocol::session::CalibrationSession;
+use crate::oversampling::service::OversamplingService;
+use crate::oversampling::types::SampleStatistics;
+use crate::scan_sequencer::service::ScanSequencer;
 use crate::sd::service::SDCardService;
+use crate::sensor::acquisition::{read_channel, OpenCircuitMonitor};
+use crate::sensor::setup::configure_adcs;
+use crate::sensor::types::{CalibratedSensorService, ChannelKind};
 use crate::session::service::SessionService;
-use crate::strain::config::LINEAR_TRANSFORMATIONS_FILE_NAME;
-use crate::strain::types::{StrainChannel, StrainReading, StrainReadingQueue, StrainServiceError};
+use crate::strain::config::{
+	AUTO_TARE_CHANNELS, BRIDGE_CONFIGURATIONS, CALIBRATION_HISTORY_FILE_NAME, CALIBRATION_MODELS_FILE_NAME, OPEN_CIRCUIT_BIAS_MAGNITUDE,
+	OPEN_CIRCUIT_CHECK_INTERVAL, OVERSAMPLING_FILE_NAME, STABILITY_CRITERIA, STRAIN_SCAN_LIST, TARE_FILE_NAME, TARE_READING_COUNT,
+};
+use crate::strain::types::{BridgeExcitation, StrainChannel, StrainReading, StrainReadingQueue, StrainServiceError};
+use crate::tare::service::TareService;
+use crate::tare::types::TaredSensorService;
 
 // A channel for buffering the strain readings and decoupling the logging to sd task from the measurement task
 pub static STRAIN_READING_QUEUE: StrainReadingQueue = StrainReadingQueue::new();
 
 pub struct StrainService<const ADC_COUNT: usize> {
 	// Other services are passed by a mutex to ensure safe concurrent access
-	pub adc_service: &'static AsyncMutex<AdcService<ADC_COUNT>>,
+	pub adc_service: &'static AdcService<ADC_COUNT>,
 	pub sd_card_service: &'static AsyncMutex<SDCardService>,
 	pub serial_service: &'static AsyncMutex<UORSerial>,
 	pub session_service: &'static AsyncMutex<SessionService>,
 
-	// Linear transformations that are applied on top of the raw readings for each ADC and channel
-	pub linear_transformation_service: LinearTransformationService<StrainChannel, f64, ADC_COUNT, { StrainChannel::COUNT }>,
+	// Checks the channels for a disconnected sensor before they are read
+	pub open_circuit_monitor: OpenCircuitMonitor<ADC_COUNT, { StrainChannel::COUNT }>,
+
+	// Analog supply voltage of each ADC in volts, measured at setup for the bridges excited from it, see BridgeExcitation
+	pub analog_supply_voltages: [Option<f64>; ADC_COUNT],
+
+	// Calibration models that are applied on top of the raw readings for each ADC and channel
+	pub calibration_model_service: CalibrationModelService<StrainChannel, f64, ADC_COUNT, { StrainChannel::COUNT }>,
+
+	// Zero offsets subtracted from the calibrated readings of each ADC and channel
+	pub tare_service: TareService<StrainChannel, ADC_COUNT, { StrainChannel::COUNT }>,
+
+	// Number of conversions averaged into the readings of each ADC and channel
+	pub oversampling_service: OversamplingService<StrainChannel, ADC_COUNT, { StrainChannel::COUNT }>,
+
+	// Decides which channels the measurement task reads and when
+	pub scan_sequencer: ScanSequencer<StrainChannel, ADC_COUNT, { STRAIN_SCAN_LIST.len() }>,
+
+	// Calibration driven by the ground application over the calibration protocol, None outside of a session
+	pub calibration_session: Option<CalibrationSession<StrainChannel>>,
+
+	// Calibration model of the channel being calibrated, registered again unless the new calibration is saved
+	pub previous_calibration: Option<PreviousModel<StrainChannel, f64>>,
 }
 
 impl<const ADC_COUNT: usize> StrainService<ADC_COUNT> {
 	pub fn new(
-		adc_service: &'static AsyncMutex<AdcService<ADC_COUNT>>,
+		adc_service: &'static AdcService<ADC_COUNT>,
 		sd_card_service: &'static AsyncMutex<SDCardService>,
 		serial_service: &'static AsyncMutex<UORSerial>,
 		session_service: &'static AsyncMutex<SessionService>,
@@ -39,28 +76,52 @@ impl<const ADC_COUNT: usize> StrainService<ADC_COUNT> {
 			sd_card_service,
 			serial_service,
 			session_service,
-			linear_transformation_service: LinearTransformationService::new(sd_card_service, LINEAR_TRANSFORMATIONS_FILE_NAME),
+			open_circuit_monitor: OpenCircuitMonitor::new(OPEN_CIRCUIT_CHECK_INTERVAL, OPEN_CIRCUIT_BIAS_MAGNITUDE),
+			analog_supply_voltages: [None; ADC_COUNT],
+			calibration_model_service: CalibrationModelService::new(
+				sd_card_service,
+				CALIBRATION_MODELS_FILE_NAME,
+				CALIBRATION_HISTORY_FILE_NAME,
+			),
+			tare_service: TareService::new(sd_card_service, session_service, TARE_FILE_NAME, AUTO_TARE_CHANNELS),
+			oversampling_service: OversamplingService::new(sd_card_service, OVERSAMPLING_FILE_NAME),
+			scan_sequencer: ScanSequencer::new(STRAIN_SCAN_LIST),
+			calibration_session: None,
+			previous_calibration: None,
 		}
 	}
 
+	/// Configures the ADCs wired to its kind and loads the files of the service, see sensor::setup::prepare_adcs for the steps run before.
 	pub async fn setup(&mut self) -> Result<(), StrainServiceError> {
-		// Delay for 100ms to ensure ADCs are powered up
-		Timer::after_millis(100).await;
-
-		for driver in self.adc_service.lock().await.drivers.iter_mut() {
-			driver.reference_range = ReferenceRange::Avdd;
-			driver.data_rate = DataRate::Sps100;
-			driver.filter = Filter::Sinc3;
-			driver.enable_internal_reference = true;
-			driver.gain = Gain::G32;
-			driver.delay_after_setting_channel = 50; // 50 ms delay to allow the ADC to stabilize after switching channels
-			driver.apply_configurations().await?;
+		configure_adcs(self.adc_service, ChannelKind::Strain, |driver| {
+			driver.enable_input_chop = true; // Cancels the offset drift seen on long strain tests at the cost of half the data rate
+			Ok(())
+		})
+		.await;
+		self.measure_analog_supply_voltages().await;
+
+		match self.calibration_model_service.load_models().await {
+			Err(e) => error!("Failed to load calibration models: {:?}", e),
+			_ => {}
 		}
 
-		match self.linear_transformation_service.load_transformations().await {
-			Err(e) => error!("Failed to load linear transformations: {:?}", e),
+		match self.oversampling_service.load_configurations().await {
+			Err(e) => error!("Failed to load oversampling configurations: {:?}", e),
 			_ => {}
 		}
+
+		match self.tare_service.load_offsets().await {
+			Err(e) => error!("Failed to load tare offsets: {:?}", e),
+			_ => {}
+		}
+
+		// Record the offsets in use alongside the session data. Skipped if the session couldn't be started
+		if self.session_service.lock().await.current_session.is_some() {
+			match self.tare_service.save_session_offsets().await {
+				Err(e) => error!("Failed to record tare offsets in the session: {:?}", e),
+				_ => {}
+			}
+		}
 		Ok(())
 	}
 
@@ -69,28 +130,218 @@ impl<const ADC_COUNT: usize> StrainService<ADC_COUNT> {
 		adc: AdcDevice,
 		channel: StrainChannel,
 	) -> Result<StrainReading, StrainServiceError> {
-		let mut adc_service = self.adc_service.lock().await;
+		let mut adcs = [false; ADC_COUNT];
+		adcs[adc as usize] = true;
+
+		let mut readings = self.read_strains(channel, adcs).await;
+		readings[adc as usize].take().unwrap_or(Err(StrainServiceError::AdcUnavailable))
+	}
 
-		// Get the respective "adc channel" pair for the "strain channel"
-		let (positive_channel, negative_channel) = channel.to_analog_input_channel_pair();
+	/// Reads the strain channel on the given ADCs at the same instant, indexed by ADC.
+	/// ADCs that aren't given, that are wired to another kind of sensor, or that are missing or faulted, return None.
+	/// See sensor::acquisition::read_channel for how disconnected sensors are rejected and the conversions are synchronized.
+	pub async fn read_strains(
+		&mut self,
+		channel: StrainChannel,
+		adcs: [bool; ADC_COUNT],
+	) -> [Option<Result<StrainReading, StrainServiceError>>; ADC_COUNT] {
+		let oversampling = core::array::from_fn(|adc_index| self.oversampling_service.get_oversampling(AdcDevice::from(adc_index), channel));
+		let statistics: [Option<Result<SampleStatistics, StrainServiceError>>; ADC_COUNT] = read_channel(
+			self.adc_service,
+			&mut self.open_circuit_monitor,
+			ChannelKind::Strain,
+			channel as usize,
+			channel.to_analog_input_channel_pair(),
+			adcs,
+			oversampling,
+		)
+		.await;
+		let local_session = self.session_service.lock().await.current_session.clone();
 
-		// Read the voltage from the ADC in millivolts
-		let voltage = adc_service.drivers[adc as usize]
-			.read_differential(positive_channel, negative_channel)
-			.await? * 1000.0; // Convert to millivolts
+		let mut statistics = statistics.into_iter();
+		core::array::from_fn(|adc_index| {
+			let statistics = statistics.next().unwrap()?;
+			Some(statistics.map(|statistics| self.build_strain_reading(AdcDevice::from(adc_index), channel, statistics, local_session)))
+		})
+	}
+
+	// Converts the averaged bridge voltage (in volts) to a strain reading
+	fn build_strain_reading(
+		&self,
+		adc: AdcDevice,
+		channel: StrainChannel,
+		statistics: SampleStatistics,
+		local_session: Option<i32>,
+	) -> StrainReading {
+		let statistics = statistics.scale(1000.0); // Convert to millivolts
+		let voltage = statistics.mean;
 
-		// Apply any linear transformations to get the strain in psi
-		let strain = self.linear_transformation_service.apply_transformation(adc, channel, voltage as f64);
+		// Convert the bridge output to microstrain, then apply any calibration model and remove the tare offset on top
+		let bridge_configuration = BRIDGE_CONFIGURATIONS[adc as usize][channel as usize];
+		let excitation_voltage = self.excitation_voltage(adc, bridge_configuration.excitation);
+		let microstrain = bridge_configuration.convert_voltage_to_microstrain(voltage as f64, excitation_voltage);
+		let strain = self.calibration_model_service.apply_model(adc, channel, microstrain);
+		let strain = self.tare_service.apply_tare(adc, channel, strain);
 
-		let strain_reading = StrainReading {
-			local_session: self.session_service.lock().await.current_session.clone(),
+		StrainReading {
+			local_session,
 			adc_device: adc,
 			strain_channel: channel,
 			recorded_at: Instant::now().as_millis(),
 			voltage,
 			strain,
-		};
+			sample_count: statistics.sample_count,
+			voltage_standard_deviation: statistics.standard_deviation,
+			voltage_min: statistics.min,
+			voltage_max: statistics.max,
+		}
+	}
 
-		Ok(strain_reading)
+	/// Measures the analog supply of every ADC wired to strain gauges, which excites the bridges set to BridgeExcitation::AnalogSupply.
+	/// The supply is regulated, so it is only measured at setup. ADCs whose supply can't be measured fall back to its nominal voltage.
+	pub async fn measure_analog_supply_voltages(&mut self) {
+		for adc_index in 0..ADC_COUNT {
+			let adc = AdcDevice::from(adc_index);
+			if !self.adc_service.is_available_for(adc, ChannelKind::Strain).await {
+				continue;
+			}
+
+			match self.adc_service.drivers[adc_index].lock().await.read_analog_supply_voltage().await {
+				Ok(voltage) => {
+					info!("Bridge excitation of {:?}: {}V", adc, voltage);
+					self.analog_supply_voltages[adc_index] = Some(voltage as f64);
+				}
+				Err(e) => error!("Failed to measure the bridge excitation of {:?}: {:?}", adc, e),
+			}
+		}
+	}
+
+	// Voltage across the excitation terminals of a bridge on the ADC, in volts
+	fn excitation_voltage(
+		&self,
+		adc: AdcDevice,
+		excitation: BridgeExcitation,
+	) -> f64 {
+		match excitation {
+			BridgeExcitation::AnalogSupply => self.analog_supply_voltages[adc as usize].unwrap_or(ANALOG_SUPPLY_NOMINAL_VOLTAGE as f64),
+			BridgeExcitation::Fixed(voltage) => voltage,
+		}
+	}
+}
+
+impl<const ADC_COUNT: usize> TaredSensorService<ADC_COUNT, { StrainChannel::COUNT }> for StrainService<ADC_COUNT> {
+	type Channel = StrainChannel;
+	type Error = StrainServiceError;
+
+	const CHANNEL_KIND: ChannelKind = ChannelKind::Strain;
+	const TARE_READING_COUNT: usize = TARE_READING_COUNT;
+
+	fn adc_service(&self) -> &'static AdcService<ADC_COUNT> {
+		self.adc_service
+	}
+
+	fn tare_service(&mut self) -> &mut TareService<StrainChannel, ADC_COUNT, { StrainChannel::COUNT }> {
+		&mut self.tare_service
+	}
+
+	async fn read_tared_value(
+		&mut self,
+		adc: AdcDevice,
+		channel: StrainChannel,
+	) -> Result<f64, StrainServiceError> {
+		Ok(self.read_strain(adc, channel).await?.strain)
+	}
+}
+
+impl<const ADC_COUNT: usize> CalibratedSensorService<ADC_COUNT, { StrainChannel::COUNT }> for StrainService<ADC_COUNT> {
+	type Channel = StrainChannel;
+	type Error = StrainServiceError;
+	type Reading = StrainReading;
+
+	const CHANNEL_KIND: ChannelKind = ChannelKind::Strain;
+	// A shunt calibration fits a single point, which doesn't define an R², so no fit is refused
+	const LOW_QUALITY_FIT_ACTION: LowQualityFitAction = LowQualityFitAction::Warn;
+	const MIN_CALIBRATION_R_SQUARED: f64 = 0.0;
+	// Only shunt calibrations are supported, see strain::calibration_protocol
+	const PROTOCOL_MODELS: &'static [CalibrationModelProtobuf] = &[CalibrationModelProtobuf::Shunt];
+	const STABILITY_CRITERIA: StabilityCriteria = STABILITY_CRITERIA;
+	const UNIT: &'static str = "ue";
+
+	fn adc_service(&self) -> &'static AdcService<ADC_COUNT> {
+		self.adc_service
+	}
+
+	fn serial_service(&self) -> &'static AsyncMutex<UORSerial> {
+		self.serial_service
+	}
+
+	fn session_service(&self) -> &'static AsyncMutex<SessionService> {
+		self.session_service
+	}
+
+	fn calibration_model_service(&mut self) -> &mut CalibrationModelService<StrainChannel, f64, ADC_COUNT, { StrainChannel::COUNT }> {
+		&mut self.calibration_model_service
+	}
+
+	fn has_calibration_session(&self) -> bool {
+		self.calibration_session.is_some()
+	}
+
+	fn calibration_session(&mut self) -> &mut Option<CalibrationSession<StrainChannel>> {
+		&mut self.calibration_session
+	}
+
+	fn calibration_value(reading: &StrainReading) -> f64 {
+		reading.strain
+	}
+
+	// Only the calibration model is deregistered, so the bridge equations alone are measured
+	fn deregister_calibration(
+		&mut self,
+		adc: AdcDevice,
+		channel: StrainChannel,
+	) {
+		self.previous_calibration = Some(PreviousModel {
+			adc,
+			channel,
+			model: self.calibration_model_service.deregister_model(adc, channel),
+		});
+	}
+
+	fn reregister_calibration(&mut self) {
+		if let Some(PreviousModel { adc, channel, model: Some(model) }) = self.previous_calibration.take() {
+			self.calibration_model_service.register_model(adc, channel, model);
+		}
+	}
+
+	fn compute_calibration_fit(&mut self) -> Result<CalibrationResponse, StrainServiceError> {
+		self.compute_shunt_calibration_fit()
+	}
+
+	async fn read_calibration_reading(
+		&mut self,
+		adc: AdcDevice,
+		channel: StrainChannel,
+	) -> Result<StrainReading, StrainServiceError> {
+		self.read_strain(adc, channel).await
+	}
+
+	async fn send_calibration_response(
+		&mut self,
+		response: CalibrationResponse,
+	) -> Result<(), StrainServiceError> {
+		let mut serial_service = self.serial_service.lock().await;
+		serial_service.write_envelope_message(EnvelopeMessage::CalibrationResponse(response)).await?;
+		Ok(())
+	}
+
+	// The tare offset of the channel is kept, the shunt calibration only corrects the span and the zero is set with a tare
+	async fn replace_previous_calibration(
+		&mut self,
+		_adc: AdcDevice,
+		_channel: StrainChannel,
+	) -> Result<(), StrainServiceError> {
+		self.previous_calibration = None;
+		Ok(())
 	}
 }
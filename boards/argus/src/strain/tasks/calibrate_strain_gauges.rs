@@ -0,0 +1,66 @@
+use defmt::error;
+use embassy_executor::task;
+use embassy_futures::yield_now;
+use uor_utils::utils::types::AsyncMutex;
+
+use crate::adc::types::AdcDevice;
+#[cfg(feature = "calibration-protocol")]
+use crate::calibration_protocol::handler::handle_calibration_command;
+use crate::sensor::calibration::{end_calibration_turn, end_interrupted_calibration};
+#[cfg(feature = "calibration-protocol")]
+use crate::sensor::calibration::receive_calibration_command;
+#[cfg(not(feature = "calibration-protocol"))]
+use crate::sensor::calibration::take_calibration_turn;
+use crate::sensor::types::ChannelKind;
+use crate::state_machine::service::StateMachineWorker;
+use crate::state_machine::types::States;
+use crate::strain::service::StrainService;
+
+#[task]
+pub async fn calibrate_strain_gauges(
+	mut worker: StateMachineWorker,
+	strain_service_mutex: &'static AsyncMutex<StrainService<{ AdcDevice::COUNT }>>,
+) {
+	loop {
+		worker
+			.run_until_exit(&[States::Calibrating], async |_| -> Result<(), ()> {
+				// The calibrate tasks of each kind take turns on the serial port, see sensor::calibration
+				#[cfg(not(feature = "calibration-protocol"))]
+				{
+					let serial_service = strain_service_mutex.lock().await.serial_service;
+					match take_calibration_turn(ChannelKind::Strain, serial_service).await {
+						Err(e) => {
+							error!("Failed to select the sensors to calibrate: {:?}", e);
+							return Ok(());
+						}
+						_ => {}
+					}
+				}
+				#[cfg(feature = "calibration-protocol")]
+				let command = receive_calibration_command(ChannelKind::Strain).await;
+
+				let mut strain_service = strain_service_mutex.lock().await;
+				// With the calibration-protocol feature the calibration is driven by protobuf commands instead of the text prompts
+				#[cfg(not(feature = "calibration-protocol"))]
+				let result = strain_service.calibrate().await;
+				#[cfg(feature = "calibration-protocol")]
+				let result = handle_calibration_command(&mut *strain_service, command).await;
+
+				match result {
+					Ok(_) => {}
+					Err(e) => error!("Strain calibration failed: {:?}", e),
+				}
+				#[cfg(not(feature = "calibration-protocol"))]
+				end_calibration_turn().await;
+				yield_now().await;
+				Ok(())
+			})
+			.await
+			.unwrap();
+
+		// Leaving the state drops a calibration wherever it was waiting, so the calibration it put aside is registered again
+		// The turns of the kinds start over the next time the state is entered
+		end_interrupted_calibration(&mut *strain_service_mutex.lock().await);
+		end_calibration_turn().await;
+	}
+}
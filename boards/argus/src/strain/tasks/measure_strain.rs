@@ -1,17 +1,20 @@
 use defmt::{error, info};
 use embassy_executor::task;
 use embassy_futures::yield_now;
+use embassy_time::{Instant, Timer};
 use strum::EnumCount;
 use uor_utils::utils::types::AsyncMutex;
 
+use crate::adc::service::AdcError;
 use crate::adc::types::AdcDevice;
 use crate::led_indicator::service::LedIndicatorService;
 use crate::state_machine::service::StateMachineWorker;
 use crate::state_machine::types::States;
 use crate::strain::service::{StrainService, STRAIN_READING_QUEUE};
-use crate::strain::types::StrainChannel;
+use crate::strain::types::StrainServiceError;
+use crate::tare::config::AUTO_TARE_POLL_INTERVAL;
 
-// Task that iterates through the ADCs and channels, measures the strain, and enqueues the readings to a channel
+// Task that measures the strain channels of the scan list as they become due, and enqueues the readings to a channel
 #[task]
 pub async fn measure_strain(
 	mut worker: StateMachineWorker,
@@ -20,16 +23,39 @@ pub async fn measure_strain(
 ) {
 	worker
 		.run_while(&[States::Recording], async |_| -> Result<(), ()> {
-			for adc_index in 0..AdcDevice::COUNT {
-				for channel_index in 0..StrainChannel::COUNT {
+			// Wait for the next channel to be due without holding the service, so the other tasks can use it in the meantime
+			let next_due_at = strain_service_mutex.lock().await.scan_sequencer.next_due_at();
+			Timer::at(next_due_at).await;
+
+			// Hold the readings back until the tare of the recording has completed, see tare::service::tare_on_recording
+			if strain_service_mutex.lock().await.tare_service.auto_tare_pending {
+				Timer::after_millis(AUTO_TARE_POLL_INTERVAL).await;
+				return Ok(());
+			}
+			let scan_steps = strain_service_mutex.lock().await.scan_sequencer.take_due(Instant::now().as_millis());
+
+			for scan_step in scan_steps.iter() {
+				let channel = scan_step.channel;
+				let readings = strain_service_mutex.lock().await.read_strains(channel, scan_step.adcs).await;
+				for (adc_index, data) in readings.into_iter().enumerate() {
+					// ADCs the channel wasn't due on, or that are missing or faulted, are skipped
+					let Some(data) = data else {
+						continue;
+					};
 					let adc = AdcDevice::from(adc_index);
-					let channel = StrainChannel::from(channel_index);
-					let data = strain_service_mutex.lock().await.read_strain(adc, channel).await;
 					match data {
 						Ok(strain_reading) => {
 							info!("{}", strain_reading);
 							STRAIN_READING_QUEUE.send(strain_reading).await;
 						}
+						Err(StrainServiceError::OpenCircuit) => {
+							error!("Open circuit on ADC {} Channel {}. Is the strain gauge connected?", adc, channel);
+							continue;
+						}
+						Err(StrainServiceError::AdcError(AdcError::DataReadyTimeout)) => {
+							error!("Timed out waiting for data from ADC {} Channel {}. Is the ADC connected?", adc, channel);
+							continue;
+						}
 						Err(err) => {
 							error!("Error reading ADC {} Channel {}: {:?}", adc, channel, err);
 							continue;
@@ -38,8 +64,10 @@ pub async fn measure_strain(
 				}
 			}
 
-			// Blink LED to indicate measurement cycle complete
-			led_indicator_service_mutex.lock().await.blink(1).await;
+			// Blink LED once per pass over the scan list to indicate the measurements are running
+			if scan_steps.iter().any(|scan_step| scan_step.starts_cycle) {
+				led_indicator_service_mutex.lock().await.blink(1).await;
+			}
 
 			// Yield to allow other tasks to run, especially the NTC measurement task
 			yield_now().await;
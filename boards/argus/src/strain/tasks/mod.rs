@@ -1,5 +1,9 @@
+mod calibrate_strain_gauges;
 mod log_measurements;
 mod measure_strain;
+mod tare_on_recording;
 
+pub use calibrate_strain_gauges::*;
 pub use log_measurements::*;
 pub use measure_strain::*;
+pub use tare_on_recording::*;
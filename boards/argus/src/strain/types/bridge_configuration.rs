@@ -0,0 +1,89 @@
+use defmt::Format;
+
+use crate::strain::bridge;
+
+/// How the strain gauges are arranged in the Wheatstone bridge
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Format)]
+pub enum BridgeType {
+	Quarter,
+	HalfBending,
+	HalfPoisson,
+	Full,
+}
+
+/// Where the voltage across the bridge excitation terminals comes from
+#[derive(Debug, Clone, Copy, PartialEq, Format)]
+pub enum BridgeExcitation {
+	// Excited from the analog supply of the ADC, which is measured with its supply monitor
+	AnalogSupply,
+	// Excited from a separate source of a known voltage
+	Fixed(f64), // Volts
+}
+
+#[derive(Debug, Clone, Copy, Format)]
+pub struct BridgeConfiguration {
+	pub bridge_type: BridgeType,
+
+	// Gauge factor from the strain gauge datasheet
+	pub gauge_factor: f64,
+
+	// Source of the bridge excitation, the bridge output is divided by its voltage
+	pub excitation: BridgeExcitation,
+
+	// Poisson ratio of the material the gauges are bonded to, only used by the half-poisson bridge
+	pub poisson_ratio: f64,
+
+	// Nominal resistance of the strain gauge
+	pub gauge_resistance: f64, // Ohms
+
+	// Resistance of a single lead wire between the board and the gauge
+	pub lead_resistance: f64, // Ohms
+}
+
+impl BridgeConfiguration {
+	/// Converts the bridge output in millivolts to microstrain, given the voltage across the excitation terminals in volts
+	pub fn convert_voltage_to_microstrain(
+		&self,
+		voltage: f64,
+		excitation_voltage: f64,
+	) -> f64 {
+		self.convert_voltage_ratio_to_microstrain(voltage / 1000.0 / excitation_voltage)
+	}
+
+	// Converts the bridge output divided by the excitation voltage to microstrain
+	fn convert_voltage_ratio_to_microstrain(
+		&self,
+		voltage_ratio: f64,
+	) -> f64 {
+		let lead_resistance_ratio = self.lead_resistance / self.gauge_resistance;
+
+		let strain = match self.bridge_type {
+			BridgeType::Quarter => bridge::convert_voltage_ratio_to_strain_quarter_bridge(voltage_ratio, self.gauge_factor, lead_resistance_ratio),
+			BridgeType::HalfBending => {
+				bridge::convert_voltage_ratio_to_strain_half_bridge_bending(voltage_ratio, self.gauge_factor, lead_resistance_ratio)
+			}
+			BridgeType::HalfPoisson => bridge::convert_voltage_ratio_to_strain_half_bridge_poisson(
+				voltage_ratio,
+				self.gauge_factor,
+				self.poisson_ratio,
+				lead_resistance_ratio,
+			),
+			BridgeType::Full => bridge::convert_voltage_ratio_to_strain_full_bridge(voltage_ratio, self.gauge_factor),
+		};
+		strain * 1_000_000.0
+	}
+
+	/// Microstrain the channel should read when the shunt resistor is connected across one gauge.
+	/// The shunt simulates a strain of the gauge itself, so the ideal strain is computed without the lead resistance correction.
+	pub fn compute_shunt_microstrain(
+		&self,
+		shunt_resistance: f64,
+	) -> f64 {
+		let voltage_ratio = bridge::compute_shunt_voltage_ratio(self.gauge_resistance, shunt_resistance);
+		let ideal_bridge = BridgeConfiguration {
+			lead_resistance: 0.0,
+			..*self
+		};
+		ideal_bridge.convert_voltage_ratio_to_microstrain(voltage_ratio)
+	}
+}
@@ -3,11 +3,34 @@ use derive_more::From;
 use uor_peripherals::serial::peripheral::UsartError;
 
 use crate::adc::service::AdcError;
+use crate::calibration_protocol::session::SessionError;
 use crate::sd::types::SdCardError;
+use crate::sensor::types::SensorServiceError;
 
 #[derive(Debug, Format, From)]
 pub enum StrainServiceError {
 	AdcError(AdcError),
 	UsartError(UsartError),
 	SdCardError(SdCardError),
+	FormatError,
+	OpenCircuit, // The sensor was found to be disconnected during the last open-circuit check
+	AdcUnavailable, // The ADC is missing or faulted, see AdcService::detect_devices
+	CalibrationSessionError(SessionError), // The calibration command can't be carried out, it is answered with a CalibrationError
+}
+
+impl SensorServiceError for StrainServiceError {
+	fn format_error() -> Self {
+		StrainServiceError::FormatError
+	}
+
+	fn open_circuit() -> Self {
+		StrainServiceError::OpenCircuit
+	}
+
+	fn session_error(&self) -> Option<SessionError> {
+		match self {
+			StrainServiceError::CalibrationSessionError(error) => Some(*error),
+			_ => None,
+		}
+	}
 }
@@ -1,9 +1,13 @@
+pub mod bridge_configuration;
 pub mod error;
 pub mod queue;
+pub mod sensor_kind;
 pub mod strain_channel;
 pub mod strain_reading;
 
+pub use bridge_configuration::*;
 pub use error::*;
 pub use queue::*;
+pub use sensor_kind::*;
 pub use strain_channel::*;
 pub use strain_reading::*;
@@ -0,0 +1,30 @@
+use uor_utils::messages::argus::envelope::envelope::Message as EnvelopeMessage;
+
+use crate::adc::types::AdcDevice;
+use crate::sensor::types::{ChannelKind, SensorKind};
+use crate::strain::types::{StrainChannel, StrainReading};
+
+// Marker for the strain gauges in the shared sensor pipeline, see SensorKind
+pub struct StrainGauges;
+
+impl SensorKind for StrainGauges {
+	type Channel = StrainChannel;
+	type Reading = StrainReading;
+
+	const CHANNEL_KIND: ChannelKind = ChannelKind::Strain;
+	// Strain readings have always been logged with the thermocouple prefix, which the ground tooling and post-processing expect.
+	// A thermocouple and a strain file can't share a name, as each ADC is wired to a single kind and the ADC is part of the name
+	const FILE_PREFIX: &'static str = "T";
+
+	fn adc_device(reading: &StrainReading) -> AdcDevice {
+		reading.adc_device
+	}
+
+	fn channel_index(reading: &StrainReading) -> usize {
+		reading.strain_channel as usize
+	}
+
+	fn to_envelope_message(reading: &StrainReading) -> EnvelopeMessage {
+		EnvelopeMessage::StrainReading(reading.to_protobuf())
+	}
+}
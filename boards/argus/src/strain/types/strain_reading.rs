@@ -28,8 +28,20 @@ pub struct StrainReading {
 	// Voltage difference measured at the strain sensor wheatstone bridge in millivolts
 	pub voltage: f32,
 
-	// Strain reading
+	// Strain in microstrain, computed with the bridge equations of the channel
 	pub strain: f64,
+
+	// Number of conversions averaged into the voltage, after the outliers were rejected
+	pub sample_count: u8,
+
+	// Standard deviation of the averaged conversions in millivolts
+	pub voltage_standard_deviation: f32,
+
+	// Lowest of the averaged conversions in millivolts
+	pub voltage_min: f32,
+
+	// Highest of the averaged conversions in millivolts
+	pub voltage_max: f32,
 }
 
 impl SerializeCSV<MAX_LINE_LENGTH> for StrainReading {
@@ -40,7 +52,11 @@ impl SerializeCSV<MAX_LINE_LENGTH> for StrainReading {
 			Strain Channel,\
 			Timestamp (ms),\
 			Voltage (mV),\
-			Strain",
+			Strain (microstrain),\
+			Sample Count,\
+			Voltage Standard Deviation (mV),\
+			Voltage Min (mV),\
+			Voltage Max (mV)",
 		)
 		.unwrap()
 	}
@@ -56,6 +72,10 @@ impl StrainReading {
 			recorded_at: self.recorded_at,
 			voltage: self.voltage,
 			strain: self.strain,
+			sample_count: self.sample_count as u32,
+			voltage_standard_deviation: self.voltage_standard_deviation,
+			voltage_min: self.voltage_min,
+			voltage_max: self.voltage_max,
 		}
 	}
 }
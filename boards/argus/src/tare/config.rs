@@ -0,0 +1,3 @@
+// How often the measurement tasks check whether the tare of the recording has completed, in milliseconds
+// Readings are held back until then, so none of them is taken against the zero of the previous recording
+pub const AUTO_TARE_POLL_INTERVAL: u64 = 10;
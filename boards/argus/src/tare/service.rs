@@ -0,0 +1,323 @@
+use core::str::FromStr;
+
+use defmt::{error, info};
+use heapless::LinearMap;
+use uor_utils::csv::SerializeCSV;
+use uor_utils::utils::types::AsyncMutex;
+
+use crate::adc::types::AdcDevice;
+use crate::calibration_model::types::ChannelMarker;
+use crate::sd::service::SDCardService;
+use crate::sd::types::{FileName, OperationScope, SdCardError};
+use crate::session::service::SessionService;
+use crate::state_machine::service::StateMachineWorker;
+use crate::state_machine::types::States;
+use crate::tare::types::{TareOffset, TaredSensorService};
+
+pub struct TareService<Channel, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>
+where
+	Channel: ChannelMarker, {
+	pub sd_card_service: &'static AsyncMutex<SDCardService>,
+	pub session_service: &'static AsyncMutex<SessionService>,
+	pub file_name: &'static str,
+
+	// Offsets of each ADC and channel that has been tared, the others are left as calibrated
+	pub offsets: LinearMap<AdcDevice, LinearMap<Channel, TareOffset<Channel>, CHANNEL_COUNT>, ADC_COUNT>,
+
+	// Channels tared each time recording starts, indexed by ADC and then channel
+	pub auto_tare_channels: [[bool; CHANNEL_COUNT]; ADC_COUNT],
+
+	// Set until the tare of the next recording has completed, the measurement task holds its readings back in the meantime
+	pub auto_tare_pending: bool,
+}
+
+impl<Channel, const ADC_COUNT: usize, const CHANNEL_COUNT: usize> TareService<Channel, ADC_COUNT, CHANNEL_COUNT>
+where
+	Channel: ChannelMarker,
+{
+	pub fn new(
+		sd_card_service: &'static AsyncMutex<SDCardService>,
+		session_service: &'static AsyncMutex<SessionService>,
+		file_name: &'static str,
+		auto_tare_channels: [[bool; CHANNEL_COUNT]; ADC_COUNT],
+	) -> Self {
+		Self {
+			sd_card_service,
+			session_service,
+			file_name,
+			offsets: LinearMap::default(),
+			auto_tare_channels,
+			auto_tare_pending: has_selected_channels(&auto_tare_channels),
+		}
+	}
+
+	pub async fn load_offsets(&mut self) -> Result<(), SdCardError> {
+		let result = self
+			.sd_card_service
+			.lock()
+			.await
+			.read(OperationScope::Root, FileName::from_str(self.file_name).unwrap(), |line| {
+				if *line == TareOffset::<Channel>::get_csv_header() {
+					return true; // Skip header line
+				}
+
+				let result = TareOffset::<Channel>::from_csv_line(line);
+				match result {
+					Ok(offset) => {
+						self.register_offset(offset);
+						info!("Loaded tare offset: {:?}", offset);
+					}
+					Err(e) => {
+						error!("Error parsing tare offset for line '{}': {:?}", line.as_str(), e);
+					}
+				}
+				true // Continue reading
+			});
+
+		match result {
+			Ok(_) => (),
+			Err(SdCardError::NotFound) => {
+				// If no channel has been tared yet, keep the calibrated values as they are and ignore this error.
+				info!("Tare offsets file not found, using defaults. Offset = 0");
+			}
+			Err(e) => return Err(e),
+		}
+		Ok(())
+	}
+
+	pub fn register_offset(
+		&mut self,
+		offset: TareOffset<Channel>,
+	) {
+		if !self.offsets.contains_key(&offset.adc) {
+			let _ = self.offsets.insert(offset.adc, LinearMap::new());
+		}
+		let map = self.offsets.get_mut(&offset.adc).unwrap();
+		let _ = map.insert(offset.channel, offset);
+	}
+
+	pub fn get_offset(
+		&self,
+		adc: AdcDevice,
+		channel: Channel,
+	) -> f64 {
+		self.offsets
+			.get(&adc)
+			.and_then(|channel_map| channel_map.get(&channel))
+			.map(|offset| offset.offset)
+			.unwrap_or(0.0) // If the channel hasn't been tared, leave the calibrated value as is
+	}
+
+	pub fn apply_tare(
+		&self,
+		adc: AdcDevice,
+		channel: Channel,
+		calibrated_value: f64,
+	) -> f64 {
+		calibrated_value - self.get_offset(adc, channel)
+	}
+
+	/// Stops subtracting the offset of the channel until it is registered again or reloaded. Returns the removed offset.
+	pub fn deregister_offset(
+		&mut self,
+		adc: AdcDevice,
+		channel: Channel,
+	) -> Option<TareOffset<Channel>> {
+		self.offsets.get_mut(&adc).and_then(|channel_map| channel_map.remove(&channel))
+	}
+
+	/// Persists the offset to the SD card and records it in the current session.
+	pub async fn save_offset(
+		&mut self,
+		adc: AdcDevice,
+		channel: Channel,
+		offset: f64,
+	) -> Result<(), SdCardError> {
+		let offset = TareOffset {
+			adc,
+			channel,
+			offset,
+			local_session: self.session_service.lock().await.current_session,
+		};
+		info!("Saving tare offset: {:?}", offset);
+		{
+			let mut sd_card_service = self.sd_card_service.lock().await;
+			let path = FileName::from_str(self.file_name).unwrap();
+			if !(sd_card_service.file_exists(OperationScope::Root, path.clone())?) {
+				sd_card_service.write(OperationScope::Root, path.clone(), TareOffset::<Channel>::get_csv_header())?;
+			}
+
+			sd_card_service.write(OperationScope::Root, path.clone(), offset.to_csv_line())?;
+		}
+		self.register_offset(offset);
+
+		match self.save_session_offset(offset).await {
+			Err(e) => error!("Failed to record tare offset in the session: {:?}", e),
+			_ => {}
+		}
+		Ok(())
+	}
+
+	/// Clears the offset of the channel, e.g. after a new calibration that already includes its zero.
+	pub async fn reset_offset(
+		&mut self,
+		adc: AdcDevice,
+		channel: Channel,
+	) -> Result<(), SdCardError> {
+		self.save_offset(adc, channel, 0.0).await
+	}
+
+	/// Records every offset in use in the current session, so the data of a session can be traced back to its zero.
+	/// A session must be set before calling this.
+	pub async fn save_session_offsets(&self) -> Result<(), SdCardError> {
+		for channel_map in self.offsets.values() {
+			for offset in channel_map.values() {
+				self.save_session_offset(*offset).await?;
+			}
+		}
+		Ok(())
+	}
+
+	async fn save_session_offset(
+		&self,
+		offset: TareOffset<Channel>,
+	) -> Result<(), SdCardError> {
+		let mut sd_card_service = self.sd_card_service.lock().await;
+		let path = FileName::from_str(self.file_name).unwrap();
+		if !(sd_card_service.file_exists(OperationScope::CurrentSession, path.clone())?) {
+			sd_card_service.write(OperationScope::CurrentSession, path.clone(), TareOffset::<Channel>::get_csv_header())?;
+		}
+
+		sd_card_service.write(OperationScope::CurrentSession, path, offset.to_csv_line())?;
+		Ok(())
+	}
+}
+
+/// Averages the channel for TARE_READING_COUNT readings and stores the result as its zero offset. Returns the new offset.
+pub async fn tare_channel<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service,
+	adc: AdcDevice,
+	channel: Service::Channel,
+) -> Result<f64, Service::Error>
+where
+	Service: TaredSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	let mut sum = 0.0;
+	for _ in 0..Service::TARE_READING_COUNT {
+		sum += service.read_tared_value(adc, channel).await?;
+	}
+	save_average_as_offset(service, adc, channel, sum / Service::TARE_READING_COUNT as f64).await
+}
+
+/// Tares every channel of the ADCs wired to the kind of the service. Channels that fail to tare keep their previous offset.
+pub async fn tare_all_channels<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(service: &mut Service)
+where
+	Service: TaredSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	for adc_index in 0..ADC_COUNT {
+		let adc = AdcDevice::from(adc_index);
+		if !service.adc_service().is_available_for(adc, Service::CHANNEL_KIND).await {
+			continue;
+		}
+
+		for channel_index in 0..CHANNEL_COUNT {
+			let channel = Service::Channel::from(channel_index);
+			match tare_channel(service, adc, channel).await {
+				Err(e) => error!("Failed to tare {:?} {:?}: {:?}", adc, channel, e),
+				_ => {}
+			}
+		}
+	}
+}
+
+/// Tares the selected channels, indexed by ADC and then channel. Channels that fail to tare keep their previous offset.
+/// Unlike tare_channel, the service is only locked for each reading, so the other tasks can use it in between.
+pub async fn tare_channels<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service_mutex: &AsyncMutex<Service>,
+	channels: &[[bool; CHANNEL_COUNT]; ADC_COUNT],
+) where
+	Service: TaredSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	for (adc_index, adc_channels) in channels.iter().enumerate() {
+		let adc = AdcDevice::from(adc_index);
+		let adc_service = service_mutex.lock().await.adc_service();
+		if !adc_service.is_available_for(adc, Service::CHANNEL_KIND).await {
+			continue;
+		}
+
+		for (channel_index, _) in adc_channels.iter().enumerate().filter(|(_, selected)| **selected) {
+			let channel = Service::Channel::from(channel_index);
+			let result = match average_readings(service_mutex, adc, channel).await {
+				Ok(average) => save_average_as_offset(&mut *service_mutex.lock().await, adc, channel, average).await,
+				Err(e) => Err(e),
+			};
+			match result {
+				Err(e) => error!("Failed to tare {:?} {:?}: {:?}", adc, channel, e),
+				_ => {}
+			}
+		}
+	}
+}
+
+/// Tares the auto-tare channels of the service once each time recording starts, see TareService::auto_tare_channels.
+/// Embassy tasks can't be generic, each sensor service spawns a task that calls this with its service.
+pub async fn tare_on_recording<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	mut worker: StateMachineWorker,
+	service_mutex: &'static AsyncMutex<Service>,
+) where
+	Service: TaredSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	loop {
+		worker
+			.run_once(&[States::Recording], async |_| -> Result<(), ()> {
+				let auto_tare_channels = service_mutex.lock().await.tare_service().auto_tare_channels;
+				tare_channels(service_mutex, &auto_tare_channels).await;
+				service_mutex.lock().await.tare_service().auto_tare_pending = false;
+				Ok(())
+			})
+			.await
+			.unwrap();
+
+		// Wait for recording to stop before arming the next tare
+		worker
+			.run_once(&[States::Idle, States::Calibrating], async |_| -> Result<(), ()> {
+				let mut service = service_mutex.lock().await;
+				let tare_service = service.tare_service();
+				tare_service.auto_tare_pending = has_selected_channels(&tare_service.auto_tare_channels);
+				Ok(())
+			})
+			.await
+			.unwrap();
+	}
+}
+
+// Same average as tare_channel, but the service is locked for each reading only
+async fn average_readings<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service_mutex: &AsyncMutex<Service>,
+	adc: AdcDevice,
+	channel: Service::Channel,
+) -> Result<f64, Service::Error>
+where
+	Service: TaredSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	let mut sum = 0.0;
+	for _ in 0..Service::TARE_READING_COUNT {
+		sum += service_mutex.lock().await.read_tared_value(adc, channel).await?;
+	}
+	Ok(sum / Service::TARE_READING_COUNT as f64)
+}
+
+// Readings already have the previous offset subtracted, so it is added back to the average
+async fn save_average_as_offset<Service, const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(
+	service: &mut Service,
+	adc: AdcDevice,
+	channel: Service::Channel,
+	average: f64,
+) -> Result<f64, Service::Error>
+where
+	Service: TaredSensorService<ADC_COUNT, CHANNEL_COUNT>, {
+	let tare_service = service.tare_service();
+	let offset = tare_service.get_offset(adc, channel) + average;
+	tare_service.save_offset(adc, channel, offset).await?;
+	info!("Tared {:?} {:?}: offset = {}", adc, channel, offset);
+	Ok(offset)
+}
+
+fn has_selected_channels<const ADC_COUNT: usize, const CHANNEL_COUNT: usize>(channels: &[[bool; CHANNEL_COUNT]; ADC_COUNT]) -> bool {
+	channels.iter().flatten().any(|selected| *selected)
+}
@@ -0,0 +1,59 @@
+use core::future::Future;
+use core::str::FromStr;
+
+use defmt::Format;
+use serde::{Deserialize, Serialize};
+use uor_utils::csv::SerializeCSV;
+
+use crate::adc::service::AdcService;
+use crate::adc::types::AdcDevice;
+use crate::calibration_model::types::ChannelMarker;
+use crate::sd::config::MAX_LINE_LENGTH;
+use crate::sd::types::{Line, SdCardError};
+use crate::sensor::types::ChannelKind;
+use crate::tare::service::TareService;
+
+// Zero offset of a single channel of an ADC, subtracted from the calibrated value
+#[derive(Debug, Clone, Copy, Format, Serialize, Deserialize)]
+pub struct TareOffset<Channel> {
+	pub adc: AdcDevice,
+	pub channel: Channel,
+
+	// Average calibrated value of the channel during the tare, in the units of the channel
+	pub offset: f64,
+
+	// Local session of the device when the tare was taken, None if no session had been started
+	pub local_session: Option<i32>,
+}
+
+impl<Channel> SerializeCSV<MAX_LINE_LENGTH> for TareOffset<Channel>
+where
+	Channel: ChannelMarker,
+{
+	fn get_csv_header() -> Line {
+		Line::from_str("ADC Index,Channel Index,Offset,Session").unwrap()
+	}
+}
+
+/// A sensor service whose channels can be tared, so the tare routines of tare::service can be shared between the services.
+pub trait TaredSensorService<const ADC_COUNT: usize, const CHANNEL_COUNT: usize> {
+	// Identifies the ADCs the service reads in ADC_CHANNEL_KINDS
+	const CHANNEL_KIND: ChannelKind;
+
+	// Number of readings averaged into the offset of a tare
+	const TARE_READING_COUNT: usize;
+
+	type Channel: ChannelMarker + From<usize>;
+	type Error: From<SdCardError> + Format;
+
+	fn adc_service(&self) -> &'static AdcService<ADC_COUNT>;
+
+	fn tare_service(&mut self) -> &mut TareService<Self::Channel, ADC_COUNT, CHANNEL_COUNT>;
+
+	/// Reads the channel once, in the units of its offset and with the current offset already subtracted.
+	fn read_tared_value(
+		&mut self,
+		adc: AdcDevice,
+		channel: Self::Channel,
+	) -> impl Future<Output = Result<f64, Self::Error>>;
+}
@@ -1,15 +1,79 @@
+use strum::EnumCount;
+
+use crate::adc::driver::types::{AnalogChannel, Gain, SensorBiasMagnitude};
+use crate::adc::types::AdcDevice;
+use crate::calibration_model::types::{LowQualityFitAction, StabilityCriteria};
+use crate::scan_sequencer::types::{scan_every_channel, ScanEntry};
+use crate::temperature::types::{RtdConfiguration, RtdReference, RtdWiring, ThermocoupleChannel, ThermocoupleType};
+
 // Size of the queue used to send temperature readings from the temperature service to the SD card service
 pub const THERMOCOUPLE_READING_QUEUE_SIZE: usize = 16;
 
-// Maximum number of calibration data points allowed to be collected during a calibration session per thermocouple channel
-pub const MAX_CALIBRATION_DATA_POINTS: usize = 10;
+// Minimum R² of a thermocouple calibration fit over its data points
+pub const MIN_CALIBRATION_R_SQUARED: f64 = 0.999;
+
+// Whether a thermocouple calibration fit below MIN_CALIBRATION_R_SQUARED is still saved after warning the operator, or refused
+pub const LOW_QUALITY_FIT_ACTION: LowQualityFitAction = LowQualityFitAction::Refuse;
+
+// When the readings of a calibration data point are stable, in degrees Celsius.
+// Calibration baths and dry-well calibrators settle slowly, so the window spans several seconds and the timeout is generous
+pub const STABILITY_CRITERIA: StabilityCriteria = StabilityCriteria {
+	max_standard_deviation: 0.05,
+	max_drift: 0.05,
+	reading_interval: 500,
+	timeout: 120000,
+};
+
+// File name used to read/write the calibration models applied to thermocouple readings to/from the SD card
+// Calibration models are stored in CSV format
+pub const CALIBRATION_MODELS_FILE_NAME: &str = "t_temp.csv"; // Cannot be longer than 12 characters
+
+// File name used to read/write the history of the calibrations saved for the thermocouple channels to/from the SD card
+// Each calibration is kept with its fit quality, data points and operator notes, so previous calibrations can be listed and restored
+pub const CALIBRATION_HISTORY_FILE_NAME: &str = "h_temp.csv"; // Cannot be longer than 12 characters
 
-// File name used to read/write linear transformations that applied to thermocouple readings to/from the SD card
-// Linear transformations are stored in CSV format
-pub const LINEAR_TRANSFORMATIONS_FILE_NAME: &str = "t_temp.csv"; // Cannot be longer than 12 characters
+// File name used to read the number of conversions averaged into each thermocouple reading from the SD card
+// Oversampling configurations are stored in CSV format, channels without one are read with a single conversion
+pub const OVERSAMPLING_FILE_NAME: &str = "o_temp.csv"; // Cannot be longer than 12 characters
+
+// Type of the thermocouple wired to each channel, indexed by ADC and then channel
+// The type selects the ITS-90 conversion used for both the thermocouple voltage and the cold-junction compensation
+pub const THERMOCOUPLE_TYPES: [[ThermocoupleType; ThermocoupleChannel::COUNT]; AdcDevice::COUNT] = [
+	[ThermocoupleType::K, ThermocoupleType::K, ThermocoupleType::K, ThermocoupleType::K],
+	[ThermocoupleType::K, ThermocoupleType::K, ThermocoupleType::K, ThermocoupleType::K],
+];
 
 // Resistance of the RTD at 0 °C.
 pub const RTD_RESISTANCE_AT_0C: f32 = 1000.0; // Ohms
 
+// How the RTD on each ADC is excited and measured.
+// Note: This is based on Argus V2 design as of September 22, 2025
+// The RTD is excited by the board in series with R6 (1k) to ground, and the AIN8-9 sequence is flipped accidentally
+// so AIN9 is before the RTD and AIN8 is after the RTD. The R6 voltage is measured against AINCOM and divided out.
+pub const RTD_CONFIGURATION: RtdConfiguration = RtdConfiguration {
+	wiring: RtdWiring::TwoWire,
+	sense_pair: (AnalogChannel::AIN9, AnalogChannel::AIN8),
+	reference: RtdReference::MeasuredPair(AnalogChannel::AIN8, AnalogChannel::AINCOM),
+	reference_resistance: 1000.0,
+	excitation: None,
+	compensation_excitation: None,
+	lead_resistance: 0.0,
+	gain: Gain::G1, // Avoid saturating the ADC
+};
+
 // Measure RTDs at a slower interval than the thermocouples
 pub const RTD_MEASUREMENT_INTERVAL: u64 = 5000; // milliseconds
+
+// How often each channel is checked for a disconnected thermocouple
+pub const OPEN_CIRCUIT_CHECK_INTERVAL: u64 = 10000; // milliseconds
+
+// Sensor bias current used for the open-circuit check. Only a small offset develops across the low resistance of a thermocouple when connected
+pub const OPEN_CIRCUIT_BIAS_MAGNITUDE: SensorBiasMagnitude = SensorBiasMagnitude::Current10uA;
+
+// Which thermocouple channels are sampled, how often, and in what order, see the scan sequencer. Every channel is sampled as fast as the ADCs allow
+pub const THERMOCOUPLE_SCAN_LIST: [ScanEntry<ThermocoupleChannel>; AdcDevice::COUNT * ThermocoupleChannel::COUNT] = scan_every_channel([
+	ThermocoupleChannel::Channel1,
+	ThermocoupleChannel::Channel2,
+	ThermocoupleChannel::Channel3,
+	ThermocoupleChannel::Channel4,
+]);
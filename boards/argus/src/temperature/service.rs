@@ -1,26 +1,41 @@
 use defmt::{error, info};
-use embassy_time::{Instant, Timer};
+use embassy_time::Instant;
 use strum::EnumCount;
 use uor_peripherals::serial::peripheral::UORSerial;
+use uor_utils::messages::argus::calibration::calibration_response::CalibrationResponse;
+use uor_utils::messages::argus::envelope::envelope::Message as EnvelopeMessage;
 use uor_utils::utils::types::AsyncMutex;
 
-use crate::adc::driver::types::{AnalogChannel, DataRate, Filter, Gain, ReferenceRange};
-use crate::adc::service::AdcService;
+use crate::adc::driver::types::IdacMagnitude;
+use crate::adc::service::{AdcError, AdcService};
 use crate::adc::types::AdcDevice;
-use crate::linear_transformation::service::LinearTransformationService;
+use crate::calibration_model::service::CalibrationModelService;
+use crate::calibration_model::types::{LowQualityFitAction, PreviousModel, StabilityCriteria};
+use crate::calibration_protocol::session::CalibrationSession;
+use crate::oversampling::service::OversamplingService;
+use crate::oversampling::types::SampleStatistics;
+use crate::scan_sequencer::service::ScanSequencer;
 use crate::sd::service::SDCardService;
+use crate::sensor::acquisition::{read_channel, OpenCircuitMonitor};
+use crate::sensor::setup::configure_adcs;
+use crate::sensor::types::{CalibratedSensorService, ChannelKind};
 use crate::session::service::SessionService;
-use crate::temperature::config::{LINEAR_TRANSFORMATIONS_FILE_NAME, RTD_RESISTANCE_AT_0C};
+use crate::temperature::config::{
+	CALIBRATION_HISTORY_FILE_NAME, CALIBRATION_MODELS_FILE_NAME, LOW_QUALITY_FIT_ACTION, MIN_CALIBRATION_R_SQUARED, OPEN_CIRCUIT_BIAS_MAGNITUDE,
+	OPEN_CIRCUIT_CHECK_INTERVAL, OVERSAMPLING_FILE_NAME, RTD_CONFIGURATION, RTD_RESISTANCE_AT_0C, STABILITY_CRITERIA, THERMOCOUPLE_SCAN_LIST,
+	THERMOCOUPLE_TYPES,
+};
 use crate::temperature::rtd;
-use crate::temperature::thermocouple::type_k;
-use crate::temperature::types::{TemperatureServiceError, ThermocoupleChannel, ThermocoupleReading, ThermocoupleReadingQueue};
+use crate::temperature::types::{
+	RtdReference, TemperatureServiceError, ThermocoupleChannel, ThermocoupleReading, ThermocoupleReadingQueue,
+};
 
 // A channel for buffering the temperature readings and decoupling the logging to sd task from the measurement task
 pub static THERMOCOUPLE_READING_QUEUE: ThermocoupleReadingQueue = ThermocoupleReadingQueue::new();
 
 pub struct TemperatureService<const ADC_COUNT: usize> {
 	// Other services are passed by a mutex to ensure safe concurrent access
-	pub adc_service: &'static AsyncMutex<AdcService<ADC_COUNT>>,
+	pub adc_service: &'static AdcService<ADC_COUNT>,
 	pub sd_card_service: &'static AsyncMutex<SDCardService>,
 	pub serial_service: &'static AsyncMutex<UORSerial>,
 	pub session_service: &'static AsyncMutex<SessionService>,
@@ -30,13 +45,28 @@ pub struct TemperatureService<const ADC_COUNT: usize> {
 	// We have one RTD per ADC, so we store an array of last readings
 	pub last_rtd_reading: [Option<f32>; ADC_COUNT],
 
-	// Linear transformations that are applied on top of the raw readings for each ADC and channel
-	pub linear_transformation_service: LinearTransformationService<ThermocoupleChannel, f64, ADC_COUNT, { ThermocoupleChannel::COUNT }>,
+	// Checks the channels for a disconnected sensor before they are read
+	pub open_circuit_monitor: OpenCircuitMonitor<ADC_COUNT, { ThermocoupleChannel::COUNT }>,
+
+	// Calibration models that are applied on top of the raw readings for each ADC and channel
+	pub calibration_model_service: CalibrationModelService<ThermocoupleChannel, f64, ADC_COUNT, { ThermocoupleChannel::COUNT }>,
+
+	// Number of conversions averaged into the readings of each ADC and channel
+	pub oversampling_service: OversamplingService<ThermocoupleChannel, ADC_COUNT, { ThermocoupleChannel::COUNT }>,
+
+	// Decides which channels the measurement task reads and when
+	pub scan_sequencer: ScanSequencer<ThermocoupleChannel, ADC_COUNT, { THERMOCOUPLE_SCAN_LIST.len() }>,
+
+	// Calibration driven by the ground application over the calibration protocol, None outside of a session
+	pub calibration_session: Option<CalibrationSession<ThermocoupleChannel>>,
+
+	// Calibration model of the channel being calibrated, registered again unless the new calibration is saved
+	pub previous_calibration: Option<PreviousModel<ThermocoupleChannel, f64>>,
 }
 
 impl<const ADC_COUNT: usize> TemperatureService<ADC_COUNT> {
 	pub fn new(
-		adc_service: &'static AsyncMutex<AdcService<ADC_COUNT>>,
+		adc_service: &'static AdcService<ADC_COUNT>,
 		sd_card_service: &'static AsyncMutex<SDCardService>,
 		serial_service: &'static AsyncMutex<UORSerial>,
 		session_service: &'static AsyncMutex<SessionService>,
@@ -47,26 +77,40 @@ impl<const ADC_COUNT: usize> TemperatureService<ADC_COUNT> {
 			serial_service,
 			session_service,
 			last_rtd_reading: [None; ADC_COUNT],
-			linear_transformation_service: LinearTransformationService::new(sd_card_service, LINEAR_TRANSFORMATIONS_FILE_NAME),
+			open_circuit_monitor: OpenCircuitMonitor::new(OPEN_CIRCUIT_CHECK_INTERVAL, OPEN_CIRCUIT_BIAS_MAGNITUDE),
+			calibration_model_service: CalibrationModelService::new(
+				sd_card_service,
+				CALIBRATION_MODELS_FILE_NAME,
+				CALIBRATION_HISTORY_FILE_NAME,
+			),
+			oversampling_service: OversamplingService::new(sd_card_service, OVERSAMPLING_FILE_NAME),
+			scan_sequencer: ScanSequencer::new(THERMOCOUPLE_SCAN_LIST),
+			calibration_session: None,
+			previous_calibration: None,
 		}
 	}
 
+	/// Configures the ADCs wired to its kind and loads the files of the service, see sensor::setup::prepare_adcs for the steps run before.
 	pub async fn setup(&mut self) -> Result<(), TemperatureServiceError> {
-		// Delay for 100ms to ensure ADCs are powered up
-		Timer::after_millis(100).await;
-
-		for driver in self.adc_service.lock().await.drivers.iter_mut() {
-			driver.reference_range = ReferenceRange::Avdd;
-			driver.data_rate = DataRate::Sps100;
-			driver.filter = Filter::Sinc3;
-			driver.enable_internal_reference = true;
-			driver.gain = Gain::G32;
-			driver.delay_after_setting_channel = 50; // 50 ms delay to allow the ADC to stabilize after switching channels
-			driver.apply_configurations().await?;
+		// The RTD inputs are converted at their own gain and reference, the thermocouples use the configuration shared by every kind
+		configure_adcs(self.adc_service, ChannelKind::Thermocouple, |driver| {
+			let rtd_profile = RTD_CONFIGURATION.acquisition_profile(driver.current_profile());
+			let (sense_positive, sense_negative) = RTD_CONFIGURATION.sense_pair;
+			driver.set_acquisition_profile(sense_positive, sense_negative, rtd_profile)?;
+			if let RtdReference::MeasuredPair(reference_positive, reference_negative) = RTD_CONFIGURATION.reference {
+				driver.set_acquisition_profile(reference_positive, reference_negative, rtd_profile)?;
+			}
+			Ok(())
+		})
+		.await;
+
+		match self.calibration_model_service.load_models().await {
+			Err(e) => error!("Failed to load calibration models: {:?}", e),
+			_ => {}
 		}
 
-		match self.linear_transformation_service.load_transformations().await {
-			Err(e) => error!("Failed to load linear transformations: {:?}", e),
+		match self.oversampling_service.load_configurations().await {
+			Err(e) => error!("Failed to load oversampling configurations: {:?}", e),
 			_ => {}
 		}
 		Ok(())
@@ -77,36 +121,79 @@ impl<const ADC_COUNT: usize> TemperatureService<ADC_COUNT> {
 		adc: AdcDevice,
 		channel: ThermocoupleChannel,
 	) -> Result<ThermocoupleReading, TemperatureServiceError> {
-		let mut adc_service = self.adc_service.lock().await;
+		let mut adcs = [false; ADC_COUNT];
+		adcs[adc as usize] = true;
 
-		// Get the respective "adc channel" pair for the "thermocouple channel"
-		let (positive_channel, negative_channel) = channel.to_analog_input_channel_pair();
+		let mut readings = self.read_thermocouples(channel, adcs).await;
+		readings[adc as usize].take().unwrap_or(Err(TemperatureServiceError::AdcUnavailable))
+	}
 
-		// Read the voltage from the ADC in millivolts
-		let voltage = adc_service.drivers[adc as usize]
-			.read_differential(positive_channel, negative_channel)
-			.await? * 1000.0; // Convert to millivolts
+	/// Reads the thermocouple channel on the given ADCs at the same instant, indexed by ADC.
+	/// ADCs that aren't given, that are wired to another kind of sensor, or that are missing or faulted, return None.
+	/// See sensor::acquisition::read_channel for how disconnected sensors are rejected and the conversions are synchronized.
+	pub async fn read_thermocouples(
+		&mut self,
+		channel: ThermocoupleChannel,
+		adcs: [bool; ADC_COUNT],
+	) -> [Option<Result<ThermocoupleReading, TemperatureServiceError>>; ADC_COUNT] {
+		let oversampling = core::array::from_fn(|adc_index| self.oversampling_service.get_oversampling(AdcDevice::from(adc_index), channel));
+		let statistics: [Option<Result<SampleStatistics, TemperatureServiceError>>; ADC_COUNT] = read_channel(
+			self.adc_service,
+			&mut self.open_circuit_monitor,
+			ChannelKind::Thermocouple,
+			channel as usize,
+			channel.to_analog_input_channel_pair(),
+			adcs,
+			oversampling,
+		)
+		.await;
+		let local_session = self.session_service.lock().await.current_session.clone();
+
+		let mut statistics = statistics.into_iter();
+		core::array::from_fn(|adc_index| {
+			let statistics = statistics.next().unwrap()?;
+			Some(statistics.and_then(|statistics| self.build_thermocouple_reading(AdcDevice::from(adc_index), channel, statistics, local_session)))
+		})
+	}
+
+	// Converts the averaged thermocouple voltage (in volts) to a compensated reading
+	fn build_thermocouple_reading(
+		&self,
+		adc: AdcDevice,
+		channel: ThermocoupleChannel,
+		statistics: SampleStatistics,
+		local_session: Option<i32>,
+	) -> Result<ThermocoupleReading, TemperatureServiceError> {
+		let statistics = statistics.scale(1000.0); // Convert to millivolts
+		let voltage = statistics.mean;
 
 		// Get the cold junction temperature from the last RTD reading for this ADC
 		let cold_junction_temperature = self.last_rtd_reading[adc as usize].unwrap_or(0.0);
-		let uncompensated_temperature = type_k::convert_voltage_to_temperature(voltage as f64)?;
+		let thermocouple_type = THERMOCOUPLE_TYPES[adc as usize][channel as usize];
+		// Without the cold junction the raw EMF can fall outside the range of the type, only this column is left out of the reading then
+		let uncompensated_temperature = thermocouple_type.convert_voltage_to_temperature(voltage as f64).unwrap_or(f64::NAN);
 		let mut compensated_temperature =
-			type_k::convert_voltage_to_temperature_with_cold_junction_compensation(voltage as f64, cold_junction_temperature as f64)?;
+			thermocouple_type.convert_voltage_to_temperature_with_cold_junction_compensation(voltage as f64, cold_junction_temperature as f64)?;
 
-		// Apply any linear transformations configured for this ADC and channel
+		// Apply any calibration model configured for this ADC and channel
 		compensated_temperature = self
-			.linear_transformation_service
-			.apply_transformation(adc, channel, compensated_temperature);
+			.calibration_model_service
+			.apply_model(adc, channel, compensated_temperature);
 
 		let thermocouple_reading = ThermocoupleReading {
-			local_session: self.session_service.lock().await.current_session.clone(),
+			local_session,
 			adc_device: adc,
 			thermocouple_channel: channel,
+			thermocouple_type,
 			recorded_at: Instant::now().as_millis(),
 			voltage,
 			uncompensated_temperature,
 			compensated_temperature,
 			cold_junction_temperature,
+			sample_count: statistics.sample_count,
+			voltage_standard_deviation: statistics.standard_deviation,
+			voltage_min: statistics.min,
+			voltage_max: statistics.max,
 		};
 
 		Ok(thermocouple_reading)
@@ -116,30 +203,48 @@ impl<const ADC_COUNT: usize> TemperatureService<ADC_COUNT> {
 		&mut self,
 		adc: AdcDevice,
 	) -> Result<f32, TemperatureServiceError> {
-		let mut adc_service = self.adc_service.lock().await;
-		let driver = &mut adc_service.drivers[adc as usize];
-		let previous_gain = driver.gain;
-
-		// Set the gain to 1 for RTD measurement to avoid saturating the ADC
-		driver.gain = Gain::G1;
-		driver.apply_gain_and_data_rate_configuration().await?;
-		driver.wait_for_next_data().await;
-
-		// Perform the measurement at the gain of 1
-
-		// Note: This is based on Argus V2 design as of September 22, 2025
-		// The AIN8-9 sequence is flipped accidentally so AIN9 is before the RTD and AIN8 is after the RTD
-		let voltage_before_rtd = driver.read_single_ended(AnalogChannel::AIN9).await?;
-		let voltage_after_rtd = driver.read_single_ended(AnalogChannel::AIN8).await?;
-
-		// Restore the previous gain
-		driver.gain = previous_gain;
-		driver.apply_gain_and_data_rate_configuration().await?;
-
-		// I = voltage_after_rtd / R6
-		// measured_resistance = V_RTD / I = R6 * (voltage_before_rtd - voltage_after_rtd) / voltage_after_rtd
-		const R6: f32 = 1000.0;
-		let measured_resistance = R6 * (voltage_before_rtd - voltage_after_rtd) / voltage_after_rtd;
+		let mut driver = self.adc_service.drivers[adc as usize].lock().await;
+		let configuration = RTD_CONFIGURATION;
+
+		// Turn on the excitation currents. The gain and reference are switched by the RTD acquisition profile once its inputs are selected
+		if let Some((output, magnitude)) = configuration.excitation {
+			driver.idac1_output = Some(output);
+			driver.idac1_magnitude = magnitude;
+			if let Some(compensation_output) = configuration.compensation_excitation {
+				driver.idac2_output = Some(compensation_output);
+				driver.idac2_magnitude = magnitude;
+			}
+			driver.apply_idac_configuration().await?;
+		}
+
+		let ratio: Result<f32, AdcError> = async {
+			if configuration.excitation.is_some() {
+				// Give the excitation a conversion to settle
+				driver.wait_for_next_data().await?;
+			}
+
+			let (sense_positive, sense_negative) = configuration.sense_pair;
+			match configuration.reference {
+				RtdReference::ReferenceInputs(_) => driver.read_differential_ratio(sense_positive, sense_negative).await,
+				RtdReference::MeasuredPair(reference_positive, reference_negative) => {
+					let rtd_voltage = driver.read_differential(sense_positive, sense_negative).await?;
+					let reference_voltage = driver.read_differential(reference_positive, reference_negative).await?;
+					Ok(rtd_voltage / reference_voltage)
+				}
+			}
+		}
+		.await;
+
+		// Turn the excitation off even if the measurement failed so the thermocouples are not left excited
+		if configuration.excitation.is_some() {
+			driver.idac1_output = None;
+			driver.idac1_magnitude = IdacMagnitude::Off;
+			driver.idac2_output = None;
+			driver.idac2_magnitude = IdacMagnitude::Off;
+			driver.apply_idac_configuration().await?;
+		}
+
+		let measured_resistance = configuration.compute_resistance(ratio?);
 		let estimated_temperature = rtd::convert_resistance_to_temperature(RTD_RESISTANCE_AT_0C, measured_resistance);
 
 		Ok(estimated_temperature)
@@ -149,9 +254,99 @@ impl<const ADC_COUNT: usize> TemperatureService<ADC_COUNT> {
 		&mut self,
 		adc: AdcDevice,
 	) -> Result<(), TemperatureServiceError> {
+		// Missing or faulted ADCs and the ADCs wired to another kind are skipped, their thermocouples aren't read either
+		if !self.adc_service.is_available_for(adc, ChannelKind::Thermocouple).await {
+			return Ok(());
+		}
+
 		let rtd_temperature = self.read_rtd(adc).await?;
 		self.last_rtd_reading[adc as usize] = Some(rtd_temperature);
 		info!("RTD Temperature {}: {}C", adc, rtd_temperature);
 		Ok(())
 	}
 }
+
+impl<const ADC_COUNT: usize> CalibratedSensorService<ADC_COUNT, { ThermocoupleChannel::COUNT }> for TemperatureService<ADC_COUNT> {
+	type Channel = ThermocoupleChannel;
+	type Error = TemperatureServiceError;
+	type Reading = ThermocoupleReading;
+
+	const CHANNEL_KIND: ChannelKind = ChannelKind::Thermocouple;
+	const LOW_QUALITY_FIT_ACTION: LowQualityFitAction = LOW_QUALITY_FIT_ACTION;
+	const MIN_CALIBRATION_R_SQUARED: f64 = MIN_CALIBRATION_R_SQUARED;
+	const STABILITY_CRITERIA: StabilityCriteria = STABILITY_CRITERIA;
+	const UNIT: &'static str = "°C";
+
+	fn adc_service(&self) -> &'static AdcService<ADC_COUNT> {
+		self.adc_service
+	}
+
+	fn serial_service(&self) -> &'static AsyncMutex<UORSerial> {
+		self.serial_service
+	}
+
+	fn session_service(&self) -> &'static AsyncMutex<SessionService> {
+		self.session_service
+	}
+
+	fn calibration_model_service(&mut self) -> &mut CalibrationModelService<ThermocoupleChannel, f64, ADC_COUNT, { ThermocoupleChannel::COUNT }> {
+		&mut self.calibration_model_service
+	}
+
+	fn has_calibration_session(&self) -> bool {
+		self.calibration_session.is_some()
+	}
+
+	fn calibration_session(&mut self) -> &mut Option<CalibrationSession<ThermocoupleChannel>> {
+		&mut self.calibration_session
+	}
+
+	fn calibration_value(reading: &ThermocoupleReading) -> f64 {
+		reading.compensated_temperature
+	}
+
+	fn deregister_calibration(
+		&mut self,
+		adc: AdcDevice,
+		channel: ThermocoupleChannel,
+	) {
+		self.previous_calibration = Some(PreviousModel {
+			adc,
+			channel,
+			model: self.calibration_model_service.deregister_model(adc, channel),
+		});
+	}
+
+	fn reregister_calibration(&mut self) {
+		if let Some(PreviousModel { adc, channel, model: Some(model) }) = self.previous_calibration.take() {
+			self.calibration_model_service.register_model(adc, channel, model);
+		}
+	}
+
+	async fn read_calibration_reading(
+		&mut self,
+		adc: AdcDevice,
+		channel: ThermocoupleChannel,
+	) -> Result<ThermocoupleReading, TemperatureServiceError> {
+		self.read_thermocouple(adc, channel).await
+	}
+
+	async fn send_calibration_response(
+		&mut self,
+		response: CalibrationResponse,
+	) -> Result<(), TemperatureServiceError> {
+		let mut serial_service = self.serial_service.lock().await;
+		serial_service.write_envelope_message(EnvelopeMessage::CalibrationResponse(response)).await?;
+		Ok(())
+	}
+
+	// Nothing else of the service depends on the calibration model of a thermocouple
+	async fn replace_previous_calibration(
+		&mut self,
+		_adc: AdcDevice,
+		_channel: ThermocoupleChannel,
+	) -> Result<(), TemperatureServiceError> {
+		self.previous_calibration = None;
+		Ok(())
+	}
+}
@@ -1,17 +1,19 @@
 use defmt::{error, info};
 use embassy_executor::task;
 use embassy_futures::yield_now;
+use embassy_time::{Instant, Timer};
 use strum::EnumCount;
 use uor_utils::utils::types::AsyncMutex;
 
+use crate::adc::service::AdcError;
 use crate::adc::types::AdcDevice;
 use crate::led_indicator::service::LedIndicatorService;
 use crate::state_machine::service::StateMachineWorker;
 use crate::state_machine::types::States;
 use crate::temperature::service::{TemperatureService, THERMOCOUPLE_READING_QUEUE};
-use crate::temperature::types::ThermocoupleChannel;
+use crate::temperature::types::TemperatureServiceError;
 
-// Task that iterates through the ADCs and channels, measures the temperature, and enqueues the readings to a channel
+// Task that measures the thermocouple channels of the scan list as they become due, and enqueues the readings to a channel
 #[task]
 pub async fn measure_thermocouples(
 	mut worker: StateMachineWorker,
@@ -20,16 +22,33 @@ pub async fn measure_thermocouples(
 ) {
 	worker
 		.run_while(&[States::Recording], async |_| -> Result<(), ()> {
-			for adc_index in 0..AdcDevice::COUNT {
-				for channel_index in 0..ThermocoupleChannel::COUNT {
+			// Wait for the next channel to be due without holding the service, so the other tasks can use it in the meantime
+			let next_due_at = temperature_service_mutex.lock().await.scan_sequencer.next_due_at();
+			Timer::at(next_due_at).await;
+			let scan_steps = temperature_service_mutex.lock().await.scan_sequencer.take_due(Instant::now().as_millis());
+
+			for scan_step in scan_steps.iter() {
+				let channel = scan_step.channel;
+				let readings = temperature_service_mutex.lock().await.read_thermocouples(channel, scan_step.adcs).await;
+				for (adc_index, data) in readings.into_iter().enumerate() {
+					// ADCs the channel wasn't due on, or that are missing or faulted, are skipped
+					let Some(data) = data else {
+						continue;
+					};
 					let adc = AdcDevice::from(adc_index);
-					let channel = ThermocoupleChannel::from(channel_index);
-					let data = temperature_service_mutex.lock().await.read_thermocouple(adc, channel).await;
 					match data {
 						Ok(thermocouple_reading) => {
 							info!("{}", thermocouple_reading);
 							THERMOCOUPLE_READING_QUEUE.send(thermocouple_reading).await;
 						}
+						Err(TemperatureServiceError::OpenCircuit) => {
+							error!("Open circuit on ADC {} Channel {}. Is the thermocouple connected?", adc, channel);
+							continue;
+						}
+						Err(TemperatureServiceError::AdcError(AdcError::DataReadyTimeout)) => {
+							error!("Timed out waiting for data from ADC {} Channel {}. Is the ADC connected?", adc, channel);
+							continue;
+						}
 						Err(err) => {
 							error!("Error reading ADC {} Channel {}: {:?}", adc, channel, err);
 							continue;
@@ -38,8 +57,10 @@ pub async fn measure_thermocouples(
 				}
 			}
 
-			// Blink LED to indicate measurement cycle complete
-			led_indicator_service_mutex.lock().await.blink(1).await;
+			// Blink LED once per pass over the scan list to indicate the measurements are running
+			if scan_steps.iter().any(|scan_step| scan_step.starts_cycle) {
+				led_indicator_service_mutex.lock().await.blink(1).await;
+			}
 
 			// Yield to allow other tasks to run, especially the RTD measurement task
 			yield_now().await;
@@ -1 +1,12 @@
+pub mod error;
+pub mod polynomial;
+pub mod type_b;
+pub mod type_e;
+pub mod type_j;
 pub mod type_k;
+pub mod type_n;
+pub mod type_r;
+pub mod type_s;
+pub mod type_t;
+
+pub use error::*;
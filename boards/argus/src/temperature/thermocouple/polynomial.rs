@@ -0,0 +1,11 @@
+/// Evaluate a power series y = Σ c_i * x^i using Horner’s method, kept legible.
+pub fn evaluate_power_series(
+	x: f64,
+	coefficients: &[f64],
+) -> f64 {
+	let mut accumulator = 0.0;
+	for &c in coefficients.iter().rev() {
+		accumulator = accumulator * x + c;
+	}
+	accumulator
+}
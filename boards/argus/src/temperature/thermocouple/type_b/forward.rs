@@ -0,0 +1,49 @@
+use crate::temperature::thermocouple::polynomial::evaluate_power_series;
+
+// ────────────────────────────────────────────────────────────────────────────
+// ITS-90: Type B reference function E(t)  (voltage from temperature)
+// Two ranges:
+//
+// A) 0 °C ≤ t ≤ 630.615 °C
+// E = Σ c_i * t^i  (i = 0..6)
+//
+// B) 630.615 °C < t ≤ 1820 °C
+// E = Σ c_i * t^i  (i = 0..8)
+//
+// Coefficients are from the official tables (units: E in mV, t in °C).
+// ────────────────────────────────────────────────────────────────────────────
+
+const B_E_OF_T_LOW_COEFFICIENTS: [f64; 7] = [
+	0.000_000_000_000e+00,
+	-0.246_508_183_460e-03,
+	0.590_404_211_710e-05,
+	-0.132_579_316_360e-08,
+	0.156_682_919_010e-11,
+	-0.169_445_292_400e-14,
+	0.629_903_470_940e-18,
+];
+
+const B_E_OF_T_HIGH_COEFFICIENTS: [f64; 9] = [
+	-0.389_381_686_210e+01,
+	0.285_717_474_700e-01,
+	-0.848_851_047_850e-04,
+	0.157_852_801_640e-06,
+	-0.168_353_448_640e-09,
+	0.111_097_940_130e-12,
+	-0.445_154_310_330e-16,
+	0.989_756_408_210e-20,
+	-0.937_913_302_890e-24,
+];
+
+/// Returns E(t) in millivolts for a Type B thermocouple, or None if t is out of range.
+pub fn convert_temperature_to_voltage(temperature: f64) -> Option<f64> {
+	if !(0.0..=1820.0).contains(&temperature) {
+		return None;
+	}
+
+	if temperature <= 630.615 {
+		Some(evaluate_power_series(temperature, &B_E_OF_T_LOW_COEFFICIENTS))
+	} else {
+		Some(evaluate_power_series(temperature, &B_E_OF_T_HIGH_COEFFICIENTS))
+	}
+}
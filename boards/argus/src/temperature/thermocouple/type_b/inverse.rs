@@ -0,0 +1,56 @@
+use crate::temperature::thermocouple::error::ThermocoupleError;
+use crate::temperature::thermocouple::polynomial::evaluate_power_series;
+
+// ────────────────────────────────────────────────────────────────────────────
+// ITS-90: Type B inverse function t90(E)  (temperature from voltage)
+// Two ranges:
+//
+// 1) 0.291 mV ≤ E ≤ 2.431 mV  (250 °C to 700 °C)
+// t = Σ d_i * E^i   (i = 0..8)
+//
+// 2) 2.431 mV < E ≤ 13.820 mV  (700 °C to 1820 °C)
+// t = Σ d_i * E^i   (i = 0..8)
+//
+// Coefficients below are the official ITS-90 values (E in mV, t in °C).
+// ────────────────────────────────────────────────────────────────────────────
+
+const B_T_OF_E_D_LOW: [f64; 9] = [
+	9.842_332_1e+01,
+	6.997_150_0e+02,
+	-8.476_530_4e+02,
+	1.005_264_4e+03,
+	-8.334_595_2e+02,
+	4.550_854_2e+02,
+	-1.552_303_7e+02,
+	2.988_675_0e+01,
+	-2.474_286_0e+00,
+];
+
+const B_T_OF_E_D_HIGH: [f64; 9] = [
+	2.131_507_1e+02,
+	2.851_050_4e+02,
+	-5.274_288_7e+01,
+	9.916_080_4e+00,
+	-1.296_530_3e+00,
+	1.119_587_0e-01,
+	-6.062_519_9e-03,
+	1.866_169_6e-04,
+	-2.487_858_5e-06,
+];
+
+const B_E_MIN_MV: f64 = 0.291;
+const B_E_LOW_MAX_MV: f64 = 2.431;
+const B_E_MAX_MV: f64 = 13.820;
+
+/// Returns t90(E) in °C for Type B, or an error if E is out of range.
+pub fn convert_voltage_to_temperature(voltage: f64) -> Result<f64, ThermocoupleError> {
+	if !(B_E_MIN_MV..=B_E_MAX_MV).contains(&voltage) {
+		return Err(ThermocoupleError::MillivoltsOutOfRange);
+	}
+
+	if voltage <= B_E_LOW_MAX_MV {
+		Ok(evaluate_power_series(voltage, &B_T_OF_E_D_LOW))
+	} else {
+		Ok(evaluate_power_series(voltage, &B_T_OF_E_D_HIGH))
+	}
+}
@@ -0,0 +1,58 @@
+use crate::temperature::thermocouple::polynomial::evaluate_power_series;
+
+// ────────────────────────────────────────────────────────────────────────────
+// ITS-90: Type E reference function E(t)  (voltage from temperature)
+// Two ranges:
+//
+// A) -270 °C ≤ t ≤ 0 °C
+// E = Σ c_i * t^i  (i = 0..13)
+//
+// B) 0 °C < t ≤ 1000 °C
+// E = Σ c_i * t^i  (i = 0..10)
+//
+// Coefficients are from the official tables (units: E in mV, t in °C).
+// ────────────────────────────────────────────────────────────────────────────
+
+const E_E_OF_T_NEG_COEFFICIENTS: [f64; 14] = [
+	0.000_000_000_000e+00,
+	0.586_655_087_080e-01,
+	0.454_109_771_240e-04,
+	-0.779_980_486_860e-06,
+	-0.258_001_608_430e-07,
+	-0.594_525_830_570e-09,
+	-0.932_140_586_670e-11,
+	-0.102_876_055_340e-12,
+	-0.803_701_236_210e-15,
+	-0.439_794_973_910e-17,
+	-0.164_147_763_550e-19,
+	-0.396_736_195_160e-22,
+	-0.558_273_287_210e-25,
+	-0.346_578_420_130e-28,
+];
+
+const E_E_OF_T_POS_COEFFICIENTS: [f64; 11] = [
+	0.000_000_000_000e+00,
+	0.586_655_087_100e-01,
+	0.450_322_755_820e-04,
+	0.289_084_072_120e-07,
+	-0.330_568_966_520e-09,
+	0.650_244_032_700e-12,
+	-0.191_974_955_040e-15,
+	-0.125_366_004_970e-17,
+	0.214_892_175_690e-20,
+	-0.143_880_417_820e-23,
+	0.359_608_994_810e-27,
+];
+
+/// Returns E(t) in millivolts for a Type E thermocouple, or None if t is out of range.
+pub fn convert_temperature_to_voltage(temperature: f64) -> Option<f64> {
+	if !(-270.0..=1000.0).contains(&temperature) {
+		return None;
+	}
+
+	if temperature <= 0.0 {
+		Some(evaluate_power_series(temperature, &E_E_OF_T_NEG_COEFFICIENTS))
+	} else {
+		Some(evaluate_power_series(temperature, &E_E_OF_T_POS_COEFFICIENTS))
+	}
+}
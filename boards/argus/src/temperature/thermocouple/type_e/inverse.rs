@@ -0,0 +1,57 @@
+use crate::temperature::thermocouple::error::ThermocoupleError;
+use crate::temperature::thermocouple::polynomial::evaluate_power_series;
+
+// ────────────────────────────────────────────────────────────────────────────
+// ITS-90: Type E inverse function t90(E)  (temperature from voltage)
+// Two ranges:
+//
+// 1) -8.825 mV ≤ E ≤ 0.000 mV  (-200 °C to 0 °C)
+// t = Σ d_i * E^i   (i = 0..8)
+//
+// 2) 0.000 mV < E ≤ 76.373 mV  (0 °C to 1000 °C)
+// t = Σ d_i * E^i   (i = 0..9)
+//
+// Coefficients below are the official ITS-90 values (E in mV, t in °C).
+// ────────────────────────────────────────────────────────────────────────────
+
+const E_T_OF_E_D_NEG: [f64; 9] = [
+	0.000_000_0e+00,
+	1.697_728_8e+01,
+	-4.351_497_0e-01,
+	-1.585_969_7e-01,
+	-9.250_287_1e-02,
+	-2.608_431_4e-02,
+	-4.136_019_9e-03,
+	-3.403_403_0e-04,
+	-1.156_489_0e-05,
+];
+
+const E_T_OF_E_D_POS: [f64; 10] = [
+	0.000_000_0e+00,
+	1.705_703_5e+01,
+	-2.330_175_9e-01,
+	6.543_558_5e-03,
+	-7.356_274_9e-05,
+	-1.789_600_1e-06,
+	8.403_616_5e-08,
+	-1.373_587_9e-09,
+	1.062_982_3e-11,
+	-3.244_708_7e-14,
+];
+
+const E_E_MIN_MV: f64 = -8.825;
+const E_E_NEG_MAX_MV: f64 = 0.000;
+const E_E_MAX_MV: f64 = 76.373;
+
+/// Returns t90(E) in °C for Type E, or an error if E is out of range.
+pub fn convert_voltage_to_temperature(voltage: f64) -> Result<f64, ThermocoupleError> {
+	if !(E_E_MIN_MV..=E_E_MAX_MV).contains(&voltage) {
+		return Err(ThermocoupleError::MillivoltsOutOfRange);
+	}
+
+	if voltage <= E_E_NEG_MAX_MV {
+		Ok(evaluate_power_series(voltage, &E_T_OF_E_D_NEG))
+	} else {
+		Ok(evaluate_power_series(voltage, &E_T_OF_E_D_POS))
+	}
+}
@@ -0,0 +1,48 @@
+use crate::temperature::thermocouple::polynomial::evaluate_power_series;
+
+// ────────────────────────────────────────────────────────────────────────────
+// ITS-90: Type J reference function E(t)  (voltage from temperature)
+// Two ranges:
+//
+// A) -210 °C ≤ t ≤ 760 °C
+// E = Σ c_i * t^i  (i = 0..8)
+//
+// B) 760 °C < t ≤ 1200 °C
+// E = Σ c_i * t^i  (i = 0..5)
+//
+// Coefficients are from the official tables (units: E in mV, t in °C).
+// ────────────────────────────────────────────────────────────────────────────
+
+const J_E_OF_T_NEG_COEFFICIENTS: [f64; 9] = [
+	0.000_000_000_000e+00,
+	0.503_811_878_150e-01,
+	0.304_758_369_300e-04,
+	-0.856_810_657_200e-07,
+	0.132_281_952_950e-09,
+	-0.170_529_583_370e-12,
+	0.209_480_906_970e-15,
+	-0.125_383_953_360e-18,
+	0.156_317_256_970e-22,
+];
+
+const J_E_OF_T_POS_COEFFICIENTS: [f64; 6] = [
+	0.296_456_256_810e+03,
+	-0.149_761_277_860e+01,
+	0.317_871_039_240e-02,
+	-0.318_476_867_010e-05,
+	0.157_208_190_040e-08,
+	-0.306_913_690_560e-12,
+];
+
+/// Returns E(t) in millivolts for a Type J thermocouple, or None if t is out of range.
+pub fn convert_temperature_to_voltage(temperature: f64) -> Option<f64> {
+	if !(-210.0..=1200.0).contains(&temperature) {
+		return None;
+	}
+
+	if temperature <= 760.0 {
+		Some(evaluate_power_series(temperature, &J_E_OF_T_NEG_COEFFICIENTS))
+	} else {
+		Some(evaluate_power_series(temperature, &J_E_OF_T_POS_COEFFICIENTS))
+	}
+}
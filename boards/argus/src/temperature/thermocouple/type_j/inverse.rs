@@ -0,0 +1,70 @@
+use crate::temperature::thermocouple::error::ThermocoupleError;
+use crate::temperature::thermocouple::polynomial::evaluate_power_series;
+
+// ────────────────────────────────────────────────────────────────────────────
+// ITS-90: Type J inverse function t90(E)  (temperature from voltage)
+// Three ranges:
+//
+// 1) -8.095 mV ≤ E ≤ 0.000 mV  (-210 °C to 0 °C)
+// t = Σ d_i * E^i   (i = 0..8)
+//
+// 2) 0.000 mV < E ≤ 42.919 mV  (0 °C to 760 °C)
+// t = Σ d_i * E^i   (i = 0..7)
+//
+// 3) 42.919 mV < E ≤ 69.553 mV  (760 °C to 1200 °C)
+// t = Σ d_i * E^i   (i = 0..5)
+//
+// Coefficients below are the official ITS-90 values (E in mV, t in °C).
+// ────────────────────────────────────────────────────────────────────────────
+
+const J_T_OF_E_D_LOW: [f64; 9] = [
+	0.000_000_0e+00,
+	1.952_826_8e+01,
+	-1.228_618_5e+00,
+	-1.075_217_8e+00,
+	-5.908_693_3e-01,
+	-1.725_671_3e-01,
+	-2.813_151_3e-02,
+	-2.396_337_0e-03,
+	-8.382_332_1e-05,
+];
+
+const J_T_OF_E_D_MID: [f64; 8] = [
+	0.000_000e+00,
+	1.978_425e+01,
+	-2.001_204e-01,
+	1.036_969e-02,
+	-2.549_687e-04,
+	3.585_153e-06,
+	-5.344_285e-08,
+	5.099_890e-10,
+];
+
+const J_T_OF_E_D_HIGH: [f64; 6] = [
+	-3.113_581_87e+03,
+	3.005_436_84e+02,
+	-9.947_732_30e+00,
+	1.702_766_30e-01,
+	-1.430_334_68e-03,
+	4.738_860_84e-06,
+];
+
+const J_E_MIN_MV: f64 = -8.095;
+const J_E_LOW_MAX_MV: f64 = 0.000;
+const J_E_MID_MAX_MV: f64 = 42.919;
+const J_E_MAX_MV: f64 = 69.553;
+
+/// Returns t90(E) in °C for Type J, or an error if E is out of range.
+pub fn convert_voltage_to_temperature(voltage: f64) -> Result<f64, ThermocoupleError> {
+	if !(J_E_MIN_MV..=J_E_MAX_MV).contains(&voltage) {
+		return Err(ThermocoupleError::MillivoltsOutOfRange);
+	}
+
+	if voltage <= J_E_LOW_MAX_MV {
+		Ok(evaluate_power_series(voltage, &J_T_OF_E_D_LOW))
+	} else if voltage <= J_E_MID_MAX_MV {
+		Ok(evaluate_power_series(voltage, &J_T_OF_E_D_MID))
+	} else {
+		Ok(evaluate_power_series(voltage, &J_T_OF_E_D_HIGH))
+	}
+}
@@ -1,4 +1,5 @@
-use crate::temperature::thermocouple::type_k::error::ThermocoupleError;
+use crate::temperature::thermocouple::error::ThermocoupleError;
+use crate::temperature::thermocouple::polynomial::evaluate_power_series;
 
 // ────────────────────────────────────────────────────────────────────────────
 // ITS-90: Type K inverse function t90(E)  (temperature from voltage)
@@ -71,15 +72,3 @@ pub fn convert_voltage_to_temperature(voltage: f64) -> Result<f64, ThermocoupleE
 		Ok(evaluate_power_series(voltage, &K_T_OF_E_D_HIGH))
 	}
 }
-
-/// Evaluate a power series t = Σ c_i * x^i using Horner’s method, kept legible.
-fn evaluate_power_series(
-	x: f64,
-	coefficients: &[f64],
-) -> f64 {
-	let mut accumulator = 0.0;
-	for &c in coefficients.iter().rev() {
-		accumulator = accumulator * x + c;
-	}
-	accumulator
-}
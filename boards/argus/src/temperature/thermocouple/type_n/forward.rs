@@ -0,0 +1,53 @@
+use crate::temperature::thermocouple::polynomial::evaluate_power_series;
+
+// ────────────────────────────────────────────────────────────────────────────
+// ITS-90: Type N reference function E(t)  (voltage from temperature)
+// Two ranges:
+//
+// A) -270 °C ≤ t ≤ 0 °C
+// E = Σ c_i * t^i  (i = 0..8)
+//
+// B) 0 °C < t ≤ 1300 °C
+// E = Σ c_i * t^i  (i = 0..10)
+//
+// Coefficients are from the official tables (units: E in mV, t in °C).
+// ────────────────────────────────────────────────────────────────────────────
+
+const N_E_OF_T_NEG_COEFFICIENTS: [f64; 9] = [
+	0.000_000_000_000e+00,
+	0.261_591_059_620e-01,
+	0.109_574_842_280e-04,
+	-0.938_411_115_540e-07,
+	-0.464_120_397_590e-10,
+	-0.263_033_577_160e-11,
+	-0.226_534_380_030e-13,
+	-0.760_893_007_910e-16,
+	-0.934_196_678_350e-19,
+];
+
+const N_E_OF_T_POS_COEFFICIENTS: [f64; 11] = [
+	0.000_000_000_000e+00,
+	0.259_293_946_010e-01,
+	0.157_101_418_800e-04,
+	0.438_256_272_370e-07,
+	-0.252_611_697_940e-09,
+	0.643_118_193_390e-12,
+	-0.100_634_715_190e-14,
+	0.997_453_389_920e-18,
+	-0.608_632_456_070e-21,
+	0.208_492_293_390e-24,
+	-0.306_821_961_510e-28,
+];
+
+/// Returns E(t) in millivolts for a Type N thermocouple, or None if t is out of range.
+pub fn convert_temperature_to_voltage(temperature: f64) -> Option<f64> {
+	if !(-270.0..=1300.0).contains(&temperature) {
+		return None;
+	}
+
+	if temperature <= 0.0 {
+		Some(evaluate_power_series(temperature, &N_E_OF_T_NEG_COEFFICIENTS))
+	} else {
+		Some(evaluate_power_series(temperature, &N_E_OF_T_POS_COEFFICIENTS))
+	}
+}
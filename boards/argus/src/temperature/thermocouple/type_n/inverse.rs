@@ -0,0 +1,71 @@
+use crate::temperature::thermocouple::error::ThermocoupleError;
+use crate::temperature::thermocouple::polynomial::evaluate_power_series;
+
+// ────────────────────────────────────────────────────────────────────────────
+// ITS-90: Type N inverse function t90(E)  (temperature from voltage)
+// Three ranges:
+//
+// 1) -3.990 mV ≤ E ≤ 0.000 mV  (-200 °C to 0 °C)
+// t = Σ d_i * E^i   (i = 0..9)
+//
+// 2) 0.000 mV < E ≤ 20.613 mV  (0 °C to 600 °C)
+// t = Σ d_i * E^i   (i = 0..7)
+//
+// 3) 20.613 mV < E ≤ 47.513 mV  (600 °C to 1300 °C)
+// t = Σ d_i * E^i   (i = 0..5)
+//
+// Coefficients below are the official ITS-90 values (E in mV, t in °C).
+// ────────────────────────────────────────────────────────────────────────────
+
+const N_T_OF_E_D_LOW: [f64; 10] = [
+	0.000_000_0e+00,
+	3.843_684_7e+01,
+	1.101_048_5e+00,
+	5.222_931_2e+00,
+	7.206_052_5e+00,
+	5.848_858_6e+00,
+	2.775_491_6e+00,
+	7.707_516_6e-01,
+	1.158_266_5e-01,
+	7.313_886_8e-03,
+];
+
+const N_T_OF_E_D_MID: [f64; 8] = [
+	0.000_00e+00,
+	3.868_96e+01,
+	-1.082_67e+00,
+	4.702_05e-02,
+	-2.121_69e-06,
+	-1.172_72e-04,
+	5.392_80e-06,
+	-7.981_56e-08,
+];
+
+const N_T_OF_E_D_HIGH: [f64; 6] = [
+	1.972_485e+01,
+	3.300_943e+01,
+	-3.915_159e-01,
+	9.855_391e-03,
+	-1.274_371e-04,
+	7.767_022e-07,
+];
+
+const N_E_MIN_MV: f64 = -3.990;
+const N_E_LOW_MAX_MV: f64 = 0.000;
+const N_E_MID_MAX_MV: f64 = 20.613;
+const N_E_MAX_MV: f64 = 47.513;
+
+/// Returns t90(E) in °C for Type N, or an error if E is out of range.
+pub fn convert_voltage_to_temperature(voltage: f64) -> Result<f64, ThermocoupleError> {
+	if !(N_E_MIN_MV..=N_E_MAX_MV).contains(&voltage) {
+		return Err(ThermocoupleError::MillivoltsOutOfRange);
+	}
+
+	if voltage <= N_E_LOW_MAX_MV {
+		Ok(evaluate_power_series(voltage, &N_T_OF_E_D_LOW))
+	} else if voltage <= N_E_MID_MAX_MV {
+		Ok(evaluate_power_series(voltage, &N_T_OF_E_D_MID))
+	} else {
+		Ok(evaluate_power_series(voltage, &N_T_OF_E_D_HIGH))
+	}
+}
@@ -0,0 +1,62 @@
+use crate::temperature::thermocouple::polynomial::evaluate_power_series;
+
+// ────────────────────────────────────────────────────────────────────────────
+// ITS-90: Type R reference function E(t)  (voltage from temperature)
+// Three ranges:
+//
+// A) -50 °C ≤ t ≤ 1064.18 °C
+// E = Σ c_i * t^i  (i = 0..9)
+//
+// B) 1064.18 °C < t ≤ 1664.5 °C
+// E = Σ c_i * t^i  (i = 0..5)
+//
+// C) 1664.5 °C < t ≤ 1768.1 °C
+// E = Σ c_i * t^i  (i = 0..4)
+//
+// Coefficients are from the official tables (units: E in mV, t in °C).
+// ────────────────────────────────────────────────────────────────────────────
+
+const R_E_OF_T_LOW_COEFFICIENTS: [f64; 10] = [
+	0.000_000_000_000e+00,
+	0.528_961_729_765e-02,
+	0.139_166_589_782e-04,
+	-0.238_855_693_017e-07,
+	0.356_916_001_063e-10,
+	-0.462_347_666_298e-13,
+	0.500_777_441_034e-16,
+	-0.373_105_886_191e-19,
+	0.157_716_482_367e-22,
+	-0.281_038_625_251e-26,
+];
+
+const R_E_OF_T_MID_COEFFICIENTS: [f64; 6] = [
+	0.295_157_925_316e+01,
+	-0.252_061_251_332e-02,
+	0.159_564_501_865e-04,
+	-0.764_085_947_576e-08,
+	0.205_305_291_024e-11,
+	-0.293_359_668_173e-15,
+];
+
+const R_E_OF_T_HIGH_COEFFICIENTS: [f64; 5] = [
+	0.152_232_118_209e+03,
+	-0.268_819_888_545e+00,
+	0.171_280_280_471e-03,
+	-0.345_895_706_453e-07,
+	-0.934_633_971_046e-14,
+];
+
+/// Returns E(t) in millivolts for a Type R thermocouple, or None if t is out of range.
+pub fn convert_temperature_to_voltage(temperature: f64) -> Option<f64> {
+	if !(-50.0..=1768.1).contains(&temperature) {
+		return None;
+	}
+
+	if temperature <= 1064.18 {
+		Some(evaluate_power_series(temperature, &R_E_OF_T_LOW_COEFFICIENTS))
+	} else if temperature <= 1664.5 {
+		Some(evaluate_power_series(temperature, &R_E_OF_T_MID_COEFFICIENTS))
+	} else {
+		Some(evaluate_power_series(temperature, &R_E_OF_T_HIGH_COEFFICIENTS))
+	}
+}
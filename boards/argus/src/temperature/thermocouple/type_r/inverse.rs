@@ -0,0 +1,89 @@
+use crate::temperature::thermocouple::error::ThermocoupleError;
+use crate::temperature::thermocouple::polynomial::evaluate_power_series;
+
+// ────────────────────────────────────────────────────────────────────────────
+// ITS-90: Type R inverse function t90(E)  (temperature from voltage)
+// Four ranges:
+//
+// 1) -0.226 mV ≤ E ≤ 1.923 mV  (-50 °C to 250 °C)
+// t = Σ d_i * E^i   (i = 0..10)
+//
+// 2) 1.923 mV < E ≤ 11.361 mV  (250 °C to 1064 °C)
+// t = Σ d_i * E^i   (i = 0..9)
+//
+// 3) 11.361 mV < E ≤ 19.739 mV  (1064 °C to 1664.5 °C)
+// t = Σ d_i * E^i   (i = 0..5)
+//
+// 4) 19.739 mV < E ≤ 21.103 mV  (1664.5 °C to 1768.1 °C)
+// t = Σ d_i * E^i   (i = 0..4)
+//
+// Coefficients below are the official ITS-90 values (E in mV, t in °C).
+// The published ranges 2 and 3 overlap, range 3 is used from its lower bound.
+// ────────────────────────────────────────────────────────────────────────────
+
+const R_T_OF_E_D_LOW: [f64; 11] = [
+	0.000_000_0e+00,
+	1.889_138_0e+02,
+	-9.383_529_0e+01,
+	1.306_861_9e+02,
+	-2.270_358_0e+02,
+	3.514_565_9e+02,
+	-3.895_390_0e+02,
+	2.823_947_1e+02,
+	-1.260_728_1e+02,
+	3.135_361_1e+01,
+	-3.318_776_9e+00,
+];
+
+const R_T_OF_E_D_MID: [f64; 10] = [
+	1.334_584_505e+01,
+	1.472_644_573e+02,
+	-1.844_024_844e+01,
+	4.031_129_726e+00,
+	-6.249_428_360e-01,
+	6.468_412_046e-02,
+	-4.458_750_426e-03,
+	1.994_710_149e-04,
+	-5.313_401_790e-06,
+	6.481_976_217e-08,
+];
+
+const R_T_OF_E_D_HIGH: [f64; 6] = [
+	-8.199_599_416e+01,
+	1.553_962_042e+02,
+	-8.342_197_663e+00,
+	4.279_433_549e-01,
+	-1.191_577_910e-02,
+	1.492_290_091e-04,
+];
+
+const R_T_OF_E_D_TOP: [f64; 5] = [
+	3.406_177_836e+04,
+	-7.023_729_171e+03,
+	5.582_903_813e+02,
+	-1.952_394_635e+01,
+	2.560_740_231e-01,
+];
+
+const R_E_MIN_MV: f64 = -0.226;
+const R_E_LOW_MAX_MV: f64 = 1.923;
+const R_E_MID_MAX_MV: f64 = 11.361;
+const R_E_HIGH_MAX_MV: f64 = 19.739;
+const R_E_MAX_MV: f64 = 21.103;
+
+/// Returns t90(E) in °C for Type R, or an error if E is out of range.
+pub fn convert_voltage_to_temperature(voltage: f64) -> Result<f64, ThermocoupleError> {
+	if !(R_E_MIN_MV..=R_E_MAX_MV).contains(&voltage) {
+		return Err(ThermocoupleError::MillivoltsOutOfRange);
+	}
+
+	if voltage <= R_E_LOW_MAX_MV {
+		Ok(evaluate_power_series(voltage, &R_T_OF_E_D_LOW))
+	} else if voltage <= R_E_MID_MAX_MV {
+		Ok(evaluate_power_series(voltage, &R_T_OF_E_D_MID))
+	} else if voltage <= R_E_HIGH_MAX_MV {
+		Ok(evaluate_power_series(voltage, &R_T_OF_E_D_HIGH))
+	} else {
+		Ok(evaluate_power_series(voltage, &R_T_OF_E_D_TOP))
+	}
+}
@@ -0,0 +1,60 @@
+use crate::temperature::thermocouple::polynomial::evaluate_power_series;
+
+// ────────────────────────────────────────────────────────────────────────────
+// ITS-90: Type S reference function E(t)  (voltage from temperature)
+// Three ranges:
+//
+// A) -50 °C ≤ t ≤ 1064.18 °C
+// E = Σ c_i * t^i  (i = 0..8)
+//
+// B) 1064.18 °C < t ≤ 1664.5 °C
+// E = Σ c_i * t^i  (i = 0..4)
+//
+// C) 1664.5 °C < t ≤ 1768.1 °C
+// E = Σ c_i * t^i  (i = 0..4)
+//
+// Coefficients are from the official tables (units: E in mV, t in °C).
+// ────────────────────────────────────────────────────────────────────────────
+
+const S_E_OF_T_LOW_COEFFICIENTS: [f64; 9] = [
+	0.000_000_000_000e+00,
+	0.540_313_308_631e-02,
+	0.125_934_289_740e-04,
+	-0.232_477_968_689e-07,
+	0.322_028_823_036e-10,
+	-0.331_465_196_389e-13,
+	0.255_744_251_786e-16,
+	-0.125_068_871_393e-19,
+	0.271_443_176_145e-23,
+];
+
+const S_E_OF_T_MID_COEFFICIENTS: [f64; 5] = [
+	0.132_900_444_085e+01,
+	0.334_509_311_344e-02,
+	0.654_805_192_818e-05,
+	-0.164_856_259_209e-08,
+	0.129_989_605_174e-13,
+];
+
+const S_E_OF_T_HIGH_COEFFICIENTS: [f64; 5] = [
+	0.146_628_232_636e+03,
+	-0.258_430_516_752e+00,
+	0.163_693_574_641e-03,
+	-0.330_439_046_987e-07,
+	-0.943_223_690_612e-14,
+];
+
+/// Returns E(t) in millivolts for a Type S thermocouple, or None if t is out of range.
+pub fn convert_temperature_to_voltage(temperature: f64) -> Option<f64> {
+	if !(-50.0..=1768.1).contains(&temperature) {
+		return None;
+	}
+
+	if temperature <= 1064.18 {
+		Some(evaluate_power_series(temperature, &S_E_OF_T_LOW_COEFFICIENTS))
+	} else if temperature <= 1664.5 {
+		Some(evaluate_power_series(temperature, &S_E_OF_T_MID_COEFFICIENTS))
+	} else {
+		Some(evaluate_power_series(temperature, &S_E_OF_T_HIGH_COEFFICIENTS))
+	}
+}
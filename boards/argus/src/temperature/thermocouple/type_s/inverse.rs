@@ -0,0 +1,88 @@
+use crate::temperature::thermocouple::error::ThermocoupleError;
+use crate::temperature::thermocouple::polynomial::evaluate_power_series;
+
+// ────────────────────────────────────────────────────────────────────────────
+// ITS-90: Type S inverse function t90(E)  (temperature from voltage)
+// Four ranges:
+//
+// 1) -0.235 mV ≤ E ≤ 1.874 mV  (-50 °C to 250 °C)
+// t = Σ d_i * E^i   (i = 0..9)
+//
+// 2) 1.874 mV < E ≤ 10.332 mV  (250 °C to 1064 °C)
+// t = Σ d_i * E^i   (i = 0..9)
+//
+// 3) 10.332 mV < E ≤ 17.536 mV  (1064 °C to 1664.5 °C)
+// t = Σ d_i * E^i   (i = 0..5)
+//
+// 4) 17.536 mV < E ≤ 18.693 mV  (1664.5 °C to 1768.1 °C)
+// t = Σ d_i * E^i   (i = 0..4)
+//
+// Coefficients below are the official ITS-90 values (E in mV, t in °C).
+// The published ranges 2 and 3 overlap, range 3 is used from its lower bound.
+// ────────────────────────────────────────────────────────────────────────────
+
+const S_T_OF_E_D_LOW: [f64; 10] = [
+	0.000_000_00e+00,
+	1.849_494_60e+02,
+	-8.005_040_62e+01,
+	1.022_374_30e+02,
+	-1.522_485_92e+02,
+	1.888_213_43e+02,
+	-1.590_859_41e+02,
+	8.230_278_80e+01,
+	-2.341_819_44e+01,
+	2.797_862_60e+00,
+];
+
+const S_T_OF_E_D_MID: [f64; 10] = [
+	1.291_507_177e+01,
+	1.466_298_863e+02,
+	-1.534_713_402e+01,
+	3.145_945_973e+00,
+	-4.163_257_839e-01,
+	3.187_963_771e-02,
+	-1.291_637_500e-03,
+	2.183_475_087e-05,
+	-1.447_379_511e-07,
+	8.211_272_125e-09,
+];
+
+const S_T_OF_E_D_HIGH: [f64; 6] = [
+	-8.087_801_117e+01,
+	1.621_573_104e+02,
+	-8.536_869_453e+00,
+	4.719_686_976e-01,
+	-1.441_693_666e-02,
+	2.081_618_890e-04,
+];
+
+const S_T_OF_E_D_TOP: [f64; 5] = [
+	5.333_875_126e+04,
+	-1.235_892_298e+04,
+	1.092_657_613e+03,
+	-4.265_693_686e+01,
+	6.247_205_420e-01,
+];
+
+const S_E_MIN_MV: f64 = -0.235;
+const S_E_LOW_MAX_MV: f64 = 1.874;
+const S_E_MID_MAX_MV: f64 = 10.332;
+const S_E_HIGH_MAX_MV: f64 = 17.536;
+const S_E_MAX_MV: f64 = 18.693;
+
+/// Returns t90(E) in °C for Type S, or an error if E is out of range.
+pub fn convert_voltage_to_temperature(voltage: f64) -> Result<f64, ThermocoupleError> {
+	if !(S_E_MIN_MV..=S_E_MAX_MV).contains(&voltage) {
+		return Err(ThermocoupleError::MillivoltsOutOfRange);
+	}
+
+	if voltage <= S_E_LOW_MAX_MV {
+		Ok(evaluate_power_series(voltage, &S_T_OF_E_D_LOW))
+	} else if voltage <= S_E_MID_MAX_MV {
+		Ok(evaluate_power_series(voltage, &S_T_OF_E_D_MID))
+	} else if voltage <= S_E_HIGH_MAX_MV {
+		Ok(evaluate_power_series(voltage, &S_T_OF_E_D_HIGH))
+	} else {
+		Ok(evaluate_power_series(voltage, &S_T_OF_E_D_TOP))
+	}
+}
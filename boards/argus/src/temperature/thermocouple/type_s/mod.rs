@@ -0,0 +1,5 @@
+pub mod forward;
+pub mod inverse;
+
+pub use forward::*;
+pub use inverse::*;
@@ -0,0 +1,57 @@
+use crate::temperature::thermocouple::polynomial::evaluate_power_series;
+
+// ────────────────────────────────────────────────────────────────────────────
+// ITS-90: Type T reference function E(t)  (voltage from temperature)
+// Two ranges:
+//
+// A) -270 °C ≤ t ≤ 0 °C
+// E = Σ c_i * t^i  (i = 0..14)
+//
+// B) 0 °C < t ≤ 400 °C
+// E = Σ c_i * t^i  (i = 0..8)
+//
+// Coefficients are from the official tables (units: E in mV, t in °C).
+// ────────────────────────────────────────────────────────────────────────────
+
+const T_E_OF_T_NEG_COEFFICIENTS: [f64; 15] = [
+	0.000_000_000_000e+00,
+	0.387_481_063_640e-01,
+	0.441_944_343_470e-04,
+	0.118_443_231_050e-06,
+	0.200_329_735_540e-07,
+	0.901_380_195_590e-09,
+	0.226_511_565_930e-10,
+	0.360_711_542_050e-12,
+	0.384_939_398_830e-14,
+	0.282_135_219_250e-16,
+	0.142_515_947_790e-18,
+	0.487_686_622_860e-21,
+	0.107_955_392_700e-23,
+	0.139_450_270_620e-26,
+	0.797_951_539_270e-30,
+];
+
+const T_E_OF_T_POS_COEFFICIENTS: [f64; 9] = [
+	0.000_000_000_000e+00,
+	0.387_481_063_640e-01,
+	0.332_922_278_800e-04,
+	0.206_182_434_040e-06,
+	-0.218_822_568_460e-08,
+	0.109_968_809_280e-10,
+	-0.308_157_587_720e-13,
+	0.454_791_352_900e-16,
+	-0.275_129_016_730e-19,
+];
+
+/// Returns E(t) in millivolts for a Type T thermocouple, or None if t is out of range.
+pub fn convert_temperature_to_voltage(temperature: f64) -> Option<f64> {
+	if !(-270.0..=400.0).contains(&temperature) {
+		return None;
+	}
+
+	if temperature <= 0.0 {
+		Some(evaluate_power_series(temperature, &T_E_OF_T_NEG_COEFFICIENTS))
+	} else {
+		Some(evaluate_power_series(temperature, &T_E_OF_T_POS_COEFFICIENTS))
+	}
+}
@@ -0,0 +1,53 @@
+use crate::temperature::thermocouple::error::ThermocoupleError;
+use crate::temperature::thermocouple::polynomial::evaluate_power_series;
+
+// ────────────────────────────────────────────────────────────────────────────
+// ITS-90: Type T inverse function t90(E)  (temperature from voltage)
+// Two ranges:
+//
+// 1) -5.603 mV ≤ E ≤ 0.000 mV  (-200 °C to 0 °C)
+// t = Σ d_i * E^i   (i = 0..7)
+//
+// 2) 0.000 mV < E ≤ 20.872 mV  (0 °C to 400 °C)
+// t = Σ d_i * E^i   (i = 0..6)
+//
+// Coefficients below are the official ITS-90 values (E in mV, t in °C).
+// ────────────────────────────────────────────────────────────────────────────
+
+const T_T_OF_E_D_NEG: [f64; 8] = [
+	0.000_000_0e+00,
+	2.594_919_2e+01,
+	-2.131_696_7e-01,
+	7.901_869_2e-01,
+	4.252_777_7e-01,
+	1.330_447_3e-01,
+	2.024_144_6e-02,
+	1.266_817_1e-03,
+];
+
+const T_T_OF_E_D_POS: [f64; 7] = [
+	0.000_000e+00,
+	2.592_800e+01,
+	-7.602_961e-01,
+	4.637_791e-02,
+	-2.165_394e-03,
+	6.048_144e-05,
+	-7.293_422e-07,
+];
+
+const T_E_MIN_MV: f64 = -5.603;
+const T_E_NEG_MAX_MV: f64 = 0.000;
+const T_E_MAX_MV: f64 = 20.872;
+
+/// Returns t90(E) in °C for Type T, or an error if E is out of range.
+pub fn convert_voltage_to_temperature(voltage: f64) -> Result<f64, ThermocoupleError> {
+	if !(T_E_MIN_MV..=T_E_MAX_MV).contains(&voltage) {
+		return Err(ThermocoupleError::MillivoltsOutOfRange);
+	}
+
+	if voltage <= T_E_NEG_MAX_MV {
+		Ok(evaluate_power_series(voltage, &T_T_OF_E_D_NEG))
+	} else {
+		Ok(evaluate_power_series(voltage, &T_T_OF_E_D_POS))
+	}
+}
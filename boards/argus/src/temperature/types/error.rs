@@ -3,8 +3,10 @@ use derive_more::From;
 use uor_peripherals::serial::peripheral::UsartError;
 
 use crate::adc::service::AdcError;
+use crate::calibration_protocol::session::SessionError;
 use crate::sd::types::SdCardError;
-use crate::temperature::thermocouple::type_k::ThermocoupleError;
+use crate::sensor::types::SensorServiceError;
+use crate::temperature::thermocouple::ThermocoupleError;
 
 #[derive(Debug, Format, From)]
 pub enum TemperatureServiceError {
@@ -13,4 +15,24 @@ pub enum TemperatureServiceError {
 	SdCardError(SdCardError),
 	ThermocoupleError(ThermocoupleError),
 	FormatError,
+	OpenCircuit, // The sensor was found to be disconnected during the last open-circuit check
+	AdcUnavailable, // The ADC is missing or faulted, see AdcService::detect_devices
+	CalibrationSessionError(SessionError), // The calibration command can't be carried out, it is answered with a CalibrationError
+}
+
+impl SensorServiceError for TemperatureServiceError {
+	fn format_error() -> Self {
+		TemperatureServiceError::FormatError
+	}
+
+	fn open_circuit() -> Self {
+		TemperatureServiceError::OpenCircuit
+	}
+
+	fn session_error(&self) -> Option<SessionError> {
+		match self {
+			TemperatureServiceError::CalibrationSessionError(error) => Some(*error),
+			_ => None,
+		}
+	}
 }
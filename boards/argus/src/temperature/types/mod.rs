@@ -1,9 +1,15 @@
 pub mod error;
 pub mod queue;
+pub mod rtd_configuration;
+pub mod sensor_kind;
 pub mod thermocouple_channel;
 pub mod thermocouple_reading;
+pub mod thermocouple_type;
 
 pub use error::*;
 pub use queue::*;
+pub use rtd_configuration::*;
+pub use sensor_kind::*;
 pub use thermocouple_channel::*;
 pub use thermocouple_reading::*;
+pub use thermocouple_type::*;
@@ -0,0 +1,74 @@
+use crate::adc::driver::types::{AcquisitionProfile, AnalogChannel, Gain, IdacMagnitude, IdacOutput, ReferenceRange};
+
+/// How the RTD is wired to the ADC, which determines how the lead resistance is handled
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RtdWiring {
+	// Lead resistance is in series with the RTD and can only be subtracted as a known value
+	TwoWire,
+	// A second matched IDAC drives the compensation lead so the lead resistances cancel out
+	ThreeWire,
+	// Sense leads carry no current so the lead resistance does not affect the reading
+	FourWire,
+}
+
+/// Where the voltage across the reference resistor is taken from
+#[derive(Debug, Clone, Copy)]
+pub enum RtdReference {
+	// Reference resistor is connected to the ADC reference inputs, so the conversion is ratiometric in hardware
+	ReferenceInputs(ReferenceRange),
+	// Reference resistor voltage is measured on this input pair and divided out in firmware
+	MeasuredPair(AnalogChannel, AnalogChannel),
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct RtdConfiguration {
+	pub wiring: RtdWiring,
+
+	// Input pair measuring the voltage across the RTD
+	pub sense_pair: (AnalogChannel, AnalogChannel),
+	pub reference: RtdReference,
+	pub reference_resistance: f32, // Ohms
+
+	// IDAC1 output and excitation current. None when the excitation is provided by the board itself
+	pub excitation: Option<(IdacOutput, IdacMagnitude)>,
+
+	// IDAC2 output driving the compensation lead in 3-wire mode. Uses the same magnitude as IDAC1
+	pub compensation_excitation: Option<IdacOutput>,
+
+	// Resistance of a single lead, subtracted twice in 2-wire mode
+	pub lead_resistance: f32, // Ohms
+
+	pub gain: Gain,
+}
+
+impl RtdConfiguration {
+	/// Acquisition profile for the RTD inputs, overriding the gain and, when wired to the reference inputs, the reference of the base profile
+	pub fn acquisition_profile(
+		&self,
+		base: AcquisitionProfile,
+	) -> AcquisitionProfile {
+		let reference_range = match self.reference {
+			RtdReference::ReferenceInputs(reference_range) => reference_range,
+			RtdReference::MeasuredPair(_, _) => base.reference_range,
+		};
+		AcquisitionProfile {
+			gain: self.gain,
+			reference_range,
+			..base
+		}
+	}
+
+	/// Computes the RTD resistance from the ratio of the RTD voltage to the reference resistor voltage.
+	/// Both voltages are produced by the same excitation current, so drift in the excitation cancels out.
+	pub fn compute_resistance(
+		&self,
+		ratio: f32,
+	) -> f32 {
+		match self.wiring {
+			RtdWiring::TwoWire => ratio * self.reference_resistance - 2.0 * self.lead_resistance,
+			// Both IDAC currents return through the reference resistor, so it sees twice the RTD current
+			RtdWiring::ThreeWire => ratio * 2.0 * self.reference_resistance,
+			RtdWiring::FourWire => ratio * self.reference_resistance,
+		}
+	}
+}
@@ -0,0 +1,28 @@
+use uor_utils::messages::argus::envelope::envelope::Message as EnvelopeMessage;
+
+use crate::adc::types::AdcDevice;
+use crate::sensor::types::{ChannelKind, SensorKind};
+use crate::temperature::types::{ThermocoupleChannel, ThermocoupleReading};
+
+// Marker for the thermocouples in the shared sensor pipeline, see SensorKind
+pub struct Thermocouples;
+
+impl SensorKind for Thermocouples {
+	type Channel = ThermocoupleChannel;
+	type Reading = ThermocoupleReading;
+
+	const CHANNEL_KIND: ChannelKind = ChannelKind::Thermocouple;
+	const FILE_PREFIX: &'static str = "T";
+
+	fn adc_device(reading: &ThermocoupleReading) -> AdcDevice {
+		reading.adc_device
+	}
+
+	fn channel_index(reading: &ThermocoupleReading) -> usize {
+		reading.thermocouple_channel as usize
+	}
+
+	fn to_envelope_message(reading: &ThermocoupleReading) -> EnvelopeMessage {
+		EnvelopeMessage::ThermocoupleReading(reading.to_protobuf())
+	}
+}
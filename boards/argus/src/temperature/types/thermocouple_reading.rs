@@ -8,7 +8,7 @@ use uor_utils::messages::argus::temperature::thermocouple_reading::ThermocoupleR
 use crate::adc::types::AdcDevice;
 use crate::sd::config::MAX_LINE_LENGTH;
 use crate::sd::types::Line;
-use crate::temperature::types::ThermocoupleChannel;
+use crate::temperature::types::{ThermocoupleChannel, ThermocoupleType};
 
 // Represents a single temperature reading from a thermocouple channel
 #[derive(Debug, Clone, Copy, Format, Serialize, Deserialize)]
@@ -22,6 +22,9 @@ pub struct ThermocoupleReading {
 	// Thermocouple channel from which the reading was taken
 	pub thermocouple_channel: ThermocoupleChannel,
 
+	// Type of the thermocouple wired to the channel, which selects the ITS-90 conversion
+	pub thermocouple_type: ThermocoupleType,
+
 	// Milliseconds since the board's epoch when the reading was recorded
 	pub recorded_at: u64,
 
@@ -31,11 +34,23 @@ pub struct ThermocoupleReading {
 	// Cold-junction-compensated temperature of the thermocouple in degrees Celsius
 	pub compensated_temperature: f64,
 
-	// Uncompensated temperature of the thermocouple in degrees Celsius
+	// Uncompensated temperature of the thermocouple in degrees Celsius, NaN when the voltage is outside the range of its type
 	pub uncompensated_temperature: f64,
 
 	// Temperature of the cold junction in degrees Celsius
 	pub cold_junction_temperature: f32,
+
+	// Number of conversions averaged into the voltage, after the outliers were rejected
+	pub sample_count: u8,
+
+	// Standard deviation of the averaged conversions in millivolts
+	pub voltage_standard_deviation: f32,
+
+	// Lowest of the averaged conversions in millivolts
+	pub voltage_min: f32,
+
+	// Highest of the averaged conversions in millivolts
+	pub voltage_max: f32,
 }
 
 impl SerializeCSV<MAX_LINE_LENGTH> for ThermocoupleReading {
@@ -44,11 +59,16 @@ impl SerializeCSV<MAX_LINE_LENGTH> for ThermocoupleReading {
 			"Local Session #,\
 			ADC Device,\
 			Thermocouple Channel,\
+			Thermocouple Type,\
 			Timestamp (ms),\
 			Voltage (mV),\
-			Compensated Temperature (C),\
-			Uncompensated Temperature (C),\
-			Cold Junction Temperature (C)",
+			Compensated Temp (C),\
+			Uncompensated Temp (C),\
+			Cold Junction Temp (C),\
+			Sample Count,\
+			Voltage Standard Deviation (mV),\
+			Voltage Min (mV),\
+			Voltage Max (mV)",
 		)
 		.unwrap()
 	}
@@ -66,6 +86,11 @@ impl ThermocoupleReading {
 			compensated_temperature: self.compensated_temperature,
 			uncompensated_temperature: self.uncompensated_temperature,
 			cold_junction_temperature: self.cold_junction_temperature,
+			sample_count: self.sample_count as u32,
+			voltage_standard_deviation: self.voltage_standard_deviation,
+			voltage_min: self.voltage_min,
+			voltage_max: self.voltage_max,
+			thermocouple_type: self.thermocouple_type.to_protobuf() as i32,
 		}
 	}
 }
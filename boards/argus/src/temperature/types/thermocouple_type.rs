@@ -0,0 +1,81 @@
+use defmt::Format;
+use serde::{Deserialize, Serialize};
+use strum::EnumCount;
+use uor_utils::messages::argus::temperature::thermocouple_type::ThermocoupleType as ThermocoupleTypeProtobuf;
+
+use crate::temperature::thermocouple::{type_b, type_e, type_j, type_k, type_n, type_r, type_s, type_t, ThermocoupleError};
+
+// Standard thermocouple types, each converted with its ITS-90 reference functions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Format, Serialize, Deserialize, EnumCount, Default)]
+pub enum ThermocoupleType {
+	B,
+	E,
+	J,
+	#[default]
+	K,
+	N,
+	R,
+	S,
+	T,
+}
+
+impl ThermocoupleType {
+	/// Returns E(t) in millivolts for this thermocouple type, or None if t is out of range.
+	pub fn convert_temperature_to_voltage(
+		&self,
+		temperature: f64,
+	) -> Option<f64> {
+		match self {
+			ThermocoupleType::B => type_b::convert_temperature_to_voltage(temperature),
+			ThermocoupleType::E => type_e::convert_temperature_to_voltage(temperature),
+			ThermocoupleType::J => type_j::convert_temperature_to_voltage(temperature),
+			ThermocoupleType::K => type_k::convert_temperature_to_voltage(temperature),
+			ThermocoupleType::N => type_n::convert_temperature_to_voltage(temperature),
+			ThermocoupleType::R => type_r::convert_temperature_to_voltage(temperature),
+			ThermocoupleType::S => type_s::convert_temperature_to_voltage(temperature),
+			ThermocoupleType::T => type_t::convert_temperature_to_voltage(temperature),
+		}
+	}
+
+	/// Returns t90(E) in °C for this thermocouple type, or an error if E is out of range.
+	pub fn convert_voltage_to_temperature(
+		&self,
+		voltage: f64,
+	) -> Result<f64, ThermocoupleError> {
+		match self {
+			ThermocoupleType::B => type_b::convert_voltage_to_temperature(voltage),
+			ThermocoupleType::E => type_e::convert_voltage_to_temperature(voltage),
+			ThermocoupleType::J => type_j::convert_voltage_to_temperature(voltage),
+			ThermocoupleType::K => type_k::convert_voltage_to_temperature(voltage),
+			ThermocoupleType::N => type_n::convert_voltage_to_temperature(voltage),
+			ThermocoupleType::R => type_r::convert_voltage_to_temperature(voltage),
+			ThermocoupleType::S => type_s::convert_voltage_to_temperature(voltage),
+			ThermocoupleType::T => type_t::convert_voltage_to_temperature(voltage),
+		}
+	}
+
+	pub fn convert_voltage_to_temperature_with_cold_junction_compensation(
+		&self,
+		measured_voltage: f64,
+		cold_junction_temperature: f64,
+	) -> Result<f64, ThermocoupleError> {
+		let cj_mv = self
+			.convert_temperature_to_voltage(cold_junction_temperature)
+			.ok_or(ThermocoupleError::ColdJunctionTemperatureOutOfRange)?;
+		let compensated_mv = measured_voltage + cj_mv;
+		self.convert_voltage_to_temperature(compensated_mv)
+	}
+
+	pub fn to_protobuf(&self) -> ThermocoupleTypeProtobuf {
+		match self {
+			ThermocoupleType::B => ThermocoupleTypeProtobuf::TypeB,
+			ThermocoupleType::E => ThermocoupleTypeProtobuf::TypeE,
+			ThermocoupleType::J => ThermocoupleTypeProtobuf::TypeJ,
+			ThermocoupleType::K => ThermocoupleTypeProtobuf::TypeK,
+			ThermocoupleType::N => ThermocoupleTypeProtobuf::TypeN,
+			ThermocoupleType::R => ThermocoupleTypeProtobuf::TypeR,
+			ThermocoupleType::S => ThermocoupleTypeProtobuf::TypeS,
+			ThermocoupleType::T => ThermocoupleTypeProtobuf::TypeT,
+		}
+	}
+}
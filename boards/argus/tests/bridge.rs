@@ -0,0 +1,114 @@
+#![feature(impl_trait_in_assoc_type)]
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+	// The voltage ratios below are computed from the forward bridge equations for 1000 microstrain in tension and a gauge factor of 2,
+	// so each conversion must give the strain back
+
+	use argus::strain::bridge::{
+		compute_shunt_voltage_ratio, convert_voltage_ratio_to_strain_full_bridge, convert_voltage_ratio_to_strain_half_bridge_bending,
+		convert_voltage_ratio_to_strain_half_bridge_poisson, convert_voltage_ratio_to_strain_quarter_bridge,
+	};
+	use argus::strain::types::{BridgeConfiguration, BridgeExcitation, BridgeType};
+	use defmt_rtt as _;
+
+	const GAUGE_FACTOR: f64 = 2.0;
+	const STRAIN: f64 = 0.001;
+	const TOLERANCE: f64 = 1e-9;
+
+	fn assert_close(
+		actual: f64,
+		expected: f64,
+		tolerance: f64,
+	) {
+		assert!((actual - expected).abs() <= tolerance);
+	}
+
+	fn quarter_bridge(lead_resistance: f64) -> BridgeConfiguration {
+		BridgeConfiguration {
+			bridge_type: BridgeType::Quarter,
+			gauge_factor: GAUGE_FACTOR,
+			excitation: BridgeExcitation::Fixed(5.0),
+			poisson_ratio: 0.3,
+			gauge_resistance: 350.0,
+			lead_resistance,
+		}
+	}
+
+	#[test]
+	fn quarter_bridge_corrects_its_nonlinearity() {
+		// Vr = GF * e / 4 / (1 + GF * e / 2), the linear approximation GF * e / 4 would be off by 0.1%
+		let voltage_ratio = GAUGE_FACTOR * STRAIN / 4.0 / (1.0 + GAUGE_FACTOR * STRAIN / 2.0);
+		assert_close(
+			convert_voltage_ratio_to_strain_quarter_bridge(voltage_ratio, GAUGE_FACTOR, 0.0),
+			STRAIN,
+			TOLERANCE,
+		);
+
+		let compression_voltage_ratio = -GAUGE_FACTOR * STRAIN / 4.0 / (1.0 - GAUGE_FACTOR * STRAIN / 2.0);
+		assert_close(
+			convert_voltage_ratio_to_strain_quarter_bridge(compression_voltage_ratio, GAUGE_FACTOR, 0.0),
+			-STRAIN,
+			TOLERANCE,
+		);
+	}
+
+	#[test]
+	fn half_bridges_convert_back_to_the_strain() {
+		let bending_voltage_ratio = GAUGE_FACTOR * STRAIN / 2.0;
+		assert_close(
+			convert_voltage_ratio_to_strain_half_bridge_bending(bending_voltage_ratio, GAUGE_FACTOR, 0.0),
+			STRAIN,
+			TOLERANCE,
+		);
+
+		// Vr = GF * e * (1 + v) / (4 - 2 * GF * e * (v - 1))
+		let poisson_ratio = 0.3;
+		let poisson_voltage_ratio = GAUGE_FACTOR * STRAIN * (1.0 + poisson_ratio) / (4.0 - 2.0 * GAUGE_FACTOR * STRAIN * (poisson_ratio - 1.0));
+		assert_close(
+			convert_voltage_ratio_to_strain_half_bridge_poisson(poisson_voltage_ratio, GAUGE_FACTOR, poisson_ratio, 0.0),
+			STRAIN,
+			TOLERANCE,
+		);
+	}
+
+	#[test]
+	fn full_bridge_converts_back_to_the_strain() {
+		assert_close(
+			convert_voltage_ratio_to_strain_full_bridge(GAUGE_FACTOR * STRAIN, GAUGE_FACTOR),
+			STRAIN,
+			TOLERANCE,
+		);
+	}
+
+	#[test]
+	fn lead_resistance_is_corrected() {
+		let voltage_ratio = GAUGE_FACTOR * STRAIN / 2.0;
+		assert_close(
+			convert_voltage_ratio_to_strain_half_bridge_bending(voltage_ratio, GAUGE_FACTOR, 0.01),
+			STRAIN * 1.01,
+			TOLERANCE,
+		);
+	}
+
+	#[test]
+	fn bridge_output_converts_to_microstrain() {
+		// 5 V excitation, the output is in millivolts
+		let voltage = GAUGE_FACTOR * STRAIN / 4.0 / (1.0 + GAUGE_FACTOR * STRAIN / 2.0) * 5000.0;
+		assert_close(quarter_bridge(0.0).convert_voltage_to_microstrain(voltage, 5.0), 1000.0, 1e-6);
+	}
+
+	#[test]
+	fn shunt_simulates_the_textbook_strain() {
+		// e = -Rg / (GF * (Rg + Rs)), a 175 kΩ shunt across a 350 Ω gauge simulates about -998 microstrain
+		let expected_microstrain = -350.0 / (GAUGE_FACTOR * (350.0 + 175_000.0)) * 1_000_000.0;
+		assert!(compute_shunt_voltage_ratio(350.0, 175_000.0) < 0.0);
+		assert_close(quarter_bridge(0.0).compute_shunt_microstrain(175_000.0), expected_microstrain, 1e-6);
+
+		// The lead resistance desensitizes the measurement, not the strain the shunt simulates
+		assert_close(quarter_bridge(1.0).compute_shunt_microstrain(175_000.0), expected_microstrain, 1e-6);
+	}
+}
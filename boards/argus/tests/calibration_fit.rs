@@ -0,0 +1,135 @@
+#![feature(impl_trait_in_assoc_type)]
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+	use argus::calibration_model::config::MAX_POLYNOMIAL_ORDER;
+	use argus::calibration_model::fit::{build_lookup_table, compute_r_squared, fit_polynomial, solve_linear_system};
+	use argus::calibration_model::types::CalibrationModel;
+	use defmt_rtt as _;
+
+	fn assert_close(
+		actual: f64,
+		expected: f64,
+		tolerance: f64,
+	) {
+		assert!((actual - expected).abs() <= tolerance);
+	}
+
+	#[test]
+	fn linear_system_is_solved() {
+		// 2x + y = 5, x - y = 1
+		let solution = solve_linear_system([[2.0, 1.0], [1.0, -1.0]], [5.0, 1.0], 2).unwrap();
+		assert_close(solution[0], 2.0, 1e-12);
+		assert_close(solution[1], 1.0, 1e-12);
+	}
+
+	#[test]
+	fn singular_linear_system_is_rejected() {
+		assert_eq!(solve_linear_system([[1.0, 2.0], [2.0, 4.0]], [3.0, 6.0], 2), None);
+	}
+
+	#[test]
+	fn line_is_fitted_exactly() {
+		let points = [(0.0, 1.0), (1.0, 3.0), (2.0, 5.0), (3.0, 7.0)];
+		let coefficients = fit_polynomial(&points, 1).unwrap();
+		assert_eq!(coefficients.len(), 2);
+		assert_close(coefficients[0], 1.0, 1e-9);
+		assert_close(coefficients[1], 2.0, 1e-9);
+	}
+
+	#[test]
+	fn noisy_line_is_the_least_squares_fit() {
+		// Slope = Sxy / Sxx = 4.8 / 5 and offset = mean(y) - slope * mean(x) = 1.5 - 0.96 * 1.5
+		let points = [(0.0, 0.1), (1.0, 0.9), (2.0, 2.1), (3.0, 2.9)];
+		let coefficients = fit_polynomial(&points, 1).unwrap();
+		assert_close(coefficients[0], 0.06, 1e-9);
+		assert_close(coefficients[1], 0.96, 1e-9);
+	}
+
+	#[test]
+	fn cubic_far_from_zero_is_fitted() {
+		// Readings around 2000 raised to the third power are what the centering and scaling of fit_polynomial are for
+		let cubic = |x: f64| 0.5 + 1e-3 * x - 2e-6 * x * x + 3e-10 * x * x * x;
+		let mut points = [(0.0, 0.0); 8];
+		for (index, point) in points.iter_mut().enumerate() {
+			let measured = 2000.0 + 50.0 * index as f64;
+			*point = (measured, cubic(measured));
+		}
+
+		let coefficients = fit_polynomial(&points, 3).unwrap();
+		let model = CalibrationModel::Polynomial { coefficients };
+		for measured in [2000.0, 2125.0, 2350.0] {
+			assert_close(model.apply(measured), cubic(measured), 1e-6);
+		}
+	}
+
+	#[test]
+	fn undetermined_polynomials_are_rejected() {
+		// Fewer points than coefficients
+		assert_eq!(fit_polynomial(&[(0.0, 0.0), (1.0, 1.0)], 2), None);
+		// Every point at the same measured value
+		assert_eq!(fit_polynomial(&[(1.0, 0.0), (1.0, 1.0), (1.0, 2.0)], 1), None);
+		// Higher order than a calibration can hold
+		let points = [(0.0, 0.0); MAX_POLYNOMIAL_ORDER + 3];
+		assert_eq!(fit_polynomial(&points, MAX_POLYNOMIAL_ORDER + 1), None);
+	}
+
+	#[test]
+	fn lookup_table_is_sorted_by_measured_value() {
+		let table = build_lookup_table(&[(2.0, 20.0), (0.0, 0.0), (1.0, 15.0)]).unwrap();
+		assert_eq!(table.as_slice(), &[(0.0, 0.0), (1.0, 15.0), (2.0, 20.0)]);
+	}
+
+	#[test]
+	fn lookup_table_with_a_repeated_measured_value_is_rejected() {
+		assert_eq!(build_lookup_table(&[(0.0, 0.0), (1.0, 10.0), (1.0, 12.0)]), None);
+	}
+
+	#[test]
+	fn lookup_table_interpolates_and_extrapolates() {
+		let points = build_lookup_table(&[(0.0, 0.0), (1.0, 15.0), (2.0, 20.0)]).unwrap();
+		let model = CalibrationModel::LookupTable { points };
+		assert_close(model.apply(1.0), 15.0, 1e-12);
+		assert_close(model.apply(0.5), 7.5, 1e-12);
+		assert_close(model.apply(1.5), 17.5, 1e-12);
+		// Past the ends of the table the first and last segments are extended
+		assert_close(model.apply(-1.0), -15.0, 1e-12);
+		assert_close(model.apply(3.0), 25.0, 1e-12);
+	}
+
+	#[test]
+	fn r_squared_compares_the_residuals_to_the_spread() {
+		// Residual sum of squares 0.1 over a total sum of squares of 5
+		let pairs = [(1.0, 1.1), (2.0, 1.9), (3.0, 3.2), (4.0, 3.8)];
+		assert_close(compute_r_squared(pairs.iter().copied()).unwrap(), 0.98, 1e-12);
+	}
+
+	#[test]
+	fn r_squared_is_undefined_without_spread() {
+		assert_eq!(compute_r_squared([(1.0, 1.0)].iter().copied()), None);
+		assert_eq!(compute_r_squared([(2.0, 1.0), (2.0, 3.0)].iter().copied()), None);
+	}
+
+	#[test]
+	fn r_squared_of_a_model_is_over_its_points() {
+		let points = [(0.0, 0.1), (1.0, 0.9), (2.0, 2.1), (3.0, 2.9)];
+		let model = CalibrationModel::Linear { scale: 1.0, offset: 0.0 };
+		// Residual sum of squares 0.04 over a total sum of squares of 4.64
+		assert_close(model.r_squared(&points).unwrap(), 1.0 - 0.04 / 4.64, 1e-12);
+	}
+
+	#[test]
+	fn r_squared_is_skipped_for_fits_through_every_point() {
+		let line = CalibrationModel::Linear { scale: 2.0, offset: 1.0 };
+		assert_eq!(line.r_squared(&[(0.0, 1.0), (1.0, 3.0)]), None);
+
+		let points = [(0.0, 0.0), (1.0, 15.0), (2.0, 20.0)];
+		let table = CalibrationModel::LookupTable {
+			points: build_lookup_table(&points).unwrap(),
+		};
+		assert_eq!(table.r_squared(&points), None);
+	}
+}
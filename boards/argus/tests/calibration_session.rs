@@ -0,0 +1,184 @@
+#![feature(impl_trait_in_assoc_type)]
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+	use argus::adc::types::AdcDevice;
+	use argus::calibration_model::config::MAX_POLYNOMIAL_ORDER;
+	use argus::calibration_model::types::{CalibrationModel, LowQualityFitAction, StableReading};
+	use argus::calibration_protocol::config::MAX_SESSION_DATA_POINTS;
+	use argus::calibration_protocol::session::{parse_channel, CalibrationSession, SessionError};
+	use argus::strain::types::StrainChannel;
+	use defmt_rtt as _;
+	use uor_utils::messages::argus::calibration::calibration_model::CalibrationModel as CalibrationModelProtobuf;
+	use uor_utils::messages::argus::calibration::calibration_response::calibration_response::Response;
+	use uor_utils::messages::argus::calibration::calibration_response::CalibrationErrorCode;
+
+	fn session(model: CalibrationModelProtobuf) -> CalibrationSession<StrainChannel> {
+		CalibrationSession::new(AdcDevice::AdcDevice1, StrainChannel::Channel1, model, 2).unwrap()
+	}
+
+	fn reading(mean: f64) -> StableReading {
+		StableReading {
+			mean,
+			standard_deviation: 0.02,
+			uncertainty: 0.01,
+			reading_count: 4,
+		}
+	}
+
+	fn error_code<T>(result: Result<T, SessionError>) -> Option<CalibrationErrorCode> {
+		result.err().map(|error| error.code)
+	}
+
+	// Captures (measured, expected) points in order, submitting each reference value before its reading
+	fn capture(
+		session: &mut CalibrationSession<StrainChannel>,
+		points: &[(f64, f64)],
+	) {
+		for &(measured, expected) in points {
+			session.submit_reference_value(expected);
+			session.add_data_point(reading(measured)).unwrap();
+		}
+	}
+
+	#[test]
+	fn polynomial_order_is_checked_for_polynomials_only() {
+		for order in [0, MAX_POLYNOMIAL_ORDER as u32 + 1] {
+			let result = CalibrationSession::new(
+				AdcDevice::AdcDevice1,
+				StrainChannel::Channel1,
+				CalibrationModelProtobuf::Polynomial,
+				order,
+			);
+			assert_eq!(error_code(result), Some(CalibrationErrorCode::UnsupportedModel));
+		}
+
+		let line = CalibrationSession::new(AdcDevice::AdcDevice1, StrainChannel::Channel1, CalibrationModelProtobuf::Linear, 0).unwrap();
+		assert_eq!(line.polynomial_order, 1);
+	}
+
+	#[test]
+	fn point_needs_a_reference_value() {
+		let mut session = session(CalibrationModelProtobuf::Linear);
+		assert_eq!(error_code(session.next_reference_value()), Some(CalibrationErrorCode::NoReferenceValue));
+		assert_eq!(
+			error_code(session.add_data_point(reading(1.0))),
+			Some(CalibrationErrorCode::NoReferenceValue)
+		);
+		assert!(session.data_points.is_empty());
+	}
+
+	#[test]
+	fn each_reference_value_is_used_for_a_single_point() {
+		let mut session = session(CalibrationModelProtobuf::Linear);
+		session.submit_reference_value(10.0);
+		assert_eq!(session.next_reference_value().unwrap(), 10.0);
+
+		let response = session.add_data_point(reading(1.0)).unwrap();
+		match response.response {
+			Some(Response::PointCaptured(point)) => {
+				assert_eq!(point.index, 0);
+				assert_eq!(point.expected, 10.0);
+				assert_eq!(point.measured, 1.0);
+				assert_eq!(point.uncertainty, 0.01);
+				assert_eq!(point.reading_count, 4);
+			}
+			_ => panic!("Expected a PointCaptured response"),
+		}
+		assert_eq!(session.points().as_slice(), &[(1.0, 10.0)]);
+		assert_eq!(session.uncertainties().as_slice(), &[0.01]);
+		assert_eq!(error_code(session.next_reference_value()), Some(CalibrationErrorCode::NoReferenceValue));
+	}
+
+	#[test]
+	fn full_session_refuses_more_points() {
+		let mut session = session(CalibrationModelProtobuf::LookupTable);
+		for index in 0..MAX_SESSION_DATA_POINTS {
+			session.submit_reference_value(index as f64);
+			session.add_data_point(reading(index as f64)).unwrap();
+		}
+		session.submit_reference_value(0.0);
+		assert_eq!(error_code(session.next_reference_value()), Some(CalibrationErrorCode::TooManyPoints));
+	}
+
+	#[test]
+	fn models_are_fitted_to_the_captured_points() {
+		let mut line = session(CalibrationModelProtobuf::Linear);
+		assert_eq!(error_code(line.fit_model()), Some(CalibrationErrorCode::NotEnoughPoints));
+		capture(&mut line, &[(0.0, 1.0), (1.0, 3.0), (2.0, 5.0)]);
+		match line.fit_model().unwrap() {
+			CalibrationModel::Linear { scale, offset } => {
+				assert!((scale - 2.0).abs() <= 1e-9);
+				assert!((offset - 1.0).abs() <= 1e-9);
+			}
+			_ => panic!("Expected a linear model"),
+		}
+
+		let mut table = session(CalibrationModelProtobuf::LookupTable);
+		capture(&mut table, &[(1.0, 10.0), (1.0, 12.0)]);
+		assert_eq!(error_code(table.fit_model()), Some(CalibrationErrorCode::NotEnoughPoints));
+
+		let shunt = session(CalibrationModelProtobuf::Shunt);
+		assert_eq!(error_code(shunt.fit_model()), Some(CalibrationErrorCode::UnsupportedModel));
+	}
+
+	#[test]
+	fn fit_is_committed_once_computed_and_accepted() {
+		let mut session = session(CalibrationModelProtobuf::Linear);
+		assert_eq!(error_code(session.fit_to_commit()), Some(CalibrationErrorCode::NoFit));
+
+		// A line fits these points with an R² of 0.2
+		capture(&mut session, &[(0.0, 0.0), (1.0, 2.0), (2.0, 0.0), (3.0, 2.0)]);
+		let model = session.fit_model().unwrap();
+		let points = session.points();
+		let uncertainties = session.uncertainties();
+
+		session.set_fit(model.clone(), &points, &uncertainties, 0.99, LowQualityFitAction::Refuse);
+		assert_eq!(error_code(session.fit_to_commit()), Some(CalibrationErrorCode::LowQualityFit));
+
+		let response = session.set_fit(model, &points, &uncertainties, 0.99, LowQualityFitAction::Warn);
+		match response.response {
+			Some(Response::FitComputed(fit)) => {
+				assert!(fit.accepted);
+				assert_eq!(fit.residuals.len(), 4);
+			}
+			_ => panic!("Expected a FitComputed response"),
+		}
+		assert_eq!(session.fit_to_commit().unwrap().points.len(), 4);
+	}
+
+	#[test]
+	fn new_point_clears_the_fit() {
+		let mut session = session(CalibrationModelProtobuf::Linear);
+		capture(&mut session, &[(0.0, 1.0), (1.0, 3.0), (2.0, 5.0)]);
+		let model = session.fit_model().unwrap();
+		session.set_fit(model, &session.points(), &session.uncertainties(), 0.99, LowQualityFitAction::Refuse);
+		assert!(session.fit_to_commit().is_ok());
+
+		capture(&mut session, &[(3.0, 7.0)]);
+		assert_eq!(error_code(session.fit_to_commit()), Some(CalibrationErrorCode::NoFit));
+	}
+
+	#[test]
+	fn channel_indexes_are_checked() {
+		let (adc, channel) = parse_channel::<StrainChannel>(1, 3).unwrap();
+		assert_eq!(adc, AdcDevice::AdcDevice2);
+		assert_eq!(channel, StrainChannel::Channel4);
+
+		assert_eq!(
+			error_code(parse_channel::<StrainChannel>(-1, 0)),
+			Some(CalibrationErrorCode::InvalidChannel)
+		);
+		assert_eq!(
+			error_code(parse_channel::<StrainChannel>(2, 0)),
+			Some(CalibrationErrorCode::InvalidChannel)
+		);
+		assert_eq!(
+			error_code(parse_channel::<StrainChannel>(0, 4)),
+			Some(CalibrationErrorCode::InvalidChannel)
+		);
+	}
+}
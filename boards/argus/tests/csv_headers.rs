@@ -0,0 +1,60 @@
+#![feature(impl_trait_in_assoc_type)]
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+	// Lines longer than MAX_LINE_LENGTH don't fit in a Line, so get_csv_header() panics on the first write of the file.
+	// The newline appended by SDCardService::write needs a character as well, so every header must be shorter than the limit
+
+	use argus::adc::types::{AdcCalibration, AdcRegisterSnapshot};
+	use argus::board_health::types::BoardHealthReading;
+	use argus::calibration_model::types::{CalibrationRecord, CalibrationRecordTerm, CalibrationResidual, CalibrationTerm, LinearTransformation};
+	use argus::oversampling::types::OversamplingConfiguration;
+	use argus::sd::config::MAX_LINE_LENGTH;
+	use argus::strain::types::{StrainChannel, StrainReading};
+	use argus::tare::types::TareOffset;
+	use defmt_rtt as _;
+	use uor_utils::csv::SerializeCSV;
+
+	fn assert_header_fits<T: SerializeCSV<MAX_LINE_LENGTH>>() {
+		assert!(T::get_csv_header().len() < MAX_LINE_LENGTH);
+	}
+
+	#[test]
+	fn adc_headers_fit_in_a_line() {
+		assert_header_fits::<AdcCalibration>();
+		assert_header_fits::<AdcRegisterSnapshot>();
+		assert_header_fits::<BoardHealthReading>();
+	}
+
+	#[test]
+	fn calibration_headers_fit_in_a_line() {
+		assert_header_fits::<LinearTransformation<StrainChannel, f64>>();
+		assert_header_fits::<CalibrationTerm<StrainChannel, f64>>();
+		assert_header_fits::<CalibrationRecord<StrainChannel, f64>>();
+		assert_header_fits::<CalibrationRecordTerm<f64>>();
+		assert_header_fits::<CalibrationResidual<f64>>();
+		assert_header_fits::<OversamplingConfiguration<StrainChannel>>();
+		assert_header_fits::<TareOffset<StrainChannel>>();
+	}
+
+	#[test]
+	fn strain_reading_header_fits_in_a_line() {
+		assert_header_fits::<StrainReading>();
+	}
+
+	#[cfg(feature = "temperature")]
+	#[test]
+	fn thermocouple_reading_header_fits_in_a_line() {
+		assert_header_fits::<argus::temperature::types::ThermocoupleReading>();
+	}
+
+	#[cfg(feature = "pressure")]
+	#[test]
+	fn pressure_headers_fit_in_a_line() {
+		assert_header_fits::<argus::pressure::types::PressureReading>();
+		assert_header_fits::<argus::pressure::types::TemperatureCompensation>();
+	}
+}
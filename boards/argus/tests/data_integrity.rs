@@ -0,0 +1,87 @@
+#![feature(impl_trait_in_assoc_type)]
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+	// Frames are the four data bytes of a conversion as clocked out after RDATA1, most significant byte first.
+	// The check bytes were worked out by hand from the INTERFACE register description: the checksum is the sum of the data bytes plus 0x9B,
+	// and the CRC is the remainder of the polynomial division by x^8 + x^2 + x + 1 with the register preset to 0xFF
+
+	use argus::adc::driver::types::{DataIntegrityCheck, Status};
+	use defmt_rtt as _;
+
+	const ZERO_CODE: [u8; 4] = [0x00, 0x00, 0x00, 0x00];
+	const POSITIVE_FULL_SCALE_CODE: [u8; 4] = [0x7F, 0xFF, 0xFF, 0xFF];
+	const NEGATIVE_FULL_SCALE_CODE: [u8; 4] = [0x80, 0x00, 0x00, 0x00];
+	const MID_SCALE_CODE: [u8; 4] = [0x12, 0x34, 0x56, 0x78];
+
+	#[test]
+	fn checksum_matches_known_frames() {
+		let check = DataIntegrityCheck::Checksum;
+		assert_eq!(check.compute(&ZERO_CODE), Some(0x9B));
+		assert_eq!(check.compute(&POSITIVE_FULL_SCALE_CODE), Some(0x17));
+		assert_eq!(check.compute(&NEGATIVE_FULL_SCALE_CODE), Some(0x1B));
+		assert_eq!(check.compute(&MID_SCALE_CODE), Some(0xAF));
+	}
+
+	#[test]
+	fn crc_matches_known_frames() {
+		let check = DataIntegrityCheck::Crc;
+		assert_eq!(check.compute(&ZERO_CODE), Some(0xD1));
+		assert_eq!(check.compute(&POSITIVE_FULL_SCALE_CODE), Some(0x3E));
+		assert_eq!(check.compute(&NEGATIVE_FULL_SCALE_CODE), Some(0xE0));
+		assert_eq!(check.compute(&MID_SCALE_CODE), Some(0xCD));
+	}
+
+	#[test]
+	fn single_flipped_bit_is_detected() {
+		let mut corrupted = MID_SCALE_CODE;
+		corrupted[2] ^= 0x04;
+
+		assert_eq!(DataIntegrityCheck::Crc.compute(&corrupted), Some(0x99));
+		assert_eq!(DataIntegrityCheck::Checksum.compute(&corrupted), Some(0xAB));
+
+		// Every single bit error of the frame must change both check bytes
+		for byte in 0..MID_SCALE_CODE.len() {
+			for bit in 0..8 {
+				let mut corrupted = MID_SCALE_CODE;
+				corrupted[byte] ^= 1 << bit;
+				assert_ne!(DataIntegrityCheck::Crc.compute(&corrupted), DataIntegrityCheck::Crc.compute(&MID_SCALE_CODE));
+				assert_ne!(DataIntegrityCheck::Checksum.compute(&corrupted), DataIntegrityCheck::Checksum.compute(&MID_SCALE_CODE));
+			}
+		}
+	}
+
+	#[test]
+	fn disabled_check_computes_nothing() {
+		assert_eq!(DataIntegrityCheck::Disabled.compute(&MID_SCALE_CODE), None);
+	}
+
+	#[test]
+	fn status_byte_decodes_alarms() {
+		// Only the new data flag of ADC1 set, a clean conversion
+		let clean = Status::from(0x40);
+		assert!(clean.adc1_new_data);
+		assert!(!clean.reference_alarm);
+		assert!(!clean.has_pga_alarm());
+		assert!(!clean.reset);
+
+		assert!(Status::from(0x50).reference_alarm);
+		assert!(Status::from(0x01).reset);
+
+		let pga_output_low = Status::from(0x48);
+		assert!(pga_output_low.pga_output_low_alarm);
+		assert!(pga_output_low.has_pga_alarm());
+
+		let pga_output_high = Status::from(0x44);
+		assert!(pga_output_high.pga_output_high_alarm);
+		assert!(pga_output_high.has_pga_alarm());
+
+		let pga_differential_output = Status::from(0x42);
+		assert!(pga_differential_output.pga_differential_output_alarm);
+		assert!(pga_differential_output.has_pga_alarm());
+		assert!(!pga_differential_output.reference_alarm);
+	}
+}
@@ -0,0 +1,63 @@
+#![feature(impl_trait_in_assoc_type)]
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+	// Resistances of a 10 kΩ, B = 3950 K thermistor from R(T) = R25 * exp(B * (1/T - 1/T25)), the equation the B value is defined by
+
+	use argus::pressure::config::NTC_CONFIGURATION;
+	use argus::pressure::ntc::{convert_resistance_to_temperature_beta, convert_resistance_to_temperature_steinhart_hart};
+	use defmt_rtt as _;
+	use libm::logf;
+
+	const RESISTANCE_AT_25C: f32 = 10_000.0;
+	const BETA: f32 = 3950.0;
+	const TOLERANCE: f32 = 0.01; // degrees Celsius
+
+	// (temperature in °C, resistance in ohms)
+	const REFERENCE_POINTS: [(f32, f32); 4] = [(0.0, 33_620.6), (25.0, 10_000.0), (50.0, 3_588.18), (100.0, 697.52)];
+
+	fn assert_close(
+		actual: f32,
+		expected: f32,
+	) {
+		assert!((actual - expected).abs() <= TOLERANCE);
+	}
+
+	#[test]
+	fn beta_model_inverts_the_beta_equation() {
+		for (temperature, resistance) in REFERENCE_POINTS {
+			assert_close(convert_resistance_to_temperature_beta(RESISTANCE_AT_25C, BETA, resistance), temperature);
+		}
+	}
+
+	#[test]
+	fn steinhart_hart_without_its_cubic_term_matches_the_beta_model() {
+		// The Beta equation is the Steinhart-Hart equation with A = 1/T25 - ln(R25)/B, B = 1/B and C = 0
+		let a = 1.0 / 298.15 - logf(RESISTANCE_AT_25C) / BETA;
+		let b = 1.0 / BETA;
+		for (temperature, resistance) in REFERENCE_POINTS {
+			assert_close(convert_resistance_to_temperature_steinhart_hart(a, b, 0.0, resistance), temperature);
+		}
+	}
+
+	#[test]
+	fn steinhart_hart_cubic_term_lowers_the_temperature() {
+		let a = 1.0 / 298.15 - logf(RESISTANCE_AT_25C) / BETA;
+		let b = 1.0 / BETA;
+		let temperature = convert_resistance_to_temperature_steinhart_hart(a, b, 1e-7, RESISTANCE_AT_25C);
+		assert!(temperature < 25.0 - TOLERANCE);
+	}
+
+	#[test]
+	fn manifold_ntc_configuration_converts_the_divider_ratio() {
+		// The NTC and its series resistor carry the same current, so their voltage ratio is their resistance ratio
+		for (temperature, resistance) in REFERENCE_POINTS {
+			let ratio = resistance / NTC_CONFIGURATION.reference_resistance;
+			let measured_resistance = NTC_CONFIGURATION.compute_resistance(ratio);
+			assert_close(NTC_CONFIGURATION.convert_resistance_to_temperature(measured_resistance), temperature);
+		}
+	}
+}
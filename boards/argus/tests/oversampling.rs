@@ -0,0 +1,45 @@
+#![feature(impl_trait_in_assoc_type)]
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+	use argus::oversampling::types::SampleStatistics;
+	use defmt_rtt as _;
+
+	// Eight conversions around 1 V with a single 5 V spike
+	const SPIKED_SAMPLES: [f32; 8] = [1.0, 1.01, 0.99, 1.0, 5.0, 1.02, 0.98, 1.0];
+
+	fn assert_close(
+		actual: f32,
+		expected: f32,
+		tolerance: f32,
+	) {
+		assert!((actual - expected).abs() <= tolerance);
+	}
+
+	#[test]
+	fn single_spike_is_rejected() {
+		let statistics = SampleStatistics::from_samples(&SPIKED_SAMPLES, 3.0);
+		assert_eq!(statistics.sample_count, 7);
+		assert_close(statistics.mean, 1.0, 1e-5);
+		assert_close(statistics.max, 1.02, 1e-5);
+	}
+
+	#[test]
+	fn single_spike_among_equal_samples_is_rejected() {
+		let statistics = SampleStatistics::from_samples(&[1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0, 5.0], 3.0);
+		assert_eq!(statistics.sample_count, 7);
+		assert_eq!(statistics.mean, 1.0);
+		assert_eq!(statistics.standard_deviation, 0.0);
+	}
+
+	#[test]
+	fn spike_is_kept_without_a_threshold() {
+		let statistics = SampleStatistics::from_samples(&SPIKED_SAMPLES, 0.0);
+		assert_eq!(statistics.sample_count, 8);
+		assert_close(statistics.mean, 1.5, 1e-5);
+		assert_eq!(statistics.max, 5.0);
+	}
+}
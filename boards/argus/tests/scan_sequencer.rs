@@ -0,0 +1,96 @@
+#![feature(impl_trait_in_assoc_type)]
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+	use argus::adc::types::AdcDevice;
+	use argus::scan_sequencer::service::ScanSequencer;
+	use argus::scan_sequencer::types::{scan_every_channel, ScanEntry};
+	use argus::strain::types::StrainChannel;
+	use defmt_rtt as _;
+	use embassy_time::Instant;
+
+	const ADC_COUNT: usize = 2;
+
+	#[test]
+	fn every_channel_is_read_on_both_adcs_on_the_first_scan() {
+		let scan_list: [ScanEntry<StrainChannel>; 8] = scan_every_channel([
+			StrainChannel::Channel1,
+			StrainChannel::Channel2,
+			StrainChannel::Channel3,
+			StrainChannel::Channel4,
+		]);
+		let mut sequencer: ScanSequencer<StrainChannel, ADC_COUNT, 8> = ScanSequencer::new(scan_list);
+		assert_eq!(sequencer.next_due_at(), Instant::from_millis(0));
+
+		let steps = sequencer.take_due(0);
+		assert_eq!(steps.len(), 4);
+		for (step, channel) in steps.iter().zip([
+			StrainChannel::Channel1,
+			StrainChannel::Channel2,
+			StrainChannel::Channel3,
+			StrainChannel::Channel4,
+		]) {
+			assert_eq!(step.channel, channel);
+			assert_eq!(step.adcs, [true, true]);
+		}
+		assert!(steps[0].starts_cycle);
+		assert!(steps[1..].iter().all(|step| !step.starts_cycle));
+	}
+
+	#[test]
+	fn entries_are_due_at_their_own_interval() {
+		let mut sequencer: ScanSequencer<StrainChannel, ADC_COUNT, 2> = ScanSequencer::new([
+			ScanEntry::new(AdcDevice::AdcDevice1, StrainChannel::Channel1, 100),
+			ScanEntry::new(AdcDevice::AdcDevice1, StrainChannel::Channel2, 250),
+		]);
+		assert_eq!(sequencer.take_due(0).len(), 2);
+		assert_eq!(sequencer.next_due_at(), Instant::from_millis(100));
+		assert!(sequencer.take_due(50).is_empty());
+
+		let steps = sequencer.take_due(100);
+		assert_eq!(steps.len(), 1);
+		assert_eq!(steps[0].channel, StrainChannel::Channel1);
+		assert!(steps[0].starts_cycle);
+		assert_eq!(sequencer.next_due_at(), Instant::from_millis(200));
+
+		// The first entry was due at 200 and the second at 250
+		assert_eq!(sequencer.take_due(250).len(), 2);
+		assert_eq!(sequencer.next_due_at(), Instant::from_millis(300));
+	}
+
+	#[test]
+	fn missed_samples_are_skipped() {
+		let mut sequencer: ScanSequencer<StrainChannel, ADC_COUNT, 1> =
+			ScanSequencer::new([ScanEntry::new(AdcDevice::AdcDevice1, StrainChannel::Channel1, 100)]);
+		sequencer.take_due(0);
+
+		// Samples at 100 to 400 were missed, the next one is an interval after the late one rather than at 200
+		assert_eq!(sequencer.take_due(450).len(), 1);
+		assert_eq!(sequencer.next_due_at(), Instant::from_millis(550));
+	}
+
+	#[test]
+	fn entries_of_the_same_channel_are_merged() {
+		let mut sequencer: ScanSequencer<StrainChannel, ADC_COUNT, 3> = ScanSequencer::new([
+			ScanEntry::new(AdcDevice::AdcDevice2, StrainChannel::Channel1, 0),
+			ScanEntry::new(AdcDevice::AdcDevice1, StrainChannel::Channel2, 0),
+			ScanEntry::new(AdcDevice::AdcDevice1, StrainChannel::Channel1, 0),
+		]);
+		let steps = sequencer.take_due(0);
+		assert_eq!(steps.len(), 2);
+		assert_eq!(steps[0].channel, StrainChannel::Channel1);
+		assert_eq!(steps[0].adcs, [true, true]);
+		assert!(steps[0].starts_cycle);
+		assert_eq!(steps[1].channel, StrainChannel::Channel2);
+		assert_eq!(steps[1].adcs, [true, false]);
+	}
+
+	#[test]
+	fn empty_scan_list_is_never_due() {
+		let sequencer: ScanSequencer<StrainChannel, ADC_COUNT, 0> = ScanSequencer::new([]);
+		assert_eq!(sequencer.next_due_at(), Instant::MAX);
+	}
+}
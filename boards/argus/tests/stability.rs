@@ -0,0 +1,95 @@
+#![feature(impl_trait_in_assoc_type)]
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+	use argus::calibration_model::stability::StabilityWindow;
+	use argus::calibration_model::types::StabilityCriteria;
+	use defmt_rtt as _;
+
+	const CRITERIA: StabilityCriteria = StabilityCriteria {
+		max_standard_deviation: 0.1,
+		max_drift: 0.05,
+		reading_interval: 100,
+		timeout: 10_000,
+	};
+
+	fn assert_close(
+		actual: f64,
+		expected: f64,
+		tolerance: f64,
+	) {
+		assert!((actual - expected).abs() <= tolerance);
+	}
+
+	fn window_of<const WINDOW_SIZE: usize>(readings: &[f64]) -> StabilityWindow<WINDOW_SIZE> {
+		let mut window = StabilityWindow::default();
+		for reading in readings {
+			window.push(*reading);
+		}
+		window
+	}
+
+	#[test]
+	fn standard_deviation_is_the_sample_standard_deviation() {
+		// Mean 5, sum of squares 32 over 8 - 1 readings
+		let window: StabilityWindow<8> = window_of(&[2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0]);
+		assert_close(window.mean(), 5.0, 1e-12);
+		assert_close(window.standard_deviation(), 2.138_089_935_299_395, 1e-12);
+
+		let single: StabilityWindow<8> = window_of(&[3.0]);
+		assert_eq!(single.standard_deviation(), 0.0);
+	}
+
+	#[test]
+	fn drift_follows_a_ramp() {
+		// Newest half averages 6.5 and oldest half 2.5
+		let window: StabilityWindow<8> = window_of(&[1.0, 2.0, 3.0, 4.0, 5.0, 6.0, 7.0, 8.0]);
+		assert_close(window.drift(), 4.0, 1e-12);
+
+		let falling: StabilityWindow<4> = window_of(&[4.0, 3.0, 2.0, 1.0]);
+		assert_close(falling.drift(), -2.0, 1e-12);
+	}
+
+	#[test]
+	fn oldest_reading_is_replaced_once_full() {
+		let window: StabilityWindow<4> = window_of(&[100.0, 1.0, 1.0, 1.0, 1.0]);
+		assert!(window.is_full());
+		assert_close(window.mean(), 1.0, 1e-12);
+		assert_eq!(window.standard_deviation(), 0.0);
+	}
+
+	#[test]
+	fn window_is_not_stable_until_full() {
+		let mut window: StabilityWindow<4> = window_of(&[1.0, 1.0, 1.0]);
+		assert!(!window.is_full());
+		assert!(!window.is_stable(&CRITERIA));
+
+		window.push(1.0);
+		assert!(window.is_stable(&CRITERIA));
+	}
+
+	#[test]
+	fn noisy_or_drifting_window_is_not_stable() {
+		let noisy: StabilityWindow<4> = window_of(&[1.0, 1.5, 1.0, 1.5]);
+		assert!(noisy.standard_deviation() > CRITERIA.max_standard_deviation);
+		assert!(!noisy.is_stable(&CRITERIA));
+
+		// Quiet enough, but still moving by 0.08 between the halves
+		let drifting: StabilityWindow<4> = window_of(&[1.00, 1.02, 1.08, 1.10]);
+		assert!(drifting.standard_deviation() <= CRITERIA.max_standard_deviation);
+		assert!(!drifting.is_stable(&CRITERIA));
+	}
+
+	#[test]
+	fn stable_reading_uncertainty_is_the_standard_error() {
+		let window: StabilityWindow<4> = window_of(&[0.9, 1.1, 0.9, 1.1]);
+		let reading = window.to_stable_reading();
+		assert_close(reading.mean, 1.0, 1e-12);
+		assert_close(reading.standard_deviation, window.standard_deviation(), 1e-12);
+		assert_close(reading.uncertainty, reading.standard_deviation / 2.0, 1e-12);
+		assert_eq!(reading.reading_count, 4);
+	}
+}
@@ -0,0 +1,97 @@
+#![feature(impl_trait_in_assoc_type)]
+#![no_std]
+#![no_main]
+
+#[cfg(test)]
+#[embedded_test::tests]
+mod tests {
+	// Reference values are from the NIST ITS-90 thermocouple tables, which round E(t) to the microvolt
+	// The inverse functions approximate the reference functions within a few hundredths of a degree over their ranges
+
+	use argus::temperature::thermocouple::ThermocoupleError;
+	use argus::temperature::types::ThermocoupleType;
+	use defmt_rtt as _;
+
+	const VOLTAGE_TOLERANCE: f64 = 0.001; // millivolts
+	const TEMPERATURE_TOLERANCE: f64 = 0.1; // degrees Celsius
+
+	// (type, temperature in °C, E(t) in mV) from the NIST tables
+	const REFERENCE_POINTS: [(ThermocoupleType, f64, f64); 16] = [
+		(ThermocoupleType::B, 500.0, 1.242),
+		(ThermocoupleType::B, 1000.0, 4.834),
+		(ThermocoupleType::E, -100.0, -5.237),
+		(ThermocoupleType::E, 500.0, 37.005),
+		(ThermocoupleType::J, -100.0, -4.633),
+		(ThermocoupleType::J, 500.0, 27.393),
+		(ThermocoupleType::K, -100.0, -3.554),
+		(ThermocoupleType::K, 1000.0, 41.276),
+		(ThermocoupleType::N, 100.0, 2.774),
+		(ThermocoupleType::N, 1000.0, 36.256),
+		(ThermocoupleType::R, 100.0, 0.647),
+		(ThermocoupleType::R, 1000.0, 10.506),
+		(ThermocoupleType::S, 100.0, 0.646),
+		(ThermocoupleType::S, 1000.0, 9.587),
+		(ThermocoupleType::T, -100.0, -3.379),
+		(ThermocoupleType::T, 300.0, 14.862),
+	];
+
+	fn assert_close(
+		actual: f64,
+		expected: f64,
+		tolerance: f64,
+	) {
+		assert!((actual - expected).abs() <= tolerance);
+	}
+
+	#[test]
+	fn voltages_match_the_reference_tables() {
+		for (thermocouple_type, temperature, voltage) in REFERENCE_POINTS {
+			assert_close(
+				thermocouple_type.convert_temperature_to_voltage(temperature).unwrap(),
+				voltage,
+				VOLTAGE_TOLERANCE,
+			);
+		}
+	}
+
+	#[test]
+	fn temperatures_match_the_reference_tables() {
+		for (thermocouple_type, temperature, voltage) in REFERENCE_POINTS {
+			assert_close(
+				thermocouple_type.convert_voltage_to_temperature(voltage).unwrap(),
+				temperature,
+				TEMPERATURE_TOLERANCE,
+			);
+		}
+	}
+
+	#[test]
+	fn reference_temperature_gives_zero_volts() {
+		for (thermocouple_type, _, _) in REFERENCE_POINTS {
+			assert_close(thermocouple_type.convert_temperature_to_voltage(0.0).unwrap(), 0.0, VOLTAGE_TOLERANCE);
+		}
+	}
+
+	#[test]
+	fn cold_junction_voltage_is_added_before_converting() {
+		// K: E(25 °C) = 1.000 mV, so a 100 °C junction measured against a 25 °C cold junction reads 4.096 - 1.000 mV
+		let temperature = ThermocoupleType::K
+			.convert_voltage_to_temperature_with_cold_junction_compensation(3.096, 25.0)
+			.unwrap();
+		assert_close(temperature, 100.0, TEMPERATURE_TOLERANCE);
+	}
+
+	#[test]
+	fn out_of_range_values_are_rejected() {
+		assert_eq!(ThermocoupleType::J.convert_temperature_to_voltage(1300.0), None);
+		assert_eq!(ThermocoupleType::T.convert_temperature_to_voltage(-300.0), None);
+		assert_eq!(
+			ThermocoupleType::K.convert_voltage_to_temperature(100.0),
+			Err(ThermocoupleError::MillivoltsOutOfRange)
+		);
+		assert_eq!(
+			ThermocoupleType::K.convert_voltage_to_temperature_with_cold_junction_compensation(1.0, 2000.0),
+			Err(ThermocoupleError::ColdJunctionTemperatureOutOfRange)
+		);
+	}
+}
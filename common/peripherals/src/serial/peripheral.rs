@@ -1,5 +1,3 @@
-use core::mem::swap;
-
 use defmt::info;
 use embassy_stm32::Peripheral;
 use embassy_stm32::interrupt::typelevel::Binding;
@@ -9,15 +7,29 @@ use embassy_stm32::usart::{Config, Instance, InterruptHandler, RxDma, RxPin, TxD
 use embassy_stm32::usart::{ConfigError, UartRx, UartTx};
 use embassy_time::Timer;
 use embedded_io_async::{ErrorType, Read, Write};
-use heapless::String;
+use heapless::{String, Vec};
 #[cfg(feature = "messages")]
 use prost::Message;
 #[cfg(feature = "messages")]
 use uor_utils::messages::argus::envelope::{Envelope, Node, envelope::Message as EnvelopeMessage};
 
+// Longest envelope accepted by read_envelope_message, including its length delimiter
+#[cfg(feature = "messages")]
+const MAX_ENVELOPE_FRAME_LENGTH: usize = 256;
+
+// Size of the chunks the envelope frames are received in
+#[cfg(feature = "messages")]
+const ENVELOPE_READ_CHUNK_LENGTH: usize = 32;
+
+// Longest length delimiter, a varint of a u64
+#[cfg(feature = "messages")]
+const MAX_LENGTH_DELIMITER_LENGTH: usize = 10;
+
 pub struct UORSerial {
 	pub tx_component: UORSerialTx,
-	pub rx_component: UORSerialRx,
+
+	// None once taken by take_rx_component, the input is then only read through the taken component
+	pub rx_component: Option<UORSerialRx>,
 }
 
 impl UORSerial {
@@ -35,10 +47,16 @@ impl UORSerial {
 
 		Ok(Self {
 			tx_component: UORSerialTx { component: tx_component },
-			rx_component: UORSerialRx { component: rx_component },
+			rx_component: Some(UORSerialRx::new(rx_component)),
 		})
 	}
 
+	/// Takes the input of the serial port, so a task of its own can wait for input without holding the serial port while the others write.
+	/// The read functions of the serial port panic once it is taken.
+	pub fn take_rx_component(&mut self) -> Option<UORSerialRx> {
+		self.rx_component.take()
+	}
+
 	/// Write the full buffer, waiting until all bytes are sent.
 	pub async fn write_all(
 		&mut self,
@@ -85,7 +103,7 @@ impl UORSerial {
 		let mut bytes = [0u8; 32];
 
 		'chunking_loop: loop {
-			let bytes_size = self.rx_component.component.read_until_idle(&mut bytes).await?;
+			let bytes_size = self.rx_component().component.read_until_idle(&mut bytes).await?;
 			for byte in &bytes[..bytes_size] {
 				if count >= N {
 					// Buffer full: stop reading and return what we have.
@@ -116,7 +134,7 @@ impl UORSerial {
 		&mut self,
 		buff: &mut [u8],
 	) -> Result<usize, UsartError> {
-		match self.rx_component.component.read_until_idle(buff).await {
+		match self.rx_component().component.read_until_idle(buff).await {
 			Ok(len) => Ok(len),
 			Err(error) => Err(error),
 		}
@@ -126,11 +144,15 @@ impl UORSerial {
 		&mut self,
 		buf: &mut [u8],
 	) {
-		self.rx_component.component.read_until_idle(buf).await;
+		self.rx_component().component.read_until_idle(buf).await;
 	}
 
 	pub fn split(self) -> (UORSerialTx, UORSerialRx) {
-		return (self.tx_component, self.rx_component);
+		return (self.tx_component, self.rx_component.expect("Serial input already taken"));
+	}
+
+	fn rx_component(&mut self) -> &mut UORSerialRx {
+		self.rx_component.as_mut().expect("Serial input already taken")
 	}
 }
 
@@ -152,7 +174,92 @@ impl Write for UORSerialTx {
 
 pub struct UORSerialRx {
 	pub component: UartRx<'static, Async>,
+
+	// Bytes received after the end of the last envelope frame, the start of the next frame
+	// Holds an incomplete frame and the chunk being received after it
+	#[cfg(feature = "messages")]
+	envelope_buffer: Vec<u8, { MAX_ENVELOPE_FRAME_LENGTH + ENVELOPE_READ_CHUNK_LENGTH }>,
+
+	// Bytes of an envelope frame longer than MAX_ENVELOPE_FRAME_LENGTH still to be dropped as they're received
+	#[cfg(feature = "messages")]
+	envelope_bytes_to_discard: usize,
+}
+
+impl UORSerialRx {
+	fn new(component: UartRx<'static, Async>) -> Self {
+		Self {
+			component,
+			#[cfg(feature = "messages")]
+			envelope_buffer: Vec::new(),
+			#[cfg(feature = "messages")]
+			envelope_bytes_to_discard: 0,
+		}
+	}
+
+	/// Read a single length-delimited envelope, as written by `write_envelope_message`.
+	/// Bytes received past the end of the frame are kept as the start of the next frame.
+	/// Returns None if the frame is too long or doesn't decode to an envelope with a message. A frame that is too long is rejected from its
+	/// length delimiter and exactly its bytes are dropped, so the next frame is still read from its own delimiter.
+	#[cfg(feature = "messages")]
+	pub async fn read_envelope_message(&mut self) -> Result<Option<EnvelopeMessage>, UsartError> {
+		let mut bytes = [0u8; ENVELOPE_READ_CHUNK_LENGTH];
+
+		loop {
+			if self.envelope_bytes_to_discard > 0 {
+				let discarded = self.envelope_bytes_to_discard.min(self.envelope_buffer.len());
+				self.consume_envelope_buffer(discarded);
+				self.envelope_bytes_to_discard -= discarded;
+				if self.envelope_bytes_to_discard == 0 {
+					return Ok(None);
+				}
+			} else {
+				match prost::decode_length_delimiter(self.envelope_buffer.as_slice()) {
+					Ok(length) => {
+						let frame_length = prost::length_delimiter_len(length) + length;
+						if frame_length > MAX_ENVELOPE_FRAME_LENGTH {
+							info!(
+								"Envelope frame of {} bytes is longer than {} bytes, dropping it",
+								frame_length, MAX_ENVELOPE_FRAME_LENGTH
+							);
+							self.envelope_bytes_to_discard = frame_length;
+							continue;
+						}
+
+						if self.envelope_buffer.len() >= frame_length {
+							let envelope = Envelope::decode_length_delimited(&self.envelope_buffer[..frame_length]);
+							self.consume_envelope_buffer(frame_length);
+							return Ok(envelope.ok().and_then(|envelope| envelope.message));
+						}
+					}
+					// Nothing tells where the next frame starts after an invalid delimiter, so everything received so far is dropped
+					Err(_) if self.envelope_buffer.len() >= MAX_LENGTH_DELIMITER_LENGTH => {
+						info!("Invalid envelope length delimiter, dropping {} bytes", self.envelope_buffer.len());
+						self.envelope_buffer.clear();
+						return Ok(None);
+					}
+					// The delimiter can't be decoded until all of its bytes have been received
+					Err(_) => {}
+				}
+			}
+
+			// Safe as the buffer holds less than a frame before each chunk, see envelope_buffer
+			let bytes_size = self.component.read_until_idle(&mut bytes).await?;
+			self.envelope_buffer.extend_from_slice(&bytes[..bytes_size]).unwrap();
+		}
+	}
+
+	// Removes the first bytes of the envelope buffer, keeping the bytes received after them
+	#[cfg(feature = "messages")]
+	fn consume_envelope_buffer(
+		&mut self,
+		length: usize,
+	) {
+		let remaining_length = self.envelope_buffer.len() - length;
+		self.envelope_buffer.copy_within(length.., 0);
+		self.envelope_buffer.truncate(remaining_length);
+	}
 }
+
 impl ErrorType for UORSerialRx {
 	type Error = embassy_stm32::usart::Error;
 }
@@ -0,0 +1 @@
+include!(concat!(env!("OUT_DIR"), "/messages.argus.board_health.board_health_reading.rs"));
@@ -0,0 +1 @@
+include!(concat!(env!("OUT_DIR"), "/messages.argus.calibration.calibration_command.rs"));
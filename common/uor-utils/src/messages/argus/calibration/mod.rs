@@ -0,0 +1,3 @@
+pub mod calibration_command;
+pub mod calibration_model;
+pub mod calibration_response;
@@ -1,6 +1,8 @@
 // TODO: Investigate why the compiler complains about the following modules not having the OUT_DIR environmental variable set
 
 pub mod adc;
+pub mod board_health;
+pub mod calibration;
 pub mod envelope;
 pub mod pressure;
 pub mod strain;
@@ -1,2 +1,3 @@
 pub mod thermocouple_channel;
 pub mod thermocouple_reading;
+pub mod thermocouple_type;
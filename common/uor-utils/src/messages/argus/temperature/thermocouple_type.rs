@@ -0,0 +1 @@
+include!(concat!(env!("OUT_DIR"), "/messages.argus.temperature.thermocouple_type.rs"));
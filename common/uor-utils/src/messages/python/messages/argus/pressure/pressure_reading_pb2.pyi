@@ -36,7 +36,7 @@ class PressureReading(google.protobuf.message.Message):
     pressure: builtins.float
     """Pressure reading in psi"""
     temperature: builtins.float
-    """Temperature of the manifold from the NTC resistor at the time of the recording in degrees Celsius"""
+    """Temperature of the manifold from the NTC resistor at the time of the recording in degrees Celsius, NaN when it isn't available"""
     def __init__(
         self,
         *,